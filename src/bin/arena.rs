@@ -0,0 +1,44 @@
+use std::env;
+use std::process;
+
+use rust_poker::poker::arena::{round_robin, Entrant, Match};
+use rust_poker::poker::bot::{CallingStation, HandStrength, RandomBot, TightAggressive};
+
+/// Plays the built-in bots against each other: `arena [hands] [seed] [--duplicate]`.
+fn main() {
+    let mut settings = Match::new(1000, 0);
+    let mut numbers = Vec::new();
+    for arg in env::args().skip(1) {
+        if arg == "--duplicate" {
+            settings.duplicate = true;
+        } else {
+            numbers.push(arg.parse::<u64>().unwrap_or_else(|_| {
+                eprintln!("Usage: arena [hands] [seed] [--duplicate]");
+                process::exit(1);
+            }));
+        }
+    }
+    if let Some(hands) = numbers.first() {
+        settings.hands = *hands as usize;
+    }
+    if let Some(seed) = numbers.get(1) {
+        settings.seed = *seed;
+    }
+
+    let entrants = [
+        Entrant::new("Random", |seed| Box::new(RandomBot::new(seed))),
+        Entrant::new("Calling station", |_| Box::new(CallingStation)),
+        Entrant::new("Tight aggressive", |seed| {
+            Box::new(TightAggressive::new(seed))
+        }),
+        Entrant::new("Hand strength", |seed| {
+            Box::new(HandStrength::new(100, seed))
+        }),
+    ];
+    let (results, leaderboard) = round_robin(&entrants, &settings);
+    for result in results.iter() {
+        println!("{}", result);
+    }
+    println!();
+    print!("{}", leaderboard);
+}
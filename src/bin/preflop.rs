@@ -0,0 +1,27 @@
+use std::env;
+use std::io::{self, Write};
+use std::thread;
+
+use rust_poker::poker::preflop::PreflopTable;
+
+/// Generates the preflop equity table: `preflop [path] [threads]`.
+fn main() {
+    let mut args = env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| String::from("preflop.bin"));
+    let threads = args
+        .next()
+        .and_then(|threads| threads.parse().ok())
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()));
+
+    println!("Computing preflop equities on {} threads.", threads);
+    let table = PreflopTable::generate_with(threads, |done, total| {
+        if done % 100 == 0 || done == total {
+            print!("\r{} of {} matchups", done, total);
+            io::stdout().flush().expect("Failed to flush stdout.");
+        }
+    });
+    println!();
+
+    table.save(&path).expect("Failed to save the table.");
+    println!("Saved to {}.", path);
+}
@@ -0,0 +1,54 @@
+use std::env;
+use std::process;
+use std::thread;
+
+use rust_poker::poker::preflop::PreflopTable;
+use rust_poker::poker::push_fold::PushFold;
+
+/// Prints heads-up push/fold ranges: `push_fold <stack> [ante] [table]`,
+/// with the stack and the ante in big blinds. Without a table file made by
+/// the `preflop` tool the equities are estimated from random boards.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let number = |index: usize, default: Option<f64>| {
+        args.get(index)
+            .map(|arg| arg.parse::<f64>().ok())
+            .unwrap_or(default)
+            .unwrap_or_else(|| {
+                eprintln!("Usage: push_fold <stack> [ante] [table]");
+                process::exit(1);
+            })
+    };
+    let game = PushFold {
+        ante: number(1, Some(0.0)),
+        ..PushFold::in_big_blinds(number(0, None))
+    };
+
+    let table = match args.get(2) {
+        Some(path) => PreflopTable::load(path).expect("Failed to load the table."),
+        None => {
+            println!("Estimating preflop equities, pass a table for exact ones.");
+            let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+            PreflopTable::estimate(2000, threads, 0)
+        }
+    };
+
+    let solution = game.solve(&table, 2000);
+    println!();
+    println!(
+        "Push {:.1}%: {}",
+        solution.push.share() * 100.0,
+        solution.push
+    );
+    println!("{}", solution.push.grid());
+    println!(
+        "Call {:.1}%: {}",
+        solution.call.share() * 100.0,
+        solution.call
+    );
+    println!("{}", solution.call.grid());
+    println!(
+        "Small blind wins {:.3} big blinds per hand.",
+        solution.value
+    );
+}
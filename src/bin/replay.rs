@@ -0,0 +1,77 @@
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use rust_poker::poker::import;
+use rust_poker::poker::render::{set_style, Style};
+use rust_poker::poker::replay::Replayer;
+
+const HELP: &str = "Enter or n: next, b: back, s: start, e: end, a number: go to the step, \
+                    ]: next hand, [: previous hand, q: quit.";
+
+/// Steps through the hands of a history file: `replay <file> [hand number] [--ascii|--ansi]`.
+fn main() {
+    let mut path = None;
+    let mut id = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--ascii" => set_style(Style::Ascii),
+            "--ansi" => set_style(Style::Ansi),
+            _ if path.is_none() => path = Some(arg),
+            _ => id = arg.parse::<u64>().ok(),
+        }
+    }
+    let path = path.unwrap_or_else(|| {
+        eprintln!("Usage: replay <file> [hand number] [--ascii|--ansi]");
+        process::exit(1);
+    });
+    let hands = import::load(&path).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+    if hands.is_empty() {
+        eprintln!("There are no hands in {}.", path);
+        process::exit(1);
+    }
+
+    let mut current = id
+        .and_then(|id| hands.iter().position(|hand| hand.id == id))
+        .unwrap_or(0);
+    let mut replayer = Replayer::new(hands[current].clone());
+    println!("{}", HELP);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        println!();
+        print!("{}", replayer);
+        print!("> ");
+        io::stdout().flush().expect("Failed to flush stdout.");
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        match line.trim() {
+            "" | "n" => {
+                replayer.forward();
+            }
+            "b" => {
+                replayer.back();
+            }
+            "s" => replayer.seek(0),
+            "e" => replayer.seek(replayer.steps()),
+            "]" | "[" => {
+                current = if line.trim() == "]" {
+                    (current + 1).min(hands.len() - 1)
+                } else {
+                    current.saturating_sub(1)
+                };
+                replayer = Replayer::new(hands[current].clone());
+            }
+            "q" => break,
+            other => match other.parse() {
+                Ok(step) => replayer.seek(step),
+                Err(_) => println!("{}", HELP),
+            },
+        }
+    }
+}
@@ -1,61 +1,174 @@
-use std::thread::sleep;
-use std::time::Duration;
+use std::env;
+use std::io::{self, BufRead, Write};
 
-use crate::poker::card::Hand;
-use poker::card::Card;
-use poker::deck::Deck;
-use poker::rules::get_rules;
-
-mod poker;
+use rust_poker::poker::card::{Card, Hand};
+use rust_poker::poker::describe::{describe, short};
+use rust_poker::poker::render::{set_style, Style};
+use rust_poker::poker::video::{Machine, Paytable};
+use rust_poker::poker::video_strategy::Calculator;
 
 fn main() {
-    loop {
-        let mut deck = Deck::create();
-        deck.shuffle();
+    let mut paytable = Paytable::jacks_or_better();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "bonus" => paytable = Paytable::bonus_poker(),
+            "deuces" => paytable = Paytable::deuces_wild(),
+            "--ascii" => set_style(Style::Ascii),
+            "--ansi" => set_style(Style::Ansi),
+            "--cards" => set_style(Style::PlayingCards),
+            _ => {}
+        }
+    }
+
+    println!("{}", paytable.name);
+    for entry in &paytable.entries {
+        println!("{:>24} {:>4}", entry.hand, entry.pays);
+    }
+    println!();
 
-        let mut hand: Hand = [Card::new(); 5];
+    let mut machine = Machine::new(paytable, 100);
+    let mut calculator: Option<Calculator> = None;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
 
-        for i in 0..5 {
-            match deck.deal() {
-                Some(card) => hand[i] = card,
-                None => panic!("Not enough cards to deal."),
-            }
+    loop {
+        if machine.credits == 0 {
+            println!("Out of credits.");
+            break;
         }
 
-        for card in &hand {
-            print!("{} ", card);
+        print!(
+            "Credits: {}. Bet 1-{} or q to quit: ",
+            machine.credits, machine.paytable.max_bet
+        );
+        io::stdout().flush().expect("Failed to flush stdout.");
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        let line = line.trim();
+        if line == "q" {
+            break;
         }
-        println!("");
 
-        let rules = get_rules();
-        for rule in rules {
-            match rule(&hand) {
-                Some(combination) => {
-                    println!("{}", combination);
-                    break;
-                }
-                None => {
-                    continue;
-                }
+        let bet = match line.parse() {
+            Ok(bet) => bet,
+            Err(_) => {
+                println!("Enter a number.");
+                continue;
+            }
+        };
+        let hand = match machine.deal(bet) {
+            Ok(hand) => hand,
+            Err(error) => {
+                println!("{}", error);
+                continue;
+            }
+        };
+
+        print_hand(&hand);
+        let line = loop {
+            print!("Cards to hold, e.g. 145, or ? for a hint: ");
+            io::stdout().flush().expect("Failed to flush stdout.");
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                _ => return,
+            };
+            if line.trim() != "?" {
+                break line;
+            }
+
+            let calculator =
+                calculator.get_or_insert_with(|| Calculator::new(machine.paytable.clone()));
+            let hold = calculator.best_hold(&hand);
+            let cards: Vec<String> = hold.cards(&hand).iter().map(Card::to_string).collect();
+            println!(
+                "Hold [{}], expected return {:.4}.",
+                cards.join(" "),
+                hold.expected
+            );
+        };
+
+        let mut held = [false; 5];
+        for position in line.chars().filter_map(|c| c.to_digit(10)) {
+            if (1..=5).contains(&position) {
+                held[position as usize - 1] = true;
             }
         }
 
-        println!("");
-        sleep(Duration::from_secs(1))
+        let outcome = machine.draw(held).expect("Hand was dealt.");
+        print_hand(&outcome.hand);
+        println!("{} ({})", describe(&outcome.hand), short(&outcome.hand));
+        match outcome.entry {
+            Some(entry) => println!("{}! Won {}.", entry.hand, outcome.won),
+            None => println!("No win."),
+        }
+        println!();
     }
 }
 
+fn print_hand(hand: &Hand) {
+    let cards: Vec<String> = hand.iter().map(Card::to_string).collect();
+    println!("{}", cards.join(" "));
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::poker::card::Card;
-    use crate::poker::card::Hand;
-    use crate::poker::card::Quantity;
-    use crate::poker::card::Suit;
-    use crate::poker::rules::check_duplicates;
-    use crate::poker::rules::check_flush;
-    use crate::poker::rules::check_flush_royal;
-    use crate::poker::rules::check_straight;
-    use crate::poker::rules::Combination;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+    use rust_poker::poker::arena::{round_robin, Entrant, Leaderboard, Match, MatchResult};
+    use rust_poker::poker::board::{Pairing, Street, Suits, Texture};
+    use rust_poker::poker::bot::{
+        self, CallingStation, HandStrength, RandomBot, Strategy, TightAggressive, View,
+    };
+    use rust_poker::poker::card::Card;
+    use rust_poker::poker::card::Hand;
+    use rust_poker::poker::card::Quantity;
+    use rust_poker::poker::card::Suit;
+    use rust_poker::poker::cfr::{expected_values, exploitability, GameTree, Profile, Solver};
+    use rust_poker::poker::deck::Deck;
+    use rust_poker::poker::describe::{describe, short};
+    use rust_poker::poker::equity::{exact, monte_carlo};
+    use rust_poker::poker::evaluator;
+    use rust_poker::poker::game::{self, Action, ActionError, Blinds, Game};
+    use rust_poker::poker::history::{Event, HandHistory};
+    use rust_poker::poker::icm::{self, AllIn};
+    use rust_poker::poker::import::{self, ParseError};
+    use rust_poker::poker::index::{
+        hand_from_index, hand_index, hole_from_index, hole_index, StartingHand, HANDS, HOLE_CARDS,
+    };
+    use rust_poker::poker::isomorphism::{canonicalize, SuitMapping};
+    use rust_poker::poker::kuhn::Kuhn;
+    use rust_poker::poker::leduc::Leduc;
+    use rust_poker::poker::outs::{outs, outs_against, Draw};
+    use rust_poker::poker::potential::{potential, potential_against};
+    use rust_poker::poker::preflop::{class_equity, PreflopTable};
+    use rust_poker::poker::push_fold::PushFold;
+    use rust_poker::poker::range::Range;
+    use rust_poker::poker::ranking::Ranking;
+    use rust_poker::poker::render::{Render, Style};
+    use rust_poker::poker::replay::Replayer;
+    use rust_poker::poker::river::{River, RiverSolver};
+    use rust_poker::poker::rules::check_duplicates;
+    use rust_poker::poker::rules::check_flush;
+    use rust_poker::poker::rules::check_flush_royal;
+    use rust_poker::poker::rules::check_straight;
+    use rust_poker::poker::rules::Combination;
+    use rust_poker::poker::rules::{evaluate, Category, Evaluation};
+    use rust_poker::poker::stats::{self, Filter, Position, Tracker};
+    use rust_poker::poker::three_card::{
+        self, Bets, Paytables, Table, TableError, ThreeCardCategory,
+    };
+    use rust_poker::poker::tournament::{LevelLength, ManualClock, Structure, Tournament};
+    use rust_poker::poker::video::{Machine, MachineError, PayHand, Paytable};
+    use rust_poker::poker::video_strategy::Calculator;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    fn card(quantity: Quantity, suit: Suit) -> Card {
+        Card { quantity, suit }
+    }
 
     #[test]
     fn test_straight() {
@@ -193,9 +306,8 @@ mod tests {
             },
         ];
 
-        match check_straight(&hand) {
-            Some(_) => panic!("Did not expect straight."),
-            None => {}
+        if check_straight(&hand).is_some() {
+            panic!("Did not expect straight.");
         }
     }
 
@@ -261,9 +373,8 @@ mod tests {
             },
         ];
 
-        match check_flush(&hand) {
-            Some(_) => panic!("Did not expect flush."),
-            None => {}
+        if check_flush(&hand).is_some() {
+            panic!("Did not expect flush.");
         }
     }
 
@@ -403,9 +514,8 @@ mod tests {
             },
         ];
 
-        match check_flush_royal(&hand) {
-            Some(combination) => panic!("Did not expect a combination. {}", combination),
-            None => {}
+        if let Some(combination) = check_flush_royal(&hand) {
+            panic!("Did not expect a combination. {}", combination);
         }
     }
 
@@ -638,9 +748,2270 @@ mod tests {
             },
         ];
 
+        if let Some(combination) = check_duplicates(&hand) {
+            panic!("Did not expect a combination: {}", combination);
+        }
+    }
+
+    #[test]
+    fn test_full_house() {
+        let hand: Hand = [
+            card(Quantity::Nine, Suit::Hearts),
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Nine, Suit::Spades),
+            card(Quantity::King, Suit::Clubs),
+            card(Quantity::King, Suit::Diamonds),
+        ];
+
         match check_duplicates(&hand) {
-            Some(combination) => panic!("Did not expect a combination: {}", combination),
-            None => {}
+            Some(combination) => assert_eq!(
+                combination,
+                Combination {
+                    name: "Full house",
+                    cards: vec![&hand[1], &hand[3], &hand[4], &hand[0], &hand[2]]
+                }
+            ),
+            None => panic!("Did not receive a full house."),
+        }
+    }
+
+    #[test]
+    fn test_jacks_or_better_qualifier() {
+        let paytable = Paytable::jacks_or_better();
+        let jacks: Hand = [
+            card(Quantity::Jack, Suit::Hearts),
+            card(Quantity::Jack, Suit::Spades),
+            card(Quantity::Four, Suit::Clubs),
+            card(Quantity::Seven, Suit::Diamonds),
+            card(Quantity::Ace, Suit::Spades),
+        ];
+        let tens: Hand = [
+            card(Quantity::Ten, Suit::Hearts),
+            card(Quantity::Ten, Suit::Spades),
+            card(Quantity::Four, Suit::Clubs),
+            card(Quantity::Seven, Suit::Diamonds),
+            card(Quantity::Ace, Suit::Spades),
+        ];
+
+        assert_eq!(
+            paytable.lookup(&jacks).unwrap().hand,
+            PayHand::Pair(Quantity::Jack)
+        );
+        assert_eq!(paytable.payout(&jacks, 3), 3);
+        assert_eq!(paytable.lookup(&tens), None);
+        assert_eq!(paytable.payout(&tens, 3), 0);
+    }
+
+    #[test]
+    fn test_royal_pays_bonus_at_max_bet() {
+        let paytable = Paytable::jacks_or_better();
+        let hand: Hand = [
+            card(Quantity::Ace, Suit::Clubs),
+            card(Quantity::King, Suit::Clubs),
+            card(Quantity::Queen, Suit::Clubs),
+            card(Quantity::Jack, Suit::Clubs),
+            card(Quantity::Ten, Suit::Clubs),
+        ];
+
+        assert_eq!(paytable.payout(&hand, 4), 1000);
+        assert_eq!(paytable.payout(&hand, 5), 4000);
+    }
+
+    #[test]
+    fn test_bonus_poker_quads() {
+        let paytable = Paytable::bonus_poker();
+        let aces: Hand = [
+            card(Quantity::Ace, Suit::Clubs),
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::Ace, Suit::Spades),
+            card(Quantity::Ace, Suit::Diamonds),
+            card(Quantity::Ten, Suit::Clubs),
+        ];
+        let threes: Hand = [
+            card(Quantity::Three, Suit::Clubs),
+            card(Quantity::Three, Suit::Hearts),
+            card(Quantity::Three, Suit::Spades),
+            card(Quantity::Three, Suit::Diamonds),
+            card(Quantity::Ten, Suit::Clubs),
+        ];
+
+        assert_eq!(paytable.payout(&aces, 1), 80);
+        assert_eq!(paytable.payout(&threes, 1), 40);
+    }
+
+    #[test]
+    fn test_deuces_wild() {
+        let paytable = Paytable::deuces_wild();
+        let wild_royal: Hand = [
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::Two, Suit::Clubs),
+            card(Quantity::Queen, Suit::Hearts),
+            card(Quantity::Jack, Suit::Hearts),
+            card(Quantity::Ten, Suit::Hearts),
+        ];
+        let five_of_a_kind: Hand = [
+            card(Quantity::Nine, Suit::Hearts),
+            card(Quantity::Two, Suit::Clubs),
+            card(Quantity::Nine, Suit::Spades),
+            card(Quantity::Two, Suit::Hearts),
+            card(Quantity::Nine, Suit::Clubs),
+        ];
+        let full_house: Hand = [
+            card(Quantity::Nine, Suit::Hearts),
+            card(Quantity::Two, Suit::Clubs),
+            card(Quantity::Nine, Suit::Spades),
+            card(Quantity::Five, Suit::Hearts),
+            card(Quantity::Five, Suit::Clubs),
+        ];
+        let nothing: Hand = [
+            card(Quantity::Nine, Suit::Hearts),
+            card(Quantity::Two, Suit::Clubs),
+            card(Quantity::King, Suit::Spades),
+            card(Quantity::Five, Suit::Hearts),
+            card(Quantity::Seven, Suit::Clubs),
+        ];
+
+        assert_eq!(
+            paytable.lookup(&wild_royal).unwrap().hand,
+            PayHand::WildRoyal
+        );
+        assert_eq!(
+            paytable.lookup(&five_of_a_kind).unwrap().hand,
+            PayHand::FiveOfAKind
+        );
+        assert_eq!(
+            paytable.lookup(&full_house).unwrap().hand,
+            PayHand::FullHouse
+        );
+        assert_eq!(paytable.lookup(&nothing), None);
+    }
+
+    #[test]
+    fn test_machine_hold_and_draw() {
+        let mut machine = Machine::new(Paytable::jacks_or_better(), 10);
+        // Cards are dealt from the end of the deck.
+        let deck = Deck {
+            cards: vec![
+                card(Quantity::Two, Suit::Diamonds),
+                card(Quantity::Nine, Suit::Diamonds),
+                card(Quantity::Queen, Suit::Clubs),
+                card(Quantity::Seven, Suit::Clubs),
+                card(Quantity::Four, Suit::Spades),
+                card(Quantity::Queen, Suit::Spades),
+                card(Quantity::Queen, Suit::Hearts),
+                card(Quantity::Ace, Suit::Hearts),
+            ],
+        };
+
+        assert_eq!(machine.draw([true; 5]), Err(MachineError::NotDealt));
+        assert_eq!(
+            machine.deal_from(Deck { cards: vec![] }, 11),
+            Err(MachineError::InvalidBet)
+        );
+        assert_eq!(
+            machine.deal_from(Deck { cards: vec![] }, 1),
+            Err(MachineError::NotEnoughCards)
+        );
+        assert_eq!(machine.credits, 10);
+
+        let hand = machine.deal_from(deck, 2).unwrap();
+        assert_eq!(machine.credits, 8);
+        assert_eq!(hand[1], card(Quantity::Queen, Suit::Hearts));
+
+        assert_eq!(machine.draw([false; 5]), Err(MachineError::NotEnoughCards));
+        let outcome = machine.draw([false, true, true, false, false]).unwrap();
+        assert_eq!(outcome.entry.unwrap().hand, PayHand::ThreeOfAKind);
+        assert_eq!(outcome.won, 6);
+        assert_eq!(machine.credits, 14);
+    }
+
+    #[test]
+    fn test_best_hold() {
+        let calculator = Calculator::new(Paytable::jacks_or_better());
+        let hand: Hand = [
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Nine, Suit::Clubs),
+            card(Quantity::Queen, Suit::Hearts),
+            card(Quantity::Jack, Suit::Hearts),
+        ];
+
+        let holds = calculator.holds(&hand);
+        assert_eq!(holds.len(), 32);
+        assert_eq!(holds[0].held, [true, true, false, true, true]);
+        // One royal flush, eight flushes, three straights and twelve high pairs.
+        let expected = (800.0 + 8.0 * 6.0 + 3.0 * 4.0 + 12.0) / 47.0;
+        assert!((holds[0].expected - expected).abs() < 1e-9);
+
+        for hold in holds
+            .iter()
+            .filter(|hold| hold.held.iter().filter(|h| **h).count() >= 2)
+        {
+            let enumerated = calculator.enumerate(&hand, hold.held);
+            assert!((hold.expected - enumerated).abs() < 1e-9, "{}", hold);
+        }
+    }
+
+    #[test]
+    fn test_jacks_or_better_return() {
+        let calculator = Calculator::new(Paytable::jacks_or_better());
+        assert!((calculator.return_rate() - 0.995439).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_three_card_straight_beats_flush() {
+        let straight = three_card::evaluate(&[
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::Two, Suit::Clubs),
+            card(Quantity::Three, Suit::Hearts),
+        ]);
+        let flush = three_card::evaluate(&[
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Nine, Suit::Hearts),
+        ]);
+        let higher_straight = three_card::evaluate(&[
+            card(Quantity::Four, Suit::Spades),
+            card(Quantity::Two, Suit::Clubs),
+            card(Quantity::Three, Suit::Hearts),
+        ]);
+
+        assert_eq!(straight.category, ThreeCardCategory::Straight);
+        assert_eq!(flush.category, ThreeCardCategory::Flush);
+        assert!(straight > flush);
+        assert!(higher_straight > straight);
+    }
+
+    #[test]
+    fn test_three_card_pair_and_qualifier() {
+        let pair = three_card::evaluate(&[
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::Five, Suit::Clubs),
+            card(Quantity::Five, Suit::Hearts),
+        ]);
+        let queen = three_card::evaluate(&[
+            card(Quantity::Queen, Suit::Hearts),
+            card(Quantity::Five, Suit::Clubs),
+            card(Quantity::Two, Suit::Hearts),
+        ]);
+        let jack = three_card::evaluate(&[
+            card(Quantity::Jack, Suit::Hearts),
+            card(Quantity::Five, Suit::Clubs),
+            card(Quantity::Two, Suit::Hearts),
+        ]);
+
+        assert_eq!(pair.category, ThreeCardCategory::Pair);
+        assert_eq!(
+            pair.quantities,
+            [Quantity::Five, Quantity::Five, Quantity::Ace]
+        );
+        assert!(pair.qualifies());
+        assert!(queen.qualifies());
+        assert!(!jack.qualifies());
+    }
+
+    #[test]
+    fn test_three_card_settle() {
+        let paytables = Paytables::standard();
+        let bets = Bets {
+            ante: 10,
+            pair_plus: 5,
+        };
+        let trips = [
+            card(Quantity::Six, Suit::Hearts),
+            card(Quantity::Six, Suit::Clubs),
+            card(Quantity::Six, Suit::Spades),
+        ];
+        let king_high = [
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Nine, Suit::Clubs),
+            card(Quantity::Two, Suit::Spades),
+        ];
+        let jack_high = [
+            card(Quantity::Jack, Suit::Hearts),
+            card(Quantity::Nine, Suit::Clubs),
+            card(Quantity::Two, Suit::Spades),
+        ];
+
+        // Ante and play win, ante bonus pays 4 to 1, Pair Plus pays 30 to 1.
+        assert_eq!(
+            paytables.settle(bets, &trips, &king_high, true).net(),
+            10 + 10 + 40 + 150
+        );
+        // Dealer does not qualify: ante wins and play pushes.
+        assert_eq!(
+            paytables.settle(bets, &king_high, &jack_high, true).net(),
+            10 - 5
+        );
+        assert_eq!(
+            paytables.settle(bets, &jack_high, &king_high, true).net(),
+            -10 - 10 - 5
+        );
+        assert_eq!(
+            paytables.settle(bets, &jack_high, &king_high, false).net(),
+            -10 - 5
+        );
+    }
+
+    #[test]
+    fn test_three_card_house_edge() {
+        let edge = Paytables::standard().house_edge();
+        assert!((edge.ante - 0.0337).abs() < 1e-4);
+        assert!((edge.pair_plus - 0.0232).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_three_card_table() {
+        let mut table = Table::new(Paytables::standard(), 30);
+        let bets = Bets {
+            ante: 10,
+            pair_plus: 0,
+        };
+        let deck = Deck {
+            cards: vec![
+                card(Quantity::Two, Suit::Spades),
+                card(Quantity::Four, Suit::Hearts),
+                card(Quantity::Queen, Suit::Clubs),
+                card(Quantity::Ace, Suit::Spades),
+                card(Quantity::Ace, Suit::Hearts),
+                card(Quantity::Nine, Suit::Diamonds),
+            ],
+        };
+
+        assert_eq!(table.decide(true), Err(TableError::NotDealt));
+        assert_eq!(
+            table.deal_from(Deck { cards: vec![] }, bets),
+            Err(TableError::NotEnoughCards)
+        );
+        let huge = Bets {
+            ante: u32::MAX,
+            pair_plus: u32::MAX,
+        };
+        assert_eq!(
+            table.deal_from(Deck { cards: vec![] }, huge),
+            Err(TableError::NotEnoughCredits)
+        );
+        let hand = table.deal_from(deck, bets).unwrap();
+        assert_eq!(hand[0], card(Quantity::Nine, Suit::Diamonds));
+
+        let (dealer, settlement) = table.decide(true).unwrap();
+        assert_eq!(dealer[0], card(Quantity::Queen, Suit::Clubs));
+        assert_eq!(settlement.net(), 20);
+        assert_eq!(table.credits, 50);
+    }
+
+    #[test]
+    fn test_describe() {
+        let full_house: Hand = [
+            card(Quantity::Seven, Suit::Hearts),
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Seven, Suit::Spades),
+            card(Quantity::King, Suit::Clubs),
+            card(Quantity::King, Suit::Diamonds),
+        ];
+        let two_pairs: Hand = [
+            card(Quantity::Four, Suit::Hearts),
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::King, Suit::Spades),
+            card(Quantity::Four, Suit::Clubs),
+            card(Quantity::Ace, Suit::Diamonds),
+        ];
+        let straight: Hand = [
+            card(Quantity::Eight, Suit::Hearts),
+            card(Quantity::Six, Suit::Hearts),
+            card(Quantity::Five, Suit::Spades),
+            card(Quantity::Nine, Suit::Clubs),
+            card(Quantity::Seven, Suit::Diamonds),
+        ];
+        let flush: Hand = [
+            card(Quantity::Ace, Suit::Clubs),
+            card(Quantity::Ten, Suit::Clubs),
+            card(Quantity::Five, Suit::Clubs),
+            card(Quantity::Nine, Suit::Clubs),
+            card(Quantity::Two, Suit::Clubs),
+        ];
+
+        assert_eq!(describe(&full_house), "Full House, Kings full of Sevens");
+        assert_eq!(short(&full_house), "KKK77");
+        assert_eq!(
+            describe(&two_pairs),
+            "Two Pair, Aces and Fours with a King kicker"
+        );
+        assert_eq!(short(&two_pairs), "AA44K");
+        assert_eq!(describe(&straight), "Straight, Five to Nine");
+        assert_eq!(short(&straight), "98765");
+        assert_eq!(describe(&flush), "Ace-high Flush");
+        assert_eq!(short(&flush), "AT952");
+    }
+
+    #[test]
+    fn test_describe_wheel_and_pair() {
+        let wheel: Hand = [
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::Two, Suit::Hearts),
+            card(Quantity::Three, Suit::Spades),
+            card(Quantity::Four, Suit::Clubs),
+            card(Quantity::Five, Suit::Diamonds),
+        ];
+        let pair: Hand = [
+            card(Quantity::Eight, Suit::Hearts),
+            card(Quantity::Jack, Suit::Hearts),
+            card(Quantity::Three, Suit::Spades),
+            card(Quantity::Jack, Suit::Clubs),
+            card(Quantity::Five, Suit::Diamonds),
+        ];
+
+        assert_eq!(describe(&wheel), "Straight, Ace to Five");
+        assert_eq!(short(&wheel), "5432A");
+        assert_eq!(describe(&pair), "Pair of Jacks with an Eight kicker");
+        assert_eq!(short(&pair), "JJ853");
+    }
+
+    #[test]
+    fn test_suit_symbols() {
+        assert_eq!(Suit::Spades.symbol(), '♠');
+        assert_eq!(Suit::Clubs.symbol(), '♣');
+        assert_eq!(Suit::Diamonds.symbol(), '♦');
+        assert_eq!(Suit::Hearts.symbol(), '♥');
+    }
+
+    #[test]
+    fn test_card_styles() {
+        let ace = card(Quantity::Ace, Suit::Spades);
+        let ten = card(Quantity::Ten, Suit::Hearts);
+        let queen = card(Quantity::Queen, Suit::Clubs);
+
+        assert_eq!(ace.styled(Style::Ascii).to_string(), "As");
+        assert_eq!(ten.styled(Style::Ascii).to_string(), "Th");
+        assert_eq!(ten.styled(Style::Unicode).to_string(), "10♥");
+        assert_eq!(ten.styled(Style::Ansi).to_string(), "\x1b[31m10♥\x1b[0m");
+        assert_eq!(ace.styled(Style::PlayingCards).to_string(), "\u{1F0A1}");
+        assert_eq!(queen.styled(Style::PlayingCards).to_string(), "\u{1F0DD}");
+    }
+
+    #[test]
+    fn test_combination_style() {
+        let hand: Hand = [
+            card(Quantity::Ace, Suit::Diamonds),
+            card(Quantity::Jack, Suit::Spades),
+            card(Quantity::Queen, Suit::Spades),
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Ace, Suit::Spades),
+        ];
+
+        let combination = check_duplicates(&hand).unwrap();
+        assert_eq!(combination.styled(Style::Ascii).to_string(), "Pair: Ad As ");
+    }
+
+    #[test]
+    fn test_parse_card() {
+        assert_eq!("Ah".parse(), Ok(card(Quantity::Ace, Suit::Hearts)));
+        assert_eq!("Td".parse(), Ok(card(Quantity::Ten, Suit::Diamonds)));
+        assert_eq!("10d".parse(), Ok(card(Quantity::Ten, Suit::Diamonds)));
+        assert_eq!("7♣".parse(), Ok(card(Quantity::Seven, Suit::Clubs)));
+        assert!("1h".parse::<Card>().is_err());
+        assert!("Ax".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn test_owned_evaluation() {
+        let hand: Hand = [
+            card(Quantity::Four, Suit::Hearts),
+            card(Quantity::Ace, Suit::Hearts),
+            card(Quantity::King, Suit::Spades),
+            card(Quantity::Four, Suit::Clubs),
+            card(Quantity::Ace, Suit::Diamonds),
+        ];
+
+        let evaluation = Evaluation::from(evaluate(&hand));
+        assert_eq!(evaluation.category, Category::TwoPairs);
+        assert_eq!(evaluation.cards(), &[hand[1], hand[4], hand[0], hand[3]]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_cards() {
+        let ace = card(Quantity::Ace, Suit::Hearts);
+        assert_eq!(serde_json::to_string(&ace).unwrap(), "\"Ah\"");
+        assert_eq!(serde_json::from_str::<Card>("\"Ah\"").unwrap(), ace);
+        assert!(serde_json::from_str::<Card>("\"Ax\"").is_err());
+        assert_eq!(serde_json::to_string(&Suit::Spades).unwrap(), "\"s\"");
+        assert_eq!(serde_json::to_string(&Quantity::Ten).unwrap(), "\"T\"");
+
+        let deck = Deck::create();
+        let json = serde_json::to_string(&deck).unwrap();
+        assert!(json.starts_with("{\"cards\":[\"2s\",\"3s\""));
+        let restored: Deck = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.cards, deck.cards);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_evaluation() {
+        let hand: Hand = [
+            card(Quantity::Nine, Suit::Hearts),
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Nine, Suit::Spades),
+            card(Quantity::King, Suit::Clubs),
+            card(Quantity::King, Suit::Diamonds),
+        ];
+
+        let json = serde_json::to_string(&hand).unwrap();
+        assert_eq!(json, "[\"9h\",\"Kh\",\"9s\",\"Kc\",\"Kd\"]");
+        let restored: Hand = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, hand);
+
+        let evaluation = Evaluation::from(evaluate(&hand));
+        let json = serde_json::to_string(&evaluation).unwrap();
+        assert_eq!(
+            json,
+            "{\"category\":\"FullHouse\",\"cards\":[\"Kh\",\"Kc\",\"Kd\",\"9h\",\"9s\"],\"strength\":7200153}"
+        );
+        assert_eq!(
+            serde_json::from_str::<Evaluation>(&json).unwrap(),
+            evaluation
+        );
+        // Categories and strengths that the cards do not make are rejected.
+        let wrong = json.replace("FullHouse", "Flush");
+        assert!(serde_json::from_str::<Evaluation>(&wrong).is_err());
+        let wrong = json.replace("7200153", "7200154");
+        assert!(serde_json::from_str::<Evaluation>(&wrong).is_err());
+
+        // Kickers count in the strength of a pair but are not written.
+        let pair = cards("As Ad Kc 7h 2s");
+        let pair = Evaluation::of(&[pair[0], pair[1], pair[2], pair[3], pair[4]]);
+        let json = serde_json::to_string(&pair).unwrap();
+        assert_eq!(serde_json::from_str::<Evaluation>(&json).unwrap(), pair);
+        let wrong = json.replace("\"Ad\"", "\"Kd\"");
+        assert!(serde_json::from_str::<Evaluation>(&wrong).is_err());
+    }
+
+    #[test]
+    fn test_evaluation_strength() {
+        let hands: Vec<Hand> = [
+            "As Ad Kc 7h 2s",
+            "As Ad Qc Jh Ts",
+            "Ah 2d 3c 4h 5s",
+            "6h 2d 3c 4h 5s",
+        ]
+        .iter()
+        .map(|hand| {
+            let cards: Vec<Card> = hand.split(' ').map(|card| card.parse().unwrap()).collect();
+            [cards[0], cards[1], cards[2], cards[3], cards[4]]
+        })
+        .collect();
+
+        let kings = Evaluation::of(&hands[0]);
+        let queens = Evaluation::of(&hands[1]);
+        let wheel = Evaluation::of(&hands[2]);
+        let six_high = Evaluation::of(&hands[3]);
+
+        assert_eq!(kings.category, Category::Pair);
+        assert!(kings.strength > queens.strength);
+        // Without kickers both pairs of aces are equally strong.
+        assert_eq!(
+            Evaluation::from(evaluate(&hands[0])).strength,
+            Evaluation::from(evaluate(&hands[1])).strength
+        );
+        assert!(six_high.strength > wheel.strength);
+        assert!(wheel.strength > kings.strength);
+    }
+
+    #[test]
+    fn test_evaluation_outlives_hand() {
+        let evaluation = std::thread::spawn(|| {
+            let hand: Hand = [
+                card(Quantity::Six, Suit::Hearts),
+                card(Quantity::Six, Suit::Clubs),
+                card(Quantity::Six, Suit::Spades),
+                card(Quantity::Two, Suit::Hearts),
+                card(Quantity::Nine, Suit::Hearts),
+            ];
+            Evaluation::of(&hand)
+        })
+        .join()
+        .unwrap();
+
+        let copy = evaluation;
+        assert_eq!(copy.category, Category::ThreeOfAKind);
+        assert_eq!(evaluation.cards().len(), 3);
+    }
+
+    #[test]
+    fn test_card_index() {
+        for index in 0..52 {
+            assert_eq!(Card::from_index(index).unwrap().index(), index);
+        }
+        assert_eq!(card(Quantity::Two, Suit::Spades).index(), 0);
+        assert_eq!(card(Quantity::Ace, Suit::Hearts).index(), 51);
+        assert_eq!(Card::from_index(52), None);
+
+        let mut deck = Deck::create();
+        deck.shuffle();
+        let restored = Deck::from_indices(&deck.indices()).unwrap();
+        assert_eq!(restored.cards, deck.cards);
+    }
+
+    #[test]
+    fn test_hand_index() {
+        let hand: Hand = [
+            card(Quantity::Nine, Suit::Hearts),
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Nine, Suit::Spades),
+            card(Quantity::King, Suit::Clubs),
+            card(Quantity::King, Suit::Diamonds),
+        ];
+        let mut sorted = hand;
+        sorted.sort_by_key(|card| card.index());
+
+        let index = hand_index(&hand);
+        assert!(index < HANDS);
+        assert_eq!(hand_from_index(index), Some(sorted));
+        assert_eq!(hand_index(&hand_from_index(0).unwrap()), 0);
+        assert_eq!(hand_index(&hand_from_index(HANDS - 1).unwrap()), HANDS - 1);
+        assert_eq!(hand_from_index(HANDS), None);
+
+        for index in (0..HANDS).step_by(9973) {
+            assert_eq!(hand_index(&hand_from_index(index).unwrap()), index);
+        }
+    }
+
+    #[test]
+    fn test_hole_index() {
+        for index in 0..HOLE_CARDS {
+            let cards = hole_from_index(index).unwrap();
+            assert_eq!(hole_index(&cards), index);
+            assert_eq!(hole_index(&[cards[1], cards[0]]), index);
+        }
+        assert_eq!(hole_from_index(HOLE_CARDS), None);
+    }
+
+    #[test]
+    fn test_starting_hands() {
+        let all = StartingHand::all();
+        assert_eq!(all.len(), 169);
+        let combos: usize = all.iter().map(|hand| hand.combos().len()).sum();
+        assert_eq!(combos, 1326);
+
+        for (index, hand) in all.iter().enumerate() {
+            assert_eq!(hand.index() as usize, index);
+            assert_eq!(hand.to_string().parse(), Ok(*hand));
+            for cards in hand.combos() {
+                assert_eq!(StartingHand::of(&cards), *hand);
+            }
+        }
+
+        let suited = StartingHand::of(&[
+            card(Quantity::King, Suit::Hearts),
+            card(Quantity::Ace, Suit::Hearts),
+        ]);
+        assert_eq!(suited.to_string(), "AKs");
+        assert_eq!("72o".parse::<StartingHand>().unwrap().combos().len(), 12);
+        assert_eq!("TT".parse::<StartingHand>().unwrap().combos().len(), 6);
+        assert!("TTs".parse::<StartingHand>().is_err());
+    }
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(' ').map(|card| card.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_canonical_preflop() {
+        let mut indices: Vec<u64> = (0..HOLE_CARDS)
+            .map(|index| canonicalize(&hole_from_index(index).unwrap(), &[]).index)
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        assert_eq!(indices.len(), 169);
+    }
+
+    #[test]
+    fn test_canonical_flop() {
+        let first = canonicalize(&cards("Ah Kh"), &cards("Qh 7d 2c"));
+        let second = canonicalize(&cards("Ks As"), &cards("2d Qs 7c"));
+        let different = canonicalize(&cards("Ah Kh"), &cards("Qd 7h 2c"));
+
+        assert_eq!(first.hole, second.hole);
+        assert_eq!(first.board, second.board);
+        assert_eq!(first.index, second.index);
+        assert_ne!(first.index, different.index);
+
+        for card in cards("Ks As 2d Qs 7c") {
+            let canonical = second.mapping.apply(&card);
+            assert_eq!(second.mapping.invert(&canonical), card);
+        }
+        assert_eq!(second.mapping.invert(&second.hole[0]).suit, Suit::Spades);
+    }
+
+    #[test]
+    fn test_suit_mappings() {
+        let mappings = SuitMapping::all();
+        assert_eq!(mappings.len(), 24);
+        assert!(mappings.contains(&SuitMapping::identity()));
+        let distinct: HashSet<SuitMapping> = mappings.into_iter().collect();
+        assert_eq!(distinct.len(), 24);
+    }
+
+    #[test]
+    fn test_evaluator_matches_evaluation() {
+        for index in (0..HANDS).step_by(997) {
+            let hand = hand_from_index(index).unwrap();
+            let strength = evaluator::evaluate(&hand);
+            assert_eq!(strength, Evaluation::of(&hand).strength);
+            assert_eq!(
+                evaluator::category(strength),
+                Evaluation::of(&hand).category
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluator_seven_cards() {
+        let flush = evaluator::evaluate(&cards("2h 3h 4d 5h 6s 9h Kh"));
+        let straight = evaluator::evaluate(&cards("2h 3h 4d 5h 6s 9c Kd"));
+        let wheel = evaluator::evaluate(&cards("Ah 2c 3d 4s 5h Kd Kc"));
+        assert_eq!(evaluator::category(flush), Category::Flush);
+        assert_eq!(evaluator::category(straight), Category::Straight);
+        assert_eq!(evaluator::category(wheel), Category::Straight);
+        assert!(flush > straight);
+        assert!(straight > wheel);
+
+        let full_house = evaluator::evaluate(&cards("Kh Kd Kc 7s 7h 7d 2c"));
+        assert_eq!(
+            full_house,
+            Evaluation::of(&[
+                card(Quantity::King, Suit::Hearts),
+                card(Quantity::King, Suit::Diamonds),
+                card(Quantity::King, Suit::Clubs),
+                card(Quantity::Seven, Suit::Spades),
+                card(Quantity::Seven, Suit::Hearts),
+            ])
+            .strength
+        );
+    }
+
+    #[test]
+    fn test_exact_equity() {
+        let hands = [hole("Ah Kh"), hole("Qs Qd")];
+        let turn = cards("Qh 7h 2c 3s");
+        let single = exact(&hands, &turn, 1);
+        let threaded = exact(&hands, &turn, 3);
+        assert_eq!(single, threaded);
+
+        // Nine hearts remain, but the two and the three of hearts fill up the set of queens.
+        let outs = 7.0;
+        assert!((single[0].equity - outs / 44.0).abs() < 1e-9);
+        assert_eq!(single[0].tie, 0.0);
+        assert!((single[0].equity + single[1].equity - 1.0).abs() < 1e-9);
+
+        let river = exact(&hands, &cards("Qh 7h 2c 3s 4h"), 2);
+        assert_eq!(river[0].win, 1.0);
+        assert_eq!(river[1].equity, 0.0);
+    }
+
+    #[test]
+    fn test_monte_carlo_equity() {
+        let hands = [hole("As Ac"), hole("Kd Kh")];
+        let first = monte_carlo(&hands, &[], 20_000, 2, 7);
+        let second = monte_carlo(&hands, &[], 20_000, 2, 7);
+        assert_eq!(first, second);
+        assert!((first[0].equity - 0.82).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_class_equity() {
+        let aces: StartingHand = "AA".parse().unwrap();
+        let kings: StartingHand = "KK".parse().unwrap();
+        let equity = class_equity(aces, kings, 2);
+        assert!((equity - 0.8195).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_preflop_table_file() {
+        let mut bytes = b"RPPF".to_vec();
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&169u32.to_le_bytes());
+        for index in 0..169 * 169 {
+            bytes.extend_from_slice(&(index as f32 / 100_000.0).to_le_bytes());
+        }
+
+        let table = PreflopTable::read(&mut bytes.as_slice()).unwrap();
+        let mut written = Vec::new();
+        table.write(&mut written).unwrap();
+        assert_eq!(written, bytes);
+
+        let twos = StartingHand::from_index(0).unwrap();
+        let threes = StartingHand::from_index(14).unwrap();
+        assert!((table.equity(twos, threes) - 14.0 / 100_000.0).abs() < 1e-9);
+
+        assert!(PreflopTable::read(&mut &b"RPPX"[..]).is_err());
+        assert!(PreflopTable::read(&mut &bytes[..100]).is_err());
+    }
+
+    #[test]
+    fn test_flush_draw_outs() {
+        let analysis = outs(&hole("Ah Kh"), &cards("Qh 7h 2c"));
+        assert_eq!(analysis.category, Category::HighCard);
+        assert_eq!(analysis.unseen, 47);
+        assert_eq!(analysis.cards(Draw::Flush).len(), 9);
+        assert_eq!(analysis.cards(Draw::Other).len(), 6);
+        assert_eq!(analysis.groups().len(), 2);
+        assert!((analysis.estimate - 0.6).abs() < 1e-9);
+        // The rule of 4 overestimates fifteen outs.
+        assert!(analysis.probability > 0.54 && analysis.probability < analysis.estimate);
+
+        let backdoors: Vec<Draw> = analysis.backdoors.iter().map(|b| b.draw).collect();
+        assert_eq!(backdoors, vec![Draw::BackdoorStraight]);
+    }
+
+    #[test]
+    fn test_straight_and_set_outs() {
+        let open = outs(&hole("9c 8d"), &cards("7s 6h 2c"));
+        assert_eq!(open.cards(Draw::OpenEnded).len(), 8);
+
+        let gutshot = outs(&hole("9c 8d"), &cards("Js 7h 2c 3d"));
+        assert_eq!(gutshot.cards(Draw::Gutshot).len(), 4);
+        assert!(gutshot.backdoors.is_empty());
+        assert!((gutshot.estimate - 0.2).abs() < 1e-9);
+        assert!((gutshot.probability - 10.0 / 46.0).abs() < 1e-9);
+
+        let set = outs(&hole("5s 5d"), &cards("Ah Kd 9c"));
+        assert_eq!(
+            set.cards(Draw::Set),
+            vec![
+                card(Quantity::Five, Suit::Clubs),
+                card(Quantity::Five, Suit::Hearts)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outs_against_opponent() {
+        let analysis = outs_against(&hole("Ah Kh"), &cards("Qh 7h 2c 3s"), &hole("Qs Qd"));
+        assert_eq!(analysis.unseen, 44);
+        assert_eq!(analysis.outs.len(), 7);
+        assert_eq!(
+            analysis.groups(),
+            vec![(Draw::Flush, analysis.cards(Draw::Flush))]
+        );
+        assert!((analysis.probability - 7.0 / 44.0).abs() < 1e-9);
+
+        let ahead = outs_against(&hole("Qs Qd"), &cards("Qh 7h 2c 3s"), &hole("Ah Kh"));
+        assert!(ahead.outs.is_empty());
+        assert!((ahead.probability - 37.0 / 44.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_board_texture() {
+        let texture = Texture::of(&cards("9h 8h 7c"));
+        assert_eq!(texture.street, Street::Flop);
+        assert_eq!(texture.pairing, Pairing::Unpaired);
+        assert_eq!(texture.suits, Suits::TwoTone);
+        assert_eq!(texture.straights, 3);
+        assert_eq!(texture.high, Quantity::Nine);
+        assert_eq!((texture.broadway, texture.low), (0, 2));
+        assert!(texture.straight_possible && !texture.straight_completed);
+        assert!(!texture.flush_possible);
+        assert_eq!(texture.nut_category, Category::Straight);
+        // Jacks with tens, and sixes with tens or fives.
+        assert_eq!(texture.nuts.len(), 16);
+
+        let dry = Texture::of(&cards("Kd Kc 2s"));
+        assert_eq!(dry.pairing, Pairing::Paired);
+        assert_eq!(dry.suits, Suits::Rainbow);
+        assert!(!dry.straight_possible);
+        assert_eq!(dry.nut_category, Category::FourOfAKind);
+        assert_eq!(dry.nuts, vec![hole("Ks Kh")]);
+
+        let monotone = Texture::of(&cards("Qs 8s 3s"));
+        assert_eq!(monotone.suits, Suits::Monotone);
+        assert!(monotone.flush_possible && !monotone.flush_completed);
+        assert_eq!(monotone.nut_category, Category::Flush);
+
+        let turn = Texture::of(&cards("Qs 8s 3s 2d"));
+        assert_eq!(turn.suits, Suits::FlushPossible);
+        assert_eq!(turn.suited, 3);
+    }
+
+    #[test]
+    fn test_river_texture() {
+        let texture = Texture::of(&cards("Ah Kh Qh Jh 2c"));
+        assert_eq!(texture.street, Street::River);
+        assert_eq!(texture.suits, Suits::FlushPossible);
+        assert!(texture.flush_possible && !texture.flush_completed);
+        assert_eq!(texture.nut_category, Category::FlushRoyal);
+        assert_eq!(texture.nuts.len(), 46);
+
+        let wheel = Texture::of(&cards("As 2d 3c 4h 5s"));
+        assert!(wheel.straight_completed);
+        assert_eq!(wheel.nut_category, Category::Straight);
+        assert!(wheel
+            .nuts
+            .iter()
+            .all(|hole| hole.iter().any(|card| card.quantity == Quantity::Six)));
+    }
+
+    #[test]
+    fn test_river_ranking() {
+        let ranking = Ranking::new(&cards("Ah Kh 7h 7c 2d"));
+        assert_eq!(ranking.holdings().len(), 1081);
+
+        let nuts = ranking.nuts();
+        assert_eq!(nuts.len(), 1);
+        assert_eq!(nuts[0].category(), Category::FourOfAKind);
+        assert_eq!(nuts[0].hole, hole("7s 7d"));
+
+        // Below the quads and the six full houses, the queen and jack of hearts are the nut flush.
+        let flush = ranking.strength(&hole("Qh Jh"));
+        assert_eq!(flush.rank, 8);
+        assert!(!flush.is_nuts());
+
+        let quads = ranking.strength(&hole("7d 7s"));
+        assert!(quads.is_nuts());
+        assert_eq!((quads.beaten_by, quads.ties), (0, 0));
+        assert_eq!(quads.beats, 990);
+        assert_eq!(quads.percentile, 1.0);
+    }
+
+    #[test]
+    fn test_relative_strength() {
+        let ranking = Ranking::new(&cards("Ks 9d 4c"));
+        assert_eq!(ranking.holdings().len(), 1176);
+
+        let top_set = ranking.strength(&hole("Kh Kd"));
+        assert!(top_set.is_nuts());
+        assert_eq!(
+            top_set.to_string(),
+            "Nuts, beats 1081, ties 0, loses to 0 (100.0%)"
+        );
+
+        let middle_set = ranking.strength(&hole("9h 9c"));
+        assert_eq!(middle_set.rank, 2);
+        assert_eq!(middle_set.beaten_by, 3);
+        assert!(middle_set.to_string().starts_with("2nd nuts"));
+
+        let air = ranking.strength(&hole("3h 2h"));
+        assert_eq!(air.beats + air.ties + air.beaten_by, 1081);
+        assert!(air.percentile < 0.1);
+    }
+
+    #[test]
+    fn test_hand_potential() {
+        // The example of Billings et al., "Opponent Modeling in Poker".
+        let flop = potential(&hole("Ad Qc"), &cards("3h 4c Jh"));
+        assert!((flop.hs - 0.585).abs() < 0.001);
+        assert!((flop.ppot - 0.208).abs() < 0.001);
+        assert!((flop.npot - 0.274).abs() < 0.001);
+        assert!(
+            (flop.ehs - (flop.hs * (1.0 - flop.npot) + (1.0 - flop.hs) * flop.ppot)).abs() < 1e-12
+        );
+        assert!(flop.optimistic() > flop.ehs);
+
+        let nuts = potential(&hole("Ah Kh"), &cards("Qh Jh Th 2c"));
+        assert_eq!((nuts.hs, nuts.npot, nuts.ppot), (1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_hand_potential_against_range() {
+        let range = [
+            (hole("Kd Kc"), 1.0),
+            (hole("2s 2d"), 3.0),
+            (hole("Ad 5d"), 1.0),
+        ];
+        let potential = potential_against(&hole("Ah Qh"), &cards("Ks 7h 2h"), &range);
+        // Ahead only of the ace of diamonds, which has a fifth of the weight.
+        assert!((potential.hs - 0.2).abs() < 1e-12);
+        assert!(potential.ppot > 0.2 && potential.ppot < 0.3);
+        assert!(potential.npot > 0.0 && potential.npot < potential.ppot);
+    }
+
+    #[test]
+    fn test_range_notation() {
+        let range: Range = "22+, A2s+, KTo+, 76s-54s, QJ, AKo:0.5".parse().unwrap();
+        assert!(range.contains("33".parse().unwrap()));
+        assert!(range.contains("A7s".parse().unwrap()));
+        assert!(range.contains("QJs".parse().unwrap()));
+        assert!(range.contains("QJo".parse().unwrap()));
+        assert!(!range.contains("K9o".parse().unwrap()));
+        assert!(!range.contains("43s".parse().unwrap()));
+        assert_eq!(range.weight("AKo".parse().unwrap()), 0.5);
+        assert_eq!(
+            range.to_string(),
+            "22+, A2s+, QJs, 76s, 65s, 54s, KTo+, QJo, AKo:0.5"
+        );
+        assert_eq!(range.to_string().parse::<Range>().unwrap(), range);
+
+        assert_eq!(Range::full().to_string(), "22+, A2s+, K2s+, Q2s+, J2s+, T2s+, 92s+, 82s+, 72s+, 62s+, 52s+, 42s+, 32s, A2o+, K2o+, Q2o+, J2o+, T2o+, 92o+, 82o+, 72o+, 62o+, 52o+, 42o+, 32o");
+        assert_eq!(Range::full().share(), 1.0);
+        assert_eq!("AA".parse::<Range>().unwrap().combos().len(), 6);
+        assert_eq!("AK-QJ".parse::<Range>().unwrap().combos().len(), 48);
+        assert!("AKs-Q5s".parse::<Range>().is_err());
+        assert!("AKs:2".parse::<Range>().is_err());
+    }
+
+    #[test]
+    fn test_range_grid() {
+        let range: Range = "TT+, AKs, AKo:0.25".parse().unwrap();
+        let grid = range.grid();
+        let rows: Vec<&str> = grid.lines().collect();
+        assert_eq!(rows.len(), 13);
+        assert!(rows[0].starts_with("AA  AKs  . "));
+        assert!(rows[1].starts_with("25% KK "));
+        assert!(rows[4].contains("TT"));
+        assert_eq!(
+            rows[12].trim(),
+            ".   .   .   .   .   .   .   .   .   .   .   .   ."
+        );
+    }
+
+    #[test]
+    fn test_push_fold() {
+        let table = PreflopTable::estimate(50, 2, 3);
+        let short = PushFold::in_big_blinds(10.0).solve(&table, 300);
+        assert!(short.push.share() > 0.5 && short.push.share() < 0.65);
+        assert!(short.call.share() > 0.3 && short.call.share() < 0.45);
+
+        let deep = PushFold::in_big_blinds(20.0).solve(&table, 300);
+        assert!(deep.push.share() < short.push.share());
+        assert!(deep.call.share() < short.call.share());
+        for hand in deep.push.classes() {
+            assert!(short.push.contains(hand));
+        }
+
+        // Pushed and called at 10 big blinds, pushed and called at 20.
+        let chart = |hand: &str| {
+            let hand = hand.parse().unwrap();
+            [
+                short.push.contains(hand),
+                short.call.contains(hand),
+                deep.push.contains(hand),
+                deep.call.contains(hand),
+            ]
+        };
+        assert_eq!(chart("AA"), [true, true, true, true]);
+        assert_eq!(chart("A5o"), [true, true, true, true]);
+        assert_eq!(chart("22"), [true, true, true, false]);
+        assert_eq!(chart("K9o"), [true, true, true, false]);
+        assert_eq!(chart("K2s"), [true, true, false, false]);
+        assert_eq!(chart("T8o"), [true, false, false, false]);
+        assert_eq!(chart("72o"), [false, false, false, false]);
+
+        let antes = PushFold {
+            ante: 0.125,
+            ..PushFold::in_big_blinds(10.0)
+        };
+        assert!(antes.solve(&table, 300).push.share() > short.push.share());
+    }
+
+    #[test]
+    fn test_icm_equities() {
+        let payouts = [50.0, 30.0, 20.0];
+        let equities = icm::equities(&[5000.0, 3000.0, 2000.0], &payouts);
+        // First 25, second (0.3 * 5 / 7 + 0.2 * 5 / 8) * 30 and the rest of the chances third.
+        let second = 0.3 * 5.0 / 7.0 + 0.2 * 5.0 / 8.0;
+        let expected = 25.0 + second * 30.0 + (1.0 - 0.5 - second) * 20.0;
+        assert!((equities[0] - expected).abs() < 1e-9);
+        assert!((equities.iter().sum::<f64>() - 100.0).abs() < 1e-9);
+        assert!(equities[0] < 50.0 && equities[2] > 20.0);
+
+        let estimated = icm::monte_carlo(&[5000.0, 3000.0, 2000.0], &payouts, 100_000, 1);
+        for (estimated, exact) in estimated.iter().zip(equities.iter()) {
+            assert!((estimated - exact).abs() < 0.3);
+        }
+
+        // Fields of more than 64 players are estimated, however few places are paid.
+        let field = icm::equities(&[1000.0; 70], &[60.0, 40.0]);
+        assert!((field.iter().sum::<f64>() - 100.0).abs() < 1e-6);
+        assert!(field
+            .iter()
+            .all(|equity| (equity - 100.0 / 70.0).abs() < 0.1));
+        let mut stacks = vec![0.0; 70];
+        stacks[3] = 3000.0;
+        stacks[68] = 1000.0;
+        assert_eq!(
+            icm::equities(&stacks, &[60.0, 40.0])[68],
+            0.25 * 60.0 + 0.75 * 40.0
+        );
+
+        let busted = icm::equities(&[100.0, 0.0, 100.0], &payouts);
+        assert_eq!(busted[1], 0.0);
+        assert!((busted[0] - 40.0).abs() < 1e-9);
+
+        let field = icm::equities(
+            &vec![1000.0; 200],
+            &[30.0, 20.0, 15.0, 10.0, 10.0, 5.0, 5.0, 5.0],
+        );
+        assert!(field.iter().all(|equity| (equity - 0.5).abs() < 0.05));
+    }
+
+    #[test]
+    fn test_icm_call() {
+        // Four players on the bubble with three paid: the big stack covers everyone.
+        let spot = AllIn {
+            stacks: vec![5000.0, 2500.0, 1500.0, 1000.0],
+            posted: vec![0.0, 0.0, 100.0, 200.0],
+            payouts: vec![50.0, 30.0, 20.0],
+            pusher: 1,
+            caller: 3,
+        };
+        let showdown = spot.showdown_stacks(3);
+        assert_eq!(showdown, vec![5000.0, 1500.0, 1400.0, 2100.0]);
+        assert_eq!(spot.fold_stacks(1), vec![5000.0, 2800.0, 1400.0, 800.0]);
+
+        // Chip EV needs 800 out of 2100 chips, ICM asks for more.
+        let required = spot.required_equity();
+        assert!(required > 800.0 / 2100.0);
+        let call = spot.call_value(required);
+        assert!(call.gain().abs() < 1e-9);
+        assert!(spot.call_value(0.6).gain() > 0.0);
+
+        let table = PreflopTable::estimate(20, 2, 5);
+        let push: Range = "22+, A2s+, K9s+, A7o+, KJo+".parse().unwrap();
+        let aces = spot.call_value_with(&table, "AA".parse().unwrap(), &push);
+        let junk = spot.call_value_with(&table, "72o".parse().unwrap(), &push);
+        assert!(aces.gain() > 0.0 && junk.gain() < 0.0);
+
+        let call: Range = "TT+, AQs+, AKo".parse().unwrap();
+        let shove = spot.push_value_with(&table, "A5s".parse().unwrap(), &call);
+        assert!(shove.play > 0.0 && shove.fold > 0.0);
+    }
+
+    #[test]
+    fn test_game_heads_up() {
+        let players = vec![
+            (0, String::from("Ann"), 1000),
+            (1, String::from("Bob"), 1000),
+        ];
+        let mut game = Game::new(players, 0, Blinds::new(10, 20, 0), Deck::create());
+        // Heads-up the button posts the small blind and acts first before the flop.
+        assert_eq!(game.blind_positions(), (0, 1));
+        assert_eq!(game.to_act(), Some(0));
+        let legal = game.legal().unwrap();
+        assert_eq!(
+            (legal.check, legal.call, legal.raise),
+            (false, 10, Some((40, 1000)))
+        );
+        assert_eq!(game.act(Action::Check), Err(ActionError::CannotCheck));
+        assert_eq!(game.act(Action::Raise(30)), Err(ActionError::TooSmall(40)));
+        assert_eq!(game.act(Action::Bet(60)), Err(ActionError::CannotBet));
+
+        game.act(Action::Call).unwrap();
+        game.act(Action::Check).unwrap();
+        assert_eq!(game.street, Street::Flop);
+        assert_eq!(game.board.len(), 3);
+        assert_eq!(game.to_act(), Some(1));
+        assert_eq!(
+            game.act(Action::Raise(50)),
+            Err(ActionError::NothingToRaise)
+        );
+        game.act(Action::Bet(50)).unwrap();
+        game.act(Action::Raise(150)).unwrap();
+        game.act(Action::Fold).unwrap();
+
+        // The uncalled part of the raise goes back.
+        assert!(game.is_finished());
+        assert_eq!(game.act(Action::Check), Err(ActionError::HandOver));
+        assert_eq!(game.players[0].stack, 1070);
+        assert_eq!(game.players[1].stack, 930);
+    }
+
+    #[test]
+    fn test_game_side_pots() {
+        // Dealt from the left of the button: Bob, Cid, Ann, twice, then the board.
+        let mut game = three_handed([100, 200, 300]);
+        assert_eq!(game.players[0].hole, hole("Ac Ad"));
+        assert_eq!(game.to_act(), Some(0));
+        game.act(Action::Raise(100)).unwrap();
+        game.act(Action::Raise(200)).unwrap();
+        assert_eq!(game.act(Action::Raise(300)), Err(ActionError::CannotRaise));
+        game.act(Action::Call).unwrap();
+
+        // Nobody is left to bet, so the board runs out.
+        assert!(game.is_finished());
+        assert_eq!(game.board, cards("2c 7d 9h 3s 4c"));
+        let pots: Vec<(u64, Vec<usize>)> = game
+            .pots()
+            .iter()
+            .map(|pot| (pot.amount, pot.winners.clone()))
+            .collect();
+        assert_eq!(pots, vec![(300, vec![0]), (200, vec![1])]);
+        let stacks: Vec<u64> = game.players.iter().map(|player| player.stack).collect();
+        assert_eq!(stacks, vec![300, 200, 100]);
+    }
+
+    #[test]
+    fn test_table_play() {
+        let mut table = game::Table::new(6);
+        assert!(table.sit(1, "Ann", 500));
+        assert!(table.sit(4, "Bob", 500));
+        assert!(!table.sit(4, "Cid", 500));
+        table.button = 1;
+
+        // Everyone folds whatever they are asked, so the small blind gives up the pot.
+        let game = table.play(Blinds::new(5, 10, 0), Deck::create(), |_| Action::Fold);
+        assert!(game.is_finished());
+        let stacks: Vec<u64> = table
+            .seats
+            .iter()
+            .flatten()
+            .map(|seat| seat.stack)
+            .collect();
+        assert_eq!(stacks, vec![495, 505]);
+        assert_eq!(table.button, 4);
+        assert_eq!(table.empty_seats(), vec![0, 2, 3, 5]);
+    }
+
+    #[test]
+    fn test_tournament_structure() {
+        let config = "# Turbo\nname = Turbo\nbuy_in = 10\nstack = 1000\nseats = 6\n\
+                      payouts = 65, 35\nlevel_hands = 5\nlevel = 10/20\nlevel = 20/40/5\n";
+        let structure: Structure = config.parse().unwrap();
+        assert_eq!(structure.name, "Turbo");
+        assert_eq!(structure.seats, 6);
+        assert_eq!(structure.length, LevelLength::Hands(5));
+        assert_eq!(structure.levels[1], Blinds::new(20, 40, 5));
+        assert_eq!(structure.level(4, Duration::ZERO), 0);
+        assert_eq!(structure.level(5, Duration::ZERO), 1);
+        assert_eq!(structure.level(500, Duration::ZERO), 1);
+        assert_eq!(structure.prizes(7), vec![46, 24]);
+        assert_eq!(structure.prizes(1), vec![10]);
+
+        let error = "stack = 1000\nlevel = 10\n"
+            .parse::<Structure>()
+            .unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            error.to_string(),
+            "Line 2: Expected a level like \"10/20\" or \"10/20/5\"."
+        );
+        let error = "stack = lots".parse::<Structure>().unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: Invalid number \"lots\".");
+        let error = "stack = 1000\nlevel = 10/20"
+            .parse::<Structure>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Set either level_hands or level_minutes."
+        );
+        let error = "stack = 1000\nlevel_minutes = 0\nlevel = 10/20"
+            .parse::<Structure>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Levels must last at least one hand or one minute."
+        );
+        assert!("colour = red".parse::<Structure>().is_err());
+
+        // Time based levels follow the clock they are given.
+        let timed: Structure = "stack = 1000\nlevel_minutes = 15\nlevel = 10/20\nlevel = 15/30"
+            .parse()
+            .unwrap();
+        let clock = ManualClock::new();
+        let tournament = Tournament::with_clock(timed, &["Ann", "Bob"], 0, Box::new(clock.clone()));
+        assert_eq!(tournament.blinds(), Blinds::new(10, 20, 0));
+        clock.advance(Duration::from_secs(15 * 60));
+        assert_eq!(tournament.level(), 1);
+        assert_eq!(tournament.blinds(), Blinds::new(15, 30, 0));
+    }
+
+    #[test]
+    fn test_tournament_balance() {
+        let structure: Structure = "stack = 1000\nseats = 6\nlevel_hands = 10\nlevel = 10/20"
+            .parse()
+            .unwrap();
+        let names = [
+            "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+        ];
+        let mut tournament = Tournament::new(structure, &names, 1);
+        let players: Vec<usize> = tournament
+            .tables
+            .iter()
+            .map(|table| table.players())
+            .collect();
+        assert_eq!(players, vec![5, 4, 4]);
+        assert!(tournament.balance().is_empty());
+
+        // Four players leave the first table, whose last player then moves.
+        for seat in 0..4 {
+            tournament.tables[0].leave(seat).unwrap();
         }
+        let moves = tournament.balance();
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].from, (0, 4));
+        let players: Vec<usize> = tournament
+            .tables
+            .iter()
+            .map(|table| table.players())
+            .collect();
+        assert_eq!(players, vec![5, 4]);
+
+        // Uneven tables are evened out one player at a time.
+        let seat = tournament.tables[0].empty_seats()[0];
+        tournament.tables[0].sit(seat, "N", 1000);
+        let seated: Vec<usize> = (0..6)
+            .filter(|seat| tournament.tables[1].seats[*seat].is_some())
+            .collect();
+        for seat in &seated[..3] {
+            tournament.tables[1].leave(*seat);
+        }
+        let moves = tournament.balance();
+        assert_eq!(moves.len(), 2);
+        let players: Vec<usize> = tournament
+            .tables
+            .iter()
+            .map(|table| table.players())
+            .collect();
+        assert_eq!(players, vec![4, 3]);
+    }
+
+    #[test]
+    fn test_tournament_play() {
+        let structure: Structure =
+            "buy_in = 100\nstack = 500\nseats = 4\npayouts = 50, 30, 20\nlevel_hands = 3\n\
+             level = 10/20\nlevel = 25/50/5\nlevel = 50/100/10"
+                .parse()
+                .unwrap();
+        let names = ["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"];
+        let mut tournament = Tournament::new(structure, &names, 7);
+        assert_eq!(tournament.tables.len(), 3);
+
+        // Everyone moves all in whenever allowed.
+        let finishes = tournament.play(|game| {
+            let legal = game.legal().unwrap();
+            match legal.raise {
+                Some((_, max)) if game.current_bet() == 0 => Action::Bet(max),
+                Some((_, max)) => Action::Raise(max),
+                None if legal.check => Action::Check,
+                None => Action::Call,
+            }
+        });
+        assert_eq!(finishes.len(), 10);
+        let mut places: Vec<usize> = finishes.iter().map(|finish| finish.place).collect();
+        places.sort();
+        assert_eq!(places, (1..=10).collect::<Vec<usize>>());
+        assert_eq!(finishes.last().unwrap().place, 1);
+        assert_eq!(
+            finishes.iter().map(|finish| finish.prize).sum::<u64>(),
+            1000
+        );
+        assert_eq!(finishes.last().unwrap().prize, 500);
+
+        assert!(tournament.is_finished());
+        assert_eq!(tournament.remaining(), 1);
+        let winner = tournament
+            .tables
+            .iter()
+            .flat_map(|table| table.seats.iter().flatten())
+            .next();
+        assert_eq!(winner.unwrap().stack, 5000);
+    }
+
+    #[test]
+    fn test_bot_view() {
+        let deck = stacked("Ah 7c Kd Ad 2s Kc");
+        let players = vec![
+            (0, String::from("Ann"), 1000),
+            (3, String::from("Bob"), 1000),
+            (5, String::from("Cid"), 1000),
+        ];
+        let mut game = Game::new(players, 0, Blinds::new(10, 20, 0), deck);
+        let view = View::of(&game).unwrap();
+        assert_eq!(view.index, 0);
+        assert_eq!(view.hole, hole("Kd Kc"));
+        assert_eq!((view.pot, view.current_bet, view.opponents()), (30, 20, 2));
+        assert_eq!(view.players[2].seat, 5);
+        assert_eq!(view.raise_to(5000), Action::Raise(1000));
+        assert_eq!(view.raise_to(0), Action::Raise(40));
+        assert_eq!(view.pot_raise(1.0), Action::Raise(70));
+
+        // The tight player raises kings and folds seven deuce to a re-raise.
+        let mut tight = TightAggressive::new(0);
+        assert_eq!(tight.act(&view), Action::Raise(60));
+        game.act(Action::Raise(60)).unwrap();
+        let view = View::of(&game).unwrap();
+        assert_eq!(view.hole, hole("Ah Ad"));
+        assert_eq!(tight.act(&view), Action::Raise(180));
+        game.act(Action::Raise(180)).unwrap();
+        let view = View::of(&game).unwrap();
+        assert_eq!(view.hole, hole("7c 2s"));
+        assert_eq!(tight.act(&view), Action::Fold);
+        assert_eq!(CallingStation.act(&view), Action::Call);
+        assert!((view.pot_odds() - 160.0 / 420.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hand_strength_bot() {
+        let mut bot = HandStrength::new(4000, 3);
+        let aces = bot.equity(&hole("As Ah"), &[], 1);
+        assert!((aces - 0.852).abs() < 0.03, "{}", aces);
+        let against_three = bot.equity(&hole("As Ah"), &[], 3);
+        assert!(against_three < aces && against_three > 0.5);
+        let nuts = bot.equity(&hole("Ah Kh"), &cards("Qh Jh Th 2c 3d"), 4);
+        assert!((nuts - 1.0).abs() < 1e-9);
+
+        // Bots of every kind play a session without breaking the rules of the table.
+        let mut table = game::Table::new(4);
+        for (seat, name) in ["Random", "Station", "Tight", "Strength"]
+            .iter()
+            .enumerate()
+        {
+            table.sit(seat, name, 2000);
+        }
+        let mut strategies: Vec<Box<dyn Strategy>> = vec![
+            Box::new(RandomBot::new(1)),
+            Box::new(CallingStation),
+            Box::new(TightAggressive::new(2)),
+            Box::new(HandStrength::new(200, 3)),
+        ];
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..40 {
+            if table
+                .seats
+                .iter()
+                .flatten()
+                .filter(|seat| seat.stack > 0)
+                .count()
+                < 2
+            {
+                break;
+            }
+            let mut deck = Deck::create();
+            deck.cards.shuffle(&mut rng);
+            let game = bot::play(&mut table, Blinds::new(10, 20, 0), deck, &mut strategies);
+            assert!(game.is_finished());
+            let total: u64 = table.seats.iter().flatten().map(|seat| seat.stack).sum();
+            assert_eq!(total, 8000);
+        }
+    }
+
+    #[test]
+    fn test_arena_match() {
+        let station = Entrant::new("Station", |_| Box::new(CallingStation));
+        let mirror = Entrant::new("Mirror", |_| Box::new(CallingStation));
+        let mut settings = Match::new(200, 4);
+        assert_eq!(settings.deck(7), settings.deck(7));
+        assert_ne!(settings.deck(7), settings.deck(8));
+
+        // Duplicate dealing cancels the cards completely for identical bots.
+        settings.duplicate = true;
+        let result = settings.play(&station, &mirror);
+        assert_eq!(result.hands, 400);
+        assert_eq!((result.bb_per_100, result.error), (0.0, 0.0));
+        assert_eq!(result.score(), 0.5);
+
+        settings.duplicate = false;
+        let result = settings.play(&station, &mirror);
+        assert!(result.error > 0.0);
+        assert_eq!(result, settings.play(&station, &mirror));
+
+        let random = Entrant::new("Random", |seed| Box::new(RandomBot::new(seed)));
+        let tight = Entrant::new("Tight", |seed| Box::new(TightAggressive::new(seed)));
+        settings.duplicate = true;
+        settings.hands = 1000;
+        let (results, leaderboard) = round_robin(&[random, tight, station], &settings);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].score(), 1.0, "{}", results[2]);
+        assert_eq!(leaderboard.standings()[0].name, "Tight");
+    }
+
+    #[test]
+    fn test_elo() {
+        assert!((Leaderboard::expected(1600.0, 1400.0) - 0.7597).abs() < 1e-4);
+        let mut leaderboard = Leaderboard::new(&["A"]);
+        let result = MatchResult {
+            players: [String::from("A"), String::from("B")],
+            hands: 1000,
+            bb_per_100: 12.0,
+            error: 5.0,
+        };
+        assert_eq!(result.interval(2.0), (2.0, 22.0));
+        leaderboard.record(&result);
+        assert_eq!(leaderboard.ratings[1].name, "B");
+        assert_eq!(leaderboard.ratings[0].elo, 1516.0);
+        assert_eq!(leaderboard.ratings[1].elo, 1484.0);
+        assert_eq!(leaderboard.ratings[1].bb_per_100(), -12.0);
+        assert_eq!(leaderboard.ratings[1].losses, 1);
+    }
+
+    #[test]
+    fn test_kuhn_cfr() {
+        let kuhn = Kuhn::new();
+        assert_eq!(kuhn.outcomes(&kuhn.root()).len(), 6);
+
+        // The equilibrium with the first player never betting.
+        let (check, bet, fold, call) = (Action::Check, Action::Bet(1), Action::Fold, Action::Call);
+        let mut nash = Profile::default();
+        for (info_set, first, second, probability) in [
+            ("J ", check, bet, 1.0),
+            ("Q ", check, bet, 1.0),
+            ("K ", check, bet, 1.0),
+            ("J k", check, bet, 2.0 / 3.0),
+            ("Q k", check, bet, 1.0),
+            ("K k", check, bet, 0.0),
+            ("J b", fold, call, 1.0),
+            ("Q b", fold, call, 2.0 / 3.0),
+            ("K b", fold, call, 0.0),
+            ("J kb", fold, call, 1.0),
+            ("Q kb", fold, call, 2.0 / 3.0),
+            ("K kb", fold, call, 0.0),
+        ] {
+            nash.strategies.insert(
+                info_set.to_string(),
+                vec![(first, probability), (second, 1.0 - probability)],
+            );
+        }
+        assert!((expected_values(&kuhn, &nash)[0] + 1.0 / 18.0).abs() < 1e-9);
+        assert!(exploitability(&kuhn, &nash).abs() < 1e-9);
+        // Random play is easy to beat.
+        assert!(exploitability(&kuhn, &Profile::default()) > 0.4);
+
+        let mut solver = Solver::cfr(Kuhn::new());
+        solver.train(1000);
+        assert_eq!(solver.info_sets(), 12);
+        assert!(exploitability(&solver.game, &solver.average_strategy()) < 0.005);
+
+        let mut solver = Solver::cfr_plus(Kuhn::new());
+        solver.train(1000);
+        let profile = solver.average_strategy();
+        assert!(exploitability(&solver.game, &profile) < 0.001);
+        assert!((expected_values(&solver.game, &profile)[0] + 1.0 / 18.0).abs() < 0.001);
+        // Kings always call a bet and jacks always fold to one.
+        assert!(profile.probability("K b", call).unwrap() > 0.99);
+        assert!(profile.probability("J b", fold).unwrap() > 0.99);
+    }
+
+    #[test]
+    fn test_leduc_cfr() {
+        let leduc = Leduc::new();
+        let mut state = leduc.outcomes(&leduc.root())[0].0.clone();
+        assert_eq!(state.hole, cards("Js Jh"));
+        assert_eq!(leduc.actions(&state), vec![Action::Check, Action::Bet(2)]);
+        for action in [Action::Bet(2), Action::Raise(4)] {
+            state = leduc.apply(&state, action);
+        }
+        assert_eq!(leduc.actions(&state), vec![Action::Fold, Action::Call]);
+        state = leduc.apply(&state, Action::Call);
+        assert!(leduc.is_chance(&state));
+        let outcomes = leduc.outcomes(&state);
+        assert_eq!(outcomes.len(), 4);
+
+        // Pairing the queen on the board beats nothing, both jacks split.
+        let mut state = outcomes[0].0.clone();
+        assert_eq!(state.board, Some(cards("Qs")[0]));
+        assert_eq!(leduc.info_set(&state), "JQ brc/");
+        for action in [Action::Check, Action::Bet(4), Action::Call] {
+            state = leduc.apply(&state, action);
+        }
+        assert!(leduc.is_terminal(&state));
+        assert_eq!(state.committed, [9, 9]);
+        assert_eq!(leduc.utility(&state, 0), 0.0);
+
+        let mut solver = Solver::cfr_plus(leduc);
+        solver.train(100);
+        assert_eq!(solver.info_sets(), 288);
+        let profile = solver.average_strategy();
+        assert!(exploitability(&solver.game, &profile) < 0.02);
+        assert!((expected_values(&solver.game, &profile)[0] + 0.0856).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_river_solver() {
+        // Bluff catchers against a polarized range with a pot sized bet.
+        let river = River {
+            board: cards("As Kd 7c 4h 2s"),
+            ranges: ["KK".parse().unwrap(), "AA, 33".parse().unwrap()],
+            pot: 100,
+            stack: 100,
+            bet_sizes: vec![1.0],
+            raise_sizes: vec![],
+            all_in: false,
+            max_raises: 0,
+        };
+        let mut solver = RiverSolver::new(river);
+        assert_eq!(solver.tree.len(), 9);
+        assert_eq!(solver.combos(1).len(), 9);
+        let bet = solver.tree[1].children[1];
+        assert_eq!(solver.tree[bet].actions, vec![Action::Fold, Action::Call]);
+
+        let exploitability = solver.solve(0.001, 2000);
+        assert!(exploitability <= 0.1);
+        let values = solver.values();
+        assert!((values[0] + values[1] - 100.0).abs() < 1e-6);
+
+        // A third of the bets are bluffs, and the bluff catchers call half the time.
+        for combo in solver.strategy(1) {
+            let bets = combo.frequencies[1].1;
+            if combo.hole[0].quantity == Quantity::Ace {
+                assert!(bets > 0.99);
+                assert!((combo.ev.unwrap() - 150.0).abs() < 0.5);
+            } else {
+                assert!((bets - 0.25).abs() < 0.02, "{}", bets);
+                assert!(combo.ev.unwrap().abs() < 0.5);
+            }
+        }
+        for combo in solver.strategy(bet) {
+            assert!((combo.frequencies[1].1 - 0.5).abs() < 0.05);
+        }
+
+        let river = River {
+            board: cards("Qs Jh 8d 5c 2s"),
+            ranges: [
+                "22+, A2s+, K9s+, QTs+, JTs, ATo+, KTo+".parse().unwrap(),
+                "22+, A8s+, KTs+, QTs+, T9s, 98s, AJo+, KQo"
+                    .parse()
+                    .unwrap(),
+            ],
+            pot: 60,
+            stack: 150,
+            bet_sizes: vec![0.5, 1.0],
+            raise_sizes: vec![1.0],
+            all_in: true,
+            max_raises: 2,
+        };
+        let mut solver = RiverSolver::new(river);
+        let exploitability = solver.solve(0.005, 1000);
+        assert!(exploitability <= 0.3, "{}", exploitability);
+        assert!(solver.best_response(0) >= solver.values()[0] - 1e-9);
+    }
+
+    #[test]
+    fn test_hand_history() {
+        let mut game = three_handed([100, 200, 300]);
+        game.act(Action::Raise(100)).unwrap();
+        game.act(Action::Raise(200)).unwrap();
+        game.act(Action::Call).unwrap();
+        assert_eq!(
+            game.events()[..3],
+            [
+                Event::SmallBlind {
+                    player: 1,
+                    amount: 5
+                },
+                Event::BigBlind {
+                    player: 2,
+                    amount: 10
+                },
+                Event::Act {
+                    player: 0,
+                    action: Action::Raise(100),
+                    amount: 100,
+                    all_in: true
+                },
+            ]
+        );
+
+        let mut history = HandHistory::of(&game);
+        history.id = 42;
+        history.table = String::from("Oak");
+        history.time = 1709317800;
+        history.hero = Some(1);
+        assert_eq!(history.players[2].stack, 300);
+        assert_eq!(history.pots(), vec![300, 200]);
+        let text = history.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[..3],
+            [
+                "PokerStars Hand #42:  Hold'em No Limit (5/10) - 2024/03/01 18:30:00 UTC",
+                "Table 'Oak' 3-max Seat #1 is the button",
+                "Seat 1: Ann (100 in chips)",
+            ]
+        );
+        for line in [
+            "Dealt to Bob [Kc Kd]",
+            "Ann: raises 90 to 100 and is all-in",
+            "Cid: calls 190",
+            "*** RIVER *** [2c 7d 9h 3s] [4c]",
+            "Ann: shows [Ac Ad] (Pair of Aces with a Nine kicker)",
+            "Bob collected 200 from side pot-1",
+            "Total pot 500 Main pot 300. Side pot-1 200. | Rake 0",
+            "Seat 3: Cid (big blind) showed [Qc Qd] and lost with Pair of Queens with a Nine kicker",
+        ] {
+            assert!(lines.contains(&line), "{}", line);
+        }
+        // Only the hero's cards are dealt face up, the others are shown at showdown.
+        assert!(!text.contains("Dealt to Ann"));
+
+        // A player who lost without showing mucked.
+        history
+            .events
+            .retain(|event| !matches!(event, Event::Show { player: 2, .. }));
+        assert!(history
+            .to_string()
+            .lines()
+            .any(|line| line == "Seat 3: Cid (big blind) mucked"));
+
+        let players = vec![
+            (0, String::from("Ann"), 1000),
+            (1, String::from("Bob"), 1000),
+        ];
+        let mut game = Game::new(players, 0, Blinds::new(10, 20, 0), Deck::create());
+        for action in [
+            Action::Call,
+            Action::Check,
+            Action::Bet(50),
+            Action::Raise(150),
+            Action::Fold,
+        ] {
+            game.act(action).unwrap();
+        }
+        let text = HandHistory::of(&game).to_string();
+        for line in [
+            "Ann: calls 10",
+            "Bob: bets 50",
+            "Ann: raises 100 to 150",
+            "Uncalled bet (100) returned to Ann",
+            "Ann collected 140 from pot",
+            "Total pot 140 | Rake 0",
+            "Seat 1: Ann (button) (small blind) collected (140)",
+            "Seat 2: Bob (big blind) folded on the Flop",
+        ] {
+            assert!(text.lines().any(|other| other == line), "{}", line);
+        }
+        assert!(!text.contains("SHOW DOWN"));
+    }
+
+    #[test]
+    fn test_import_hand_history() {
+        // Exported histories read back the same.
+        let mut game = three_handed([100, 200, 300]);
+        game.players[2].name = String::from("Cid Two");
+        game.act(Action::Raise(100)).unwrap();
+        game.act(Action::Raise(200)).unwrap();
+        game.act(Action::Call).unwrap();
+        let mut history = HandHistory::of(&game);
+        history.time = 1709317800;
+        history.hero = Some(2);
+        let text = format!("{}\n\n{}", history, history);
+        assert_eq!(
+            import::parse(&text).unwrap(),
+            vec![history.clone(), history]
+        );
+
+        let text = "\
+PokerStars Hand #250123456789:  Hold'em No Limit ($0.05/$0.10 USD) - 2024/03/01 13:30:00 ET
+Table 'Alcor II' 6-max Seat #4 is the button
+Seat 1: Hero ($10 in chips)
+Seat 4: vill ain ($12.35 in chips)
+Seat 5: Rex ($9.80 in chips)
+Seat 6: Away ($5 in chips) is sitting out
+Rex: posts small blind $0.05
+Hero: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Hero [Ts 9s]
+vill ain: raises $0.20 to $0.30
+Rex: folds
+Hero: calls $0.20
+*** FLOP *** [8s 7h 2d]
+Hero: checks
+vill ain: bets $0.45
+Hero said, \"nh: bets\"
+Hero: raises $1.05 to $1.50
+vill ain: folds
+Uncalled bet ($1.05) returned to Hero
+Hero collected $1.57 from pot
+Hero: doesn't show hand
+*** SUMMARY ***
+Total pot $1.65 | Rake $0.08
+Board [8s 7h 2d]
+Seat 1: Hero (big blind) collected ($1.57)";
+        let hand: HandHistory = text.parse().unwrap();
+        assert_eq!(hand.id, 250123456789);
+        assert_eq!(hand.table, "Alcor II");
+        assert_eq!((hand.size, hand.button), (6, 1));
+        assert_eq!(hand.blinds, Blinds::new(5, 10, 0));
+        assert_eq!(hand.time, 1709299800);
+        assert_eq!(hand.players[1].name, "vill ain");
+        assert_eq!(hand.players[1].stack, 1235);
+        assert!(!hand.players[1].sitting_out && hand.players[3].sitting_out);
+        assert_eq!(hand.players[0].hole, Some(hole("Ts 9s")));
+        assert_eq!(hand.hero, Some(0));
+        assert_eq!(hand.board, cards("8s 7h 2d"));
+        assert_eq!(
+            hand.events[8..11],
+            [
+                Event::Act {
+                    player: 0,
+                    action: Action::Raise(150),
+                    amount: 150,
+                    all_in: false
+                },
+                Event::Act {
+                    player: 1,
+                    action: Action::Fold,
+                    amount: 0,
+                    all_in: false
+                },
+                Event::Uncalled {
+                    player: 0,
+                    amount: 105
+                },
+            ]
+        );
+        assert_eq!(hand.winnings(), vec![157, 0, 0, 0]);
+        assert!(hand
+            .to_string()
+            .contains("Seat 6: Away (500 in chips) is sitting out\n"));
+
+        let header = "Poker Hand #RC1234: Hold'em No Limit ($0.05/$0.10) - 2021/01/01 00:00:00";
+        let broken = text.replacen(text.lines().next().unwrap(), header, 1);
+        assert_eq!(broken.parse::<HandHistory>().unwrap().id, 1234);
+        let broken = text.replace("[8s 7h 2d]", "[8s 7h 2x]");
+        assert_eq!(
+            broken.parse::<HandHistory>(),
+            Err(ParseError {
+                line: 14,
+                message: String::from("Invalid card \"2x\"."),
+            })
+        );
+        let broken = format!("{}\n\n{}", text, text.replace("Rex: folds", "Rex: limps"));
+        assert_eq!(
+            import::parse(&broken).unwrap_err().to_string(),
+            "Line 39: Unknown action \"limps\"."
+        );
+        let broken = text.replace("Hero: checks", "Zed: checks");
+        assert_eq!(broken.parse::<HandHistory>().unwrap_err().line, 15);
+
+        // Amounts too large for the chip count are errors, not overflows.
+        let broken = text.replace("Hero ($10 in chips)", "Hero ($999999999999999999 in chips)");
+        assert_eq!(
+            broken.parse::<HandHistory>().unwrap_err().to_string(),
+            "Line 3: Invalid amount \"$999999999999999999\"."
+        );
+        let broken = text.replace("Rex: folds", "Rex: calls $184467440737095516.15");
+        assert_eq!(
+            broken.parse::<HandHistory>().unwrap_err().to_string(),
+            "Line 12: Invalid amount \"$184467440737095516.15\"."
+        );
+    }
+
+    #[test]
+    fn test_import_ggpoker() {
+        let text = "\
+Poker Hand #RC1234567890: Hold'em No Limit ($0.02/$0.05) - 2023/05/14 18:22:31
+Table 'RushAndCash12345' 6-max Seat #1 is the button
+Seat 1: 5a3b2c1d ($5.12 in chips)
+Seat 2: Hero ($5 in chips)
+Seat 3: 7f8e9d0c ($4.87 in chips)
+Seat 4: 1a2b3c4d ($6.2 in chips)
+Seat 5: 9e8d7c6b ($5.05 in chips)
+Seat 6: 4d5e6f7a ($2.31 in chips)
+Hero: posts small blind $0.02
+7f8e9d0c: posts big blind $0.05
+*** HOLE CARDS ***
+Dealt to 5a3b2c1d 
+Dealt to Hero [Ah Kd]
+Dealt to 7f8e9d0c 
+Dealt to 1a2b3c4d 
+Dealt to 9e8d7c6b 
+Dealt to 4d5e6f7a 
+1a2b3c4d: folds
+9e8d7c6b: folds
+4d5e6f7a: raises $0.1 to $0.15
+5a3b2c1d: folds
+Hero: raises $0.4 to $0.55
+7f8e9d0c: folds
+4d5e6f7a: calls $0.4
+*** FLOP *** [Ks 7d 2c]
+Hero: bets $0.41
+4d5e6f7a: calls $0.41
+*** TURN *** [Ks 7d 2c] [9h]
+Hero: bets $1.35
+4d5e6f7a: calls $1.35 and is all-in
+*** RIVER *** [Ks 7d 2c 9h] [3s]
+*** SHOWDOWN ***
+Hero: shows [Ah Kd]
+4d5e6f7a: shows [Kc Qc]
+Hero collected $4.5 from pot
+*** SUMMARY ***
+Total pot $4.67 | Rake $0.12 | Jackpot $0.05 | Bingo $0 | Fortune $0 | Tax $0
+Board [Ks 7d 2c 9h 3s]
+Seat 1: 5a3b2c1d (button) folded before Flop
+Seat 2: Hero (small blind) showed [Ah Kd] and won ($4.5) with One pair, Kings
+Seat 3: 7f8e9d0c (big blind) folded before Flop
+Seat 4: 1a2b3c4d folded before Flop
+Seat 5: 9e8d7c6b folded before Flop
+Seat 6: 4d5e6f7a showed [Kc Qc] and lost with One pair, Kings";
+        let hand: HandHistory = text.parse().unwrap();
+        assert_eq!(hand.id, 1234567890);
+        assert_eq!(hand.blinds, Blinds::new(2, 5, 0));
+        assert_eq!(hand.players[3].stack, 620);
+        assert_eq!(hand.hero, Some(1));
+        assert_eq!(hand.players[0].hole, None);
+        assert_eq!(hand.players[5].hole, Some(hole("Kc Qc")));
+        assert_eq!(hand.board, cards("Ks 7d 2c 9h 3s"));
+        assert_eq!(hand.events.len(), 19);
+        assert_eq!(
+            hand.events[16..],
+            [
+                Event::Show {
+                    player: 1,
+                    hole: hole("Ah Kd")
+                },
+                Event::Show {
+                    player: 5,
+                    hole: hole("Kc Qc")
+                },
+                Event::Collect {
+                    player: 1,
+                    amount: 450,
+                    pot: 0
+                },
+            ]
+        );
+        assert!(hand.to_string().contains("*** SHOW DOWN ***"));
+
+        let twice = text
+            .replace("*** FLOP ***", "*** FIRST FLOP ***")
+            .replace("*** SHOWDOWN ***", "*** FIRST SHOWDOWN ***");
+        assert_eq!(
+            twice.parse::<HandHistory>().unwrap_err().to_string(),
+            "Line 25: Hands run twice are not supported."
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let play = |actions: &[Action]| {
+            let mut game = three_handed([1000, 1000, 1000]);
+            for action in actions {
+                game.act(*action).unwrap();
+            }
+            assert!(game.is_finished());
+            HandHistory::of(&game)
+        };
+        // Bob 3-bets the raise of Ann, who folds.
+        let three_bet = play(&[
+            Action::Raise(30),
+            Action::Raise(90),
+            Action::Fold,
+            Action::Fold,
+        ]);
+        // Cid calls the raise of Ann, who bets the flop and wins at showdown.
+        let cbet = play(&[
+            Action::Raise(30),
+            Action::Fold,
+            Action::Call,
+            Action::Check,
+            Action::Bet(40),
+            Action::Call,
+            Action::Check,
+            Action::Check,
+            Action::Check,
+            Action::Check,
+        ]);
+        assert_eq!(
+            Position::of(&cbet),
+            vec![
+                Some(Position::Button),
+                Some(Position::SmallBlind),
+                Some(Position::BigBlind)
+            ]
+        );
+
+        let mut tracker = Tracker::default();
+        tracker.add(&three_bet);
+        tracker.add(&cbet);
+        let ann = tracker.get("Ann").unwrap();
+        assert_eq!((ann.hands, ann.vpip(), ann.pfr()), (2, 100.0, 100.0));
+        assert_eq!((ann.three_bets_faced, ann.fold_to_three_bet()), (1, 100.0));
+        assert_eq!((ann.cbet_chances, ann.cbet()), (1, 100.0));
+        assert_eq!(ann.aggression_factor(), 1.0);
+        assert_eq!((ann.wtsd(), ann.wsd()), (100.0, 100.0));
+        assert_eq!((ann.net, ann.bb_per_100()), (45, 225.0));
+        let bob = tracker.get("Bob").unwrap();
+        assert_eq!(
+            (bob.vpip(), bob.three_bet_chances, bob.three_bet()),
+            (50.0, 2, 50.0)
+        );
+        assert_eq!(bob.net, 35);
+        let cid = tracker.get("Cid").unwrap();
+        assert_eq!((cid.vpip, cid.three_bet_chances, cid.calls), (1, 1, 1));
+        assert_eq!((cid.showdowns, cid.wsd(), cid.net), (1, 0.0, -80));
+        assert_eq!(tracker.standings()[0].0, "Ann");
+
+        let hands = vec![three_bet.clone(), cbet.clone(), three_bet, cbet];
+        let filter = Filter {
+            positions: vec![Position::Button],
+            big_blind: None,
+        };
+        let button = stats::track(&hands, &filter, 3);
+        assert_eq!(button.players.len(), 1);
+        assert_eq!(button.get("Ann").unwrap().hands, 4);
+        let all = stats::track(&hands, &Filter::default(), 4);
+        assert_eq!(all.get("Bob").unwrap().net, 70);
+        let filter = Filter {
+            positions: Vec::new(),
+            big_blind: Some(20),
+        };
+        assert!(stats::track(&hands, &filter, 2).players.is_empty());
+    }
+
+    #[test]
+    fn test_stats_sitting_out() {
+        // Away has chips but sits out, Rex folds to the flop bet of Hero.
+        let text = "\
+PokerStars Hand #250000000001:  Hold'em No Limit ($0.05/$0.10 USD) - 2024/03/01 13:30:00 ET
+Table 'Alcor II' 6-max Seat #1 is the button
+Seat 1: Hero ($10 in chips)
+Seat 2: Away ($10 in chips) is sitting out
+Seat 3: Rex ($10 in chips)
+Hero: posts small blind $0.05
+Rex: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kh]
+Hero: raises $0.20 to $0.30
+Rex: calls $0.20
+*** FLOP *** [2c 7d 9h]
+Rex: checks
+Hero: bets $0.30
+Rex: folds
+Uncalled bet ($0.30) returned to Hero
+Hero collected $0.57 from pot
+Hero: doesn't show hand
+*** SUMMARY ***
+Total pot $0.60 | Rake $0.03
+Board [2c 7d 9h]
+Seat 1: Hero (button) (small blind) collected ($0.57)
+Seat 3: Rex (big blind) folded on the Flop";
+        let hand: HandHistory = text.parse().unwrap();
+        assert_eq!(
+            Position::of(&hand),
+            vec![Some(Position::SmallBlind), None, Some(Position::BigBlind)]
+        );
+
+        let mut tracker = Tracker::default();
+        tracker.add(&hand);
+        assert!(tracker.get("Away").is_none());
+        let hero = tracker.get("Hero").unwrap();
+        assert_eq!((hero.hands, hero.flops, hero.cbets), (1, 1, 1));
+        assert_eq!((hero.showdowns, hero.showdowns_won), (0, 0));
+        let rex = tracker.get("Rex").unwrap();
+        assert_eq!((rex.flops, rex.showdowns), (1, 0));
+
+        // Rex checks to the river and mucks, which still counts as a showdown.
+        let (start, _) = text.split_once("*** FLOP ***").unwrap();
+        let mucked: HandHistory = format!(
+            "{}\
+*** FLOP *** [2c 7d 9h]
+Rex: checks
+Hero: checks
+*** TURN *** [2c 7d 9h] [3s]
+Rex: checks
+Hero: checks
+*** RIVER *** [2c 7d 9h 3s] [4c]
+Rex: checks
+Hero: checks
+*** SHOW DOWN ***
+Hero: shows [Ah Kh] (high card Ace)
+Rex: mucks hand
+Hero collected $0.57 from pot
+*** SUMMARY ***
+Total pot $0.60 | Rake $0.03
+Board [2c 7d 9h 3s 4c]
+Seat 1: Hero (button) (small blind) showed [Ah Kh] and won ($0.57) with high card Ace
+Seat 3: Rex (big blind) mucked",
+            start
+        )
+        .parse()
+        .unwrap();
+        let mut tracker = Tracker::default();
+        tracker.add(&mucked);
+        let hero = tracker.get("Hero").unwrap();
+        assert_eq!((hero.showdowns, hero.showdowns_won), (1, 1));
+        let rex = tracker.get("Rex").unwrap();
+        assert_eq!((rex.flops, rex.showdowns, rex.showdowns_won), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_replayer() {
+        let mut game = three_handed([1000, 1000, 1000]);
+        for action in [
+            Action::Raise(30),
+            Action::Fold,
+            Action::Call,
+            Action::Check,
+            Action::Bet(40),
+            Action::Call,
+            Action::Check,
+            Action::Check,
+            Action::Check,
+            Action::Check,
+        ] {
+            game.act(action).unwrap();
+        }
+        let mut replayer = Replayer::new(HandHistory::of(&game));
+        assert_eq!(replayer.steps(), game.events().len() + 1);
+        assert_eq!(replayer.event(), None);
+        assert!(!replayer.back());
+
+        // After the blinds Ann acts first.
+        replayer.seek(2);
+        let state = replayer.state();
+        assert_eq!((state.pot, state.to_act), (15, Some(0)));
+        assert_eq!(state.players[2].stack, 990);
+        let preflop = state.players[0].equity.unwrap();
+        assert!(preflop > 0.6 && preflop < 0.75);
+
+        replayer.forward();
+        replayer.forward();
+        assert!(replayer.state().players[1].folded);
+        assert_eq!(replayer.state().players[1].equity, None);
+        replayer.forward();
+        replayer.forward();
+        let state = replayer.state();
+        assert_eq!(state.street, Street::Flop);
+        assert_eq!((state.pot, state.to_act), (65, Some(2)));
+        assert_eq!(state.board, cards("2c 7d 9h"));
+        let equities: Vec<f64> = [0, 2]
+            .iter()
+            .map(|index| state.players[*index].equity.unwrap())
+            .collect();
+        assert!((equities[0] + equities[1] - 1.0).abs() < 1e-9);
+        assert!(equities[0] > 0.9);
+        assert_eq!(
+            replayer.describe(replayer.event().unwrap()),
+            replayer.to_string().lines().last().unwrap()
+        );
+        assert!(replayer.back());
+        assert_eq!(replayer.state().street, Street::Preflop);
+
+        replayer.seek(usize::MAX);
+        assert!(replayer.is_at_end());
+        assert!(!replayer.forward());
+        let stacks: Vec<u64> = replayer
+            .state()
+            .players
+            .iter()
+            .map(|player| player.stack)
+            .collect();
+        let expected: Vec<u64> = game.players.iter().map(|player| player.stack).collect();
+        assert_eq!(stacks, expected);
+        assert_eq!(replayer.state().pot, 0);
+        assert!(replayer.state().players[0].shown);
+    }
+
+    #[test]
+    fn test_replay_imported_hand() {
+        let hand: HandHistory = "\
+PokerStars Hand #300000000001: Tournament #1, $1+$0 USD Hold'em No Limit - Level I (10/20) - 2024/03/01 13:30:00 ET
+Table '1 1' 4-max Seat #1 is the button
+Seat 1: Hero (1000 in chips)
+Seat 2: Short (300 in chips)
+Seat 3: Deep (2000 in chips)
+Seat 4: Away (1500 in chips) is sitting out
+Short: posts small blind 10
+Deep: posts big blind 20
+*** HOLE CARDS ***
+Dealt to Hero [Ah Ad]
+Hero: raises 80 to 100
+Short: raises 200 to 300 and is all-in
+Deep: raises 1700 to 2000 and is all-in
+Hero: calls 900 and is all-in
+Uncalled bet (1000) returned to Deep
+*** FLOP *** [2c 7d 9h]
+*** TURN *** [2c 7d 9h] [3s]
+*** RIVER *** [2c 7d 9h 3s] [Kc]
+*** SHOW DOWN ***
+Hero: shows [Ah Ad] (a pair of Aces)
+Short: shows [Kd Ks] (three of a kind, Kings)
+Deep: shows [Qc Qd] (a pair of Queens)
+Hero collected 1400 from side pot
+Short collected 900 from main pot
+*** SUMMARY ***
+Total pot 2300 Main pot 900. Side pot 1400. | Rake 0
+Board [2c 7d 9h 3s Kc]
+Seat 1: Hero (button) showed [Ah Ad] and won (1400) with a pair of Aces
+Seat 2: Short (small blind) showed [Kd Ks] and won (900) with three of a kind, Kings
+Seat 3: Deep (big blind) showed [Qc Qd] and lost with a pair of Queens"
+            .parse()
+            .unwrap();
+        let mut replayer = Replayer::new(hand);
+        assert_eq!(replayer.steps(), 16);
+
+        // Only the cards dealt to the hero are seen, but every recorded hand counts in the equities.
+        let holes = |replayer: &Replayer| -> Vec<Option<[Card; 2]>> {
+            replayer
+                .state()
+                .players
+                .iter()
+                .map(|player| player.hole)
+                .collect()
+        };
+        assert_eq!(
+            holes(&replayer),
+            vec![Some(hole("Ah Ad")), None, None, None]
+        );
+        let state = replayer.state();
+        assert!(state.players[3].folded && state.players[3].equity.is_none());
+        let aces = state.players[0].equity.unwrap();
+        assert!(aces > 0.55 && aces < 0.75);
+        let text = replayer.to_string();
+        assert!(text.contains("?? ??") && text.contains("sitting out"));
+
+        // Deep still has to act on the all-in of Short.
+        replayer.seek(4);
+        assert_eq!(replayer.state().players[1].hole, None);
+
+        // Once Hero calls nobody can bet, the uncalled part of the raise of Deep goes back.
+        replayer.seek(7);
+        let state = replayer.state();
+        assert_eq!((state.pot, state.players[2].stack), (2300, 1000));
+        assert!(!state.players[2].all_in && state.players[1].all_in);
+        assert_eq!(
+            holes(&replayer),
+            vec![
+                Some(hole("Ah Ad")),
+                Some(hole("Kd Ks")),
+                Some(hole("Qc Qd")),
+                None
+            ]
+        );
+        assert!(!replayer.state().players[1].shown);
+
+        // The set of kings on the river wins the hand, though Hero wins the side pot.
+        replayer.seek(10);
+        let state = replayer.state();
+        assert_eq!(state.street, Street::River);
+        let equities: Vec<Option<f64>> = state.players.iter().map(|player| player.equity).collect();
+        assert_eq!(equities, vec![Some(0.0), Some(1.0), Some(0.0), None]);
+
+        replayer.seek(12);
+        assert!(replayer.state().players[1].shown && !replayer.state().players[2].shown);
+
+        replayer.seek(replayer.steps());
+        let state = replayer.state();
+        let stacks: Vec<u64> = state.players.iter().map(|player| player.stack).collect();
+        assert_eq!(stacks, vec![1400, 900, 1000, 1500]);
+        assert_eq!(state.pot, 0);
+    }
+
+    /// Ann on the button, Bob and Cid in the blinds of 5 and 10, dealt aces, kings
+    /// and queens of clubs and diamonds with a board of 2c 7d 9h 3s 4c.
+    fn three_handed(stacks: [u64; 3]) -> Game {
+        let players = ["Ann", "Bob", "Cid"]
+            .iter()
+            .zip(stacks)
+            .enumerate()
+            .map(|(seat, (name, stack))| (seat, name.to_string(), stack))
+            .collect();
+        let deck = stacked("Kc Qc Ac Kd Qd Ad 2c 7d 9h 3s 4c");
+        Game::new(players, 0, Blinds::new(5, 10, 0), deck)
+    }
+
+    /// A deck that deals the given cards first.
+    fn stacked(text: &str) -> Deck {
+        let mut deal = cards(text);
+        let mut deck = Deck::create();
+        deck.cards.retain(|card| !deal.contains(card));
+        deal.reverse();
+        deck.cards.extend(deal);
+        deck
+    }
+
+    fn hole(text: &str) -> [Card; 2] {
+        let cards = cards(text);
+        [cards[0], cards[1]]
     }
 }
@@ -1,5 +1,34 @@
+pub mod arena;
+pub mod board;
+pub mod bot;
 pub mod card;
+pub mod cfr;
 pub mod deck;
+pub mod describe;
+pub mod equity;
+pub mod evaluator;
+pub mod game;
+pub mod history;
+pub mod icm;
+pub mod import;
+pub mod index;
+pub mod isomorphism;
+pub mod kuhn;
+pub mod leduc;
+pub mod outs;
+pub mod potential;
+pub mod preflop;
+pub mod push_fold;
+pub mod range;
+pub mod ranking;
+pub mod render;
+pub mod replay;
+pub mod river;
 pub mod rules;
-
-mod poker {}
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod stats;
+pub mod three_card;
+pub mod tournament;
+pub mod video;
+pub mod video_strategy;
@@ -0,0 +1,318 @@
+//! Heads-up matches between bots with reproducible decks, and a rating leaderboard.
+//!
+//! Every hand is dealt from a deck shuffled with a seed derived from the match seed,
+//! and stacks are reset before each hand. In duplicate mode every deck is played twice
+//! with the bots swapping seats, so the luck of the cards mostly cancels out.
+//! Results are in big blinds per 100 hands with a normal confidence interval.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::poker::bot::{self, Strategy};
+use crate::poker::deck::Deck;
+use crate::poker::game::{Blinds, Table};
+
+/// A named bot, created afresh from a seed for every match.
+pub struct Entrant {
+    pub name: String,
+    create: Box<dyn Fn(u64) -> Box<dyn Strategy>>,
+}
+
+impl Entrant {
+    pub fn new<F: Fn(u64) -> Box<dyn Strategy> + 'static>(name: &str, create: F) -> Entrant {
+        Entrant {
+            name: name.to_string(),
+            create: Box::new(create),
+        }
+    }
+
+    pub fn create(&self, seed: u64) -> Box<dyn Strategy> {
+        (self.create)(seed)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// Decks to deal, each played twice in duplicate mode.
+    pub hands: usize,
+    pub blinds: Blinds,
+    /// Stack of both players at the start of every hand.
+    pub stack: u64,
+    pub duplicate: bool,
+    pub seed: u64,
+}
+
+impl Match {
+    /// Hands of 100 big blinds deep at 1/2.
+    pub fn new(hands: usize, seed: u64) -> Match {
+        Match {
+            hands,
+            blinds: Blinds::new(1, 2, 0),
+            stack: 200,
+            duplicate: false,
+            seed,
+        }
+    }
+
+    /// The deck of a hand, the same for every match with the same seed.
+    pub fn deck(&self, hand: usize) -> Deck {
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(hand as u64));
+        let mut deck = Deck::create();
+        deck.cards.shuffle(&mut rng);
+        deck
+    }
+
+    /// Plays the first entrant against the second one.
+    pub fn play(&self, first: &Entrant, second: &Entrant) -> MatchResult {
+        let mut strategies = [first.create(self.seed), second.create(self.seed ^ 1)];
+        let mut samples = Samples::default();
+        for hand in 0..self.hands {
+            let deck = self.deck(hand);
+            let result = if self.duplicate {
+                // The pair of hands with swapped seats is one sample.
+                self.hand(&mut strategies, deck.clone(), false)
+                    + self.hand(&mut strategies, deck, true)
+            } else {
+                self.hand(&mut strategies, deck, hand % 2 == 1)
+            };
+            samples.add(result / self.blinds.big_blind as f64);
+        }
+
+        let per_sample = if self.duplicate { 2.0 } else { 1.0 };
+        MatchResult {
+            players: [first.name.clone(), second.name.clone()],
+            hands: self.hands * per_sample as usize,
+            bb_per_100: samples.mean() / per_sample * 100.0,
+            error: samples.error() / per_sample * 100.0,
+        }
+    }
+
+    /// Chips the first strategy wins in one hand, with the first strategy on the button
+    /// unless `swapped`.
+    fn hand(&self, strategies: &mut [Box<dyn Strategy>; 2], deck: Deck, swapped: bool) -> f64 {
+        let mut table = Table::new(2);
+        table.sit(0, "Button", self.stack);
+        table.sit(1, "Big blind", self.stack);
+        let game = if swapped {
+            strategies.swap(0, 1);
+            let game = bot::play(&mut table, self.blinds, deck, strategies);
+            strategies.swap(0, 1);
+            game
+        } else {
+            bot::play(&mut table, self.blinds, deck, strategies)
+        };
+        let seat = usize::from(swapped);
+        game.players[seat].stack as f64 - self.stack as f64
+    }
+}
+
+/// Mean and spread of the results of a match.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Samples {
+    count: usize,
+    mean: f64,
+    /// Sum of squared differences from the mean.
+    squares: f64,
+}
+
+impl Samples {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (value - self.mean);
+    }
+
+    fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Standard error of the mean.
+    fn error(&self) -> f64 {
+        if self.count < 2 {
+            return 0.0;
+        }
+        (self.squares / (self.count - 1) as f64 / self.count as f64).sqrt()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult {
+    pub players: [String; 2],
+    pub hands: usize,
+    /// Big blinds the first player wins per 100 hands.
+    pub bb_per_100: f64,
+    /// Standard error of `bb_per_100`.
+    pub error: f64,
+}
+
+impl MatchResult {
+    /// Confidence interval of `bb_per_100`, 1.96 standard errors wide for 95%.
+    pub fn interval(&self, deviations: f64) -> (f64, f64) {
+        (
+            self.bb_per_100 - deviations * self.error,
+            self.bb_per_100 + deviations * self.error,
+        )
+    }
+
+    /// Score of the first player: a win when the 95% interval is above zero,
+    /// a loss when it is below and a draw otherwise.
+    pub fn score(&self) -> f64 {
+        let (low, high) = self.interval(1.96);
+        if low > 0.0 {
+            1.0
+        } else if high < 0.0 {
+            0.0
+        } else {
+            0.5
+        }
+    }
+}
+
+impl std::fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} vs {}: {:+.1} ± {:.1} bb/100 over {} hands",
+            self.players[0],
+            self.players[1],
+            self.bb_per_100,
+            1.96 * self.error,
+            self.hands
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rating {
+    pub name: String,
+    pub elo: f64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// Total of the bb/100 of every match, divided by the matches for the average.
+    total: f64,
+}
+
+impl Rating {
+    pub fn matches(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Average bb/100 over the matches played.
+    pub fn bb_per_100(&self) -> f64 {
+        self.total / self.matches().max(1) as f64
+    }
+}
+
+/// Elo ratings updated after every match.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Leaderboard {
+    pub ratings: Vec<Rating>,
+    /// Largest change of a rating after one match.
+    pub k: f64,
+}
+
+impl Leaderboard {
+    pub fn new(names: &[&str]) -> Leaderboard {
+        Leaderboard {
+            ratings: names
+                .iter()
+                .map(|name| Rating {
+                    name: name.to_string(),
+                    elo: 1500.0,
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                    total: 0.0,
+                })
+                .collect(),
+            k: 32.0,
+        }
+    }
+
+    /// Expected score of the first rating against the second one.
+    pub fn expected(first: f64, second: f64) -> f64 {
+        1.0 / (1.0 + 10f64.powf((second - first) / 400.0))
+    }
+
+    /// Records a match, adding players that are not on the board yet.
+    pub fn record(&mut self, result: &MatchResult) {
+        let first = self.position(&result.players[0]);
+        let second = self.position(&result.players[1]);
+        let score = result.score();
+        let expected = Leaderboard::expected(self.ratings[first].elo, self.ratings[second].elo);
+        let change = self.k * (score - expected);
+
+        for (index, score, change, bb_per_100) in [
+            (first, score, change, result.bb_per_100),
+            (second, 1.0 - score, -change, -result.bb_per_100),
+        ] {
+            let rating = &mut self.ratings[index];
+            rating.elo += change;
+            rating.total += bb_per_100;
+            match score {
+                score if score > 0.5 => rating.wins += 1,
+                score if score < 0.5 => rating.losses += 1,
+                _ => rating.draws += 1,
+            }
+        }
+    }
+
+    /// Ratings from the highest.
+    pub fn standings(&self) -> Vec<&Rating> {
+        let mut standings: Vec<&Rating> = self.ratings.iter().collect();
+        standings.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+        standings
+    }
+
+    fn position(&mut self, name: &str) -> usize {
+        match self.ratings.iter().position(|rating| rating.name == name) {
+            Some(position) => position,
+            None => {
+                let mut added = Leaderboard::new(&[name]);
+                self.ratings.append(&mut added.ratings);
+                self.ratings.len() - 1
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (place, rating) in self.standings().iter().enumerate() {
+            writeln!(
+                f,
+                "{:>2}. {:<20} {:>6.0}  {}-{}-{}  {:+.1} bb/100",
+                place + 1,
+                rating.name,
+                rating.elo,
+                rating.wins,
+                rating.draws,
+                rating.losses,
+                rating.bb_per_100()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays every entrant against every other one with the match settings
+/// and returns the results with the leaderboard they make.
+pub fn round_robin(entrants: &[Entrant], settings: &Match) -> (Vec<MatchResult>, Leaderboard) {
+    let names: Vec<&str> = entrants
+        .iter()
+        .map(|entrant| entrant.name.as_str())
+        .collect();
+    let mut leaderboard = Leaderboard::new(&names);
+    let mut results = Vec::new();
+    for first in 0..entrants.len() {
+        for second in first + 1..entrants.len() {
+            let result = settings.play(&entrants[first], &entrants[second]);
+            leaderboard.record(&result);
+            results.push(result);
+        }
+    }
+    (results, leaderboard)
+}
@@ -0,0 +1,178 @@
+//! Texture of a flop, turn or river board.
+
+use std::fmt::Display;
+
+use crate::poker::card::{Card, HoleCards, Quantity};
+use crate::poker::evaluator::{mask, ranks};
+use crate::poker::ranking::Ranking;
+use crate::poker::rules::Category;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    pub fn of(board: &[Card]) -> Option<Street> {
+        match board.len() {
+            0 => Some(Street::Preflop),
+            3 => Some(Street::Flop),
+            4 => Some(Street::Turn),
+            5 => Some(Street::River),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Street::Preflop => "Preflop",
+            Street::Flop => "Flop",
+            Street::Turn => "Turn",
+            Street::River => "River",
+        }
+    }
+}
+
+impl Display for Street {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Cards of the board that share a quantity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    TwoPaired,
+    Trips,
+    FullHouse,
+    Quads,
+}
+
+/// Cards of the board that share a suit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Suits {
+    /// No two cards of the same suit.
+    Rainbow,
+    /// Two cards of a suit and no more.
+    TwoTone,
+    /// Three or more cards of one suit, but not all of them.
+    FlushPossible,
+    /// Every card of the same suit.
+    Monotone,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Texture {
+    pub street: Street,
+    pub pairing: Pairing,
+    pub suits: Suits,
+    /// Most cards of one suit.
+    pub suited: usize,
+    /// Number of different straights that the board allows.
+    pub straights: usize,
+    pub high: Quantity,
+    /// Cards from ten to ace.
+    pub broadway: usize,
+    /// Cards from two to eight.
+    pub low: usize,
+    pub flush_possible: bool,
+    pub flush_completed: bool,
+    pub straight_possible: bool,
+    pub straight_completed: bool,
+    /// Best category any hole cards can make.
+    pub nut_category: Category,
+    /// Every pair of hole cards that makes the best possible hand.
+    pub nuts: Vec<HoleCards>,
+}
+
+impl Texture {
+    /// Panics if the board does not have three to five cards or a card is dealt twice.
+    pub fn of(board: &[Card]) -> Texture {
+        let street = match Street::of(board) {
+            Some(Street::Preflop) | None => panic!("A board has three to five cards."),
+            Some(street) => street,
+        };
+        let board_mask = mask(board);
+        assert_eq!(
+            board_mask.count_ones() as usize,
+            board.len(),
+            "Board has a card dealt twice."
+        );
+
+        let mut counts = [0; 13];
+        let mut suits = [0; 4];
+        for card in board {
+            counts[card.quantity as usize - 2] += 1;
+            suits[card.index() as usize % 4] += 1;
+        }
+        let suited = *suits.iter().max().unwrap();
+        let windows = straight_windows(ranks(board_mask));
+        let nuts = Ranking::new(board).nuts();
+
+        Texture {
+            street,
+            pairing: pairing(&counts),
+            suits: match suited {
+                1 => Suits::Rainbow,
+                2 => Suits::TwoTone,
+                _ if suited == board.len() => Suits::Monotone,
+                _ => Suits::FlushPossible,
+            },
+            suited,
+            straights: windows.iter().filter(|needed| **needed <= 2).count(),
+            high: board.iter().map(|card| card.quantity).max().unwrap(),
+            broadway: board
+                .iter()
+                .filter(|card| card.quantity >= Quantity::Ten)
+                .count(),
+            low: board
+                .iter()
+                .filter(|card| card.quantity <= Quantity::Eight)
+                .count(),
+            flush_possible: suited >= 3,
+            flush_completed: suited >= 5,
+            straight_possible: windows.iter().any(|needed| *needed <= 2),
+            straight_completed: windows.contains(&0),
+            nut_category: nuts[0].category(),
+            nuts: nuts.iter().map(|holding| holding.hole).collect(),
+        }
+    }
+
+    pub fn is_paired(&self) -> bool {
+        self.pairing != Pairing::Unpaired
+    }
+}
+
+fn pairing(counts: &[usize; 13]) -> Pairing {
+    let pairs = counts.iter().filter(|count| **count == 2).count();
+    let trips = counts.iter().filter(|count| **count == 3).count();
+    if counts.contains(&4) {
+        Pairing::Quads
+    } else if trips > 0 && pairs > 0 {
+        Pairing::FullHouse
+    } else if trips > 0 {
+        Pairing::Trips
+    } else if pairs > 1 {
+        Pairing::TwoPaired
+    } else if pairs == 1 {
+        Pairing::Paired
+    } else {
+        Pairing::Unpaired
+    }
+}
+
+/// Number of quantities missing from the board for each of the ten straights, from the wheel up.
+fn straight_windows(ranks: u16) -> [u32; 10] {
+    let mut windows = [0; 10];
+    for (high, needed) in windows.iter_mut().enumerate() {
+        // The straight with the five as its highest card is the wheel.
+        let run: u16 = (0..5).fold(0, |run, i| run | 1 << ((high + 12 + i) % 13));
+        *needed = 5 - (ranks & run).count_ones();
+    }
+    windows
+}
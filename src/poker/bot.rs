@@ -0,0 +1,404 @@
+//! Computer players for the Hold'em game engine.
+//!
+//! A `Strategy` only sees what the player to act could see at the table: its own hole cards,
+//! the board, the stacks and the bets. The reference bots range from random play
+//! to a tight-aggressive player with starting hand charts and one that bets by hand strength.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::poker::board::Street;
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::deck::Deck;
+use crate::poker::evaluator::{category, evaluate, evaluate_mask, mask};
+use crate::poker::game::{Action, Blinds, Game, Legal, Table};
+use crate::poker::index::StartingHand;
+use crate::poker::range::Range;
+use crate::poker::rules::Category;
+
+/// A player as the others see it, without the hole cards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Opponent {
+    pub seat: usize,
+    pub name: String,
+    pub stack: u64,
+    pub bet: u64,
+    pub invested: u64,
+    pub folded: bool,
+}
+
+/// What the player to act knows about the hand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct View {
+    /// Index of the player to act among `players`.
+    pub index: usize,
+    pub hole: HoleCards,
+    pub board: Vec<Card>,
+    pub street: Street,
+    pub blinds: Blinds,
+    pub button: usize,
+    pub pot: u64,
+    pub current_bet: u64,
+    pub legal: Legal,
+    pub players: Vec<Opponent>,
+}
+
+impl View {
+    /// The view of the player to act, `None` when the hand is over.
+    pub fn of(game: &Game) -> Option<View> {
+        let index = game.to_act()?;
+        Some(View {
+            index,
+            hole: game.players[index].hole,
+            board: game.board.clone(),
+            street: game.street,
+            blinds: game.blinds,
+            button: game.button,
+            pot: game.pot(),
+            current_bet: game.current_bet(),
+            legal: game.legal()?,
+            players: game
+                .players
+                .iter()
+                .map(|player| Opponent {
+                    seat: player.seat,
+                    name: player.name.clone(),
+                    stack: player.stack,
+                    bet: player.bet,
+                    invested: player.invested,
+                    folded: player.folded,
+                })
+                .collect(),
+        })
+    }
+
+    /// Chips the player has behind.
+    pub fn stack(&self) -> u64 {
+        self.players[self.index].stack
+    }
+
+    /// Other players still in the hand.
+    pub fn opponents(&self) -> usize {
+        self.players
+            .iter()
+            .enumerate()
+            .filter(|(index, player)| *index != self.index && !player.folded)
+            .count()
+    }
+
+    /// Share of the final pot the player puts in by calling.
+    pub fn pot_odds(&self) -> f64 {
+        let call = self.legal.call as f64;
+        call / (self.pot as f64 + call)
+    }
+
+    /// Checks when possible and folds otherwise.
+    pub fn check_or_fold(&self) -> Action {
+        if self.legal.check {
+            Action::Check
+        } else {
+            Action::Fold
+        }
+    }
+
+    pub fn check_or_call(&self) -> Action {
+        if self.legal.check {
+            Action::Check
+        } else {
+            Action::Call
+        }
+    }
+
+    /// A bet or raise to the total, kept within the legal amounts,
+    /// or a call when raising is not allowed.
+    pub fn raise_to(&self, total: u64) -> Action {
+        match self.legal.raise {
+            Some((min, max)) => {
+                let total = total.clamp(min, max);
+                if self.current_bet == 0 {
+                    Action::Bet(total)
+                } else {
+                    Action::Raise(total)
+                }
+            }
+            None => self.check_or_call(),
+        }
+    }
+
+    /// A bet or raise of a share of the pot after calling.
+    pub fn pot_raise(&self, share: f64) -> Action {
+        let called = self.pot + self.legal.call;
+        let total = self.current_bet + (called as f64 * share).round() as u64;
+        self.raise_to(total)
+    }
+}
+
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    fn act(&mut self, view: &View) -> Action;
+}
+
+/// Plays a hand at the table with the strategy sitting at each seat.
+/// Panics if a player to act has no strategy.
+pub fn play(
+    table: &mut Table,
+    blinds: Blinds,
+    deck: Deck,
+    strategies: &mut [Box<dyn Strategy>],
+) -> Game {
+    table.play(blinds, deck, |game| {
+        let view = View::of(game).unwrap();
+        let seat = view.players[view.index].seat;
+        strategies
+            .get_mut(seat)
+            .unwrap_or_else(|| panic!("No strategy for seat {}.", seat))
+            .act(&view)
+    })
+}
+
+/// Picks any legal action, folding only when there is something to call.
+pub struct RandomBot {
+    rng: StdRng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> RandomBot {
+        RandomBot {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomBot {
+    fn name(&self) -> &str {
+        "Random"
+    }
+
+    fn act(&mut self, view: &View) -> Action {
+        let mut actions = vec![view.check_or_call()];
+        if !view.legal.check {
+            actions.push(Action::Fold);
+        }
+        if let Some((min, max)) = view.legal.raise {
+            actions.push(view.raise_to(self.rng.gen_range(min..=max)));
+        }
+        *actions.choose(&mut self.rng).unwrap()
+    }
+}
+
+/// Never folds and never raises.
+pub struct CallingStation;
+
+impl Strategy for CallingStation {
+    fn name(&self) -> &str {
+        "Calling station"
+    }
+
+    fn act(&mut self, view: &View) -> Action {
+        view.check_or_call()
+    }
+}
+
+/// Plays few starting hands from charts and bets them hard.
+///
+/// Before the flop it raises hands of the opening chart when nobody has raised,
+/// and against a raise re-raises the re-raising chart and calls the calling chart.
+/// Partial weights are played that share of the time. After the flop it bets
+/// two pairs or better and pairs made with a hole card, and gives up otherwise.
+pub struct TightAggressive {
+    pub open: Range,
+    pub call: Range,
+    pub reraise: Range,
+    rng: StdRng,
+}
+
+impl TightAggressive {
+    pub fn new(seed: u64) -> TightAggressive {
+        TightAggressive::with_charts(
+            "22+, A2s+, KTs+, QTs+, JTs, T9s, 98s, ATo+, KJo+, QJo"
+                .parse()
+                .unwrap(),
+            "JJ-77, AQs-AJs, KQs, AQo".parse().unwrap(),
+            "QQ+, AKs, AKo".parse().unwrap(),
+            seed,
+        )
+    }
+
+    pub fn with_charts(open: Range, call: Range, reraise: Range, seed: u64) -> TightAggressive {
+        TightAggressive {
+            open,
+            call,
+            reraise,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn preflop(&mut self, view: &View) -> Action {
+        let hand = StartingHand::of(&view.hole);
+        let big_blind = view.blinds.big_blind;
+        if view.current_bet <= big_blind {
+            let limpers = view
+                .players
+                .iter()
+                .filter(|player| player.bet >= big_blind)
+                .count()
+                .saturating_sub(1) as u64;
+            if plays(&mut self.rng, &self.open, hand) {
+                return view.raise_to(big_blind * (3 + limpers));
+            }
+            return view.check_or_fold();
+        }
+        if plays(&mut self.rng, &self.reraise, hand) {
+            return view.raise_to(view.current_bet * 3);
+        }
+        if plays(&mut self.rng, &self.call, hand) {
+            return view.check_or_call();
+        }
+        view.check_or_fold()
+    }
+}
+
+/// Whether to play a hand of the chart, drawing for partial weights.
+fn plays(rng: &mut StdRng, chart: &Range, hand: StartingHand) -> bool {
+    let weight = chart.weight(hand);
+    weight >= 1.0 || (weight > 0.0 && rng.gen::<f64>() < weight)
+}
+
+impl Strategy for TightAggressive {
+    fn name(&self) -> &str {
+        "Tight aggressive"
+    }
+
+    fn act(&mut self, view: &View) -> Action {
+        if view.street == Street::Preflop {
+            return self.preflop(view);
+        }
+        let made = made_hand(&view.hole, &view.board);
+        if made >= Category::TwoPairs {
+            view.pot_raise(0.75)
+        } else if made == Category::Pair {
+            if view.current_bet == 0 {
+                view.pot_raise(0.5)
+            } else if view.pot_odds() <= 0.3 {
+                view.check_or_call()
+            } else {
+                Action::Fold
+            }
+        } else {
+            view.check_or_fold()
+        }
+    }
+}
+
+/// The category of the best hand, or high card when the board alone is as good.
+fn made_hand(hole: &HoleCards, board: &[Card]) -> Category {
+    if board.is_empty() {
+        return Category::HighCard;
+    }
+    let mut cards = board.to_vec();
+    cards.extend_from_slice(hole);
+    let made = category(evaluate(&cards));
+    if made == board_category(board) {
+        Category::HighCard
+    } else {
+        made
+    }
+}
+
+/// Pairs, trips and quads showing on a board of fewer than five cards.
+fn board_category(board: &[Card]) -> Category {
+    if board.len() >= 5 {
+        return category(evaluate(board));
+    }
+    let mut counts = [0; 13];
+    for card in board {
+        counts[card.quantity as usize - 2] += 1;
+    }
+    let pairs = counts.iter().filter(|count| **count == 2).count();
+    match counts.iter().max().copied().unwrap_or(0) {
+        4 => Category::FourOfAKind,
+        3 if pairs > 0 => Category::FullHouse,
+        3 => Category::ThreeOfAKind,
+        2 if pairs > 1 => Category::TwoPairs,
+        2 => Category::Pair,
+        _ => Category::HighCard,
+    }
+}
+
+/// Bets by its chance of winning against random hands of the opponents still in.
+///
+/// The equity is estimated from random opponent hands and boards. Strong hands,
+/// or any hand with a made two pairs or better, are bet and raised,
+/// and weaker ones continue while the equity beats the pot odds.
+pub struct HandStrength {
+    pub trials: usize,
+    /// Equity from which the bot bets and raises.
+    pub aggression: f64,
+    rng: StdRng,
+}
+
+impl HandStrength {
+    pub fn new(trials: usize, seed: u64) -> HandStrength {
+        HandStrength {
+            trials,
+            aggression: 0.65,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Share of the pots won against the given number of random hands.
+    pub fn equity(&mut self, hole: &HoleCards, board: &[Card], opponents: usize) -> f64 {
+        let mut deck = Deck::create();
+        for card in hole.iter().chain(board.iter()) {
+            assert!(deck.remove(card), "Card {} is dealt twice.", card);
+        }
+        let known = mask(board);
+        let missing = 5 - board.len();
+        let mut share = 0.0;
+        for _ in 0..self.trials.max(1) {
+            let (drawn, _) = deck
+                .cards
+                .partial_shuffle(&mut self.rng, missing + 2 * opponents);
+            let board = known | mask(&drawn[..missing]);
+            let hero = evaluate_mask(board | mask(hole));
+            let mut ties = 1;
+            let mut beaten = false;
+            for hand in drawn[missing..].chunks(2) {
+                let strength = evaluate_mask(board | mask(hand));
+                if strength > hero {
+                    beaten = true;
+                    break;
+                }
+                if strength == hero {
+                    ties += 1;
+                }
+            }
+            if !beaten {
+                share += 1.0 / ties as f64;
+            }
+        }
+        share / self.trials.max(1) as f64
+    }
+}
+
+impl Strategy for HandStrength {
+    fn name(&self) -> &str {
+        "Hand strength"
+    }
+
+    fn act(&mut self, view: &View) -> Action {
+        let equity = self.equity(&view.hole, &view.board, view.opponents().max(1));
+        let made = made_hand(&view.hole, &view.board);
+        if equity >= self.aggression || made >= Category::TwoPairs {
+            view.pot_raise(equity.min(1.0))
+        } else if view.legal.check {
+            Action::Check
+        } else if equity >= view.pot_odds() {
+            Action::Call
+        } else {
+            Action::Fold
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use std::cmp::Ordering;
 use std::fmt::Display;
+use std::str::FromStr;
 
-#[derive(Copy, Clone, Debug, Eq)]
+use crate::poker::render::{style, Render};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Card {
     pub suit: Suit,
     pub quantity: Quantity,
@@ -14,35 +17,86 @@ impl Card {
             quantity: Quantity::Ten,
         }
     }
+
+    /// Number from 0 to 51, ordered by quantity and then by suit.
+    pub fn index(&self) -> u8 {
+        let suit = Suit::all().iter().position(|s| *s == self.suit).unwrap();
+        (self.quantity as u8 - 2) * 4 + suit as u8
+    }
+
+    pub fn from_index(index: u8) -> Option<Card> {
+        if index >= 52 {
+            return None;
+        }
+        Some(Card {
+            quantity: Quantity::all()[index as usize / 4],
+            suit: Suit::all()[index as usize % 4],
+        })
+    }
 }
 
-impl PartialEq for Card {
-    fn eq(&self, other: &Self) -> bool {
-        self.suit == other.suit && self.quantity == other.quantity
+impl Default for Card {
+    fn default() -> Self {
+        Card::new()
     }
 }
 
 impl Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", self.quantity, self.suit)
+        self.render(f, style())
     }
 }
 
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        return self.quantity.partial_cmp(&other.quantity);
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Card {
     fn cmp(&self, other: &Self) -> Ordering {
-        return self.quantity.cmp(&other.quantity);
+        self.quantity.cmp(&other.quantity)
+    }
+}
+
+/// Parses cards written as "As", "Td", "10d" or "Q♥".
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseCardError(s.to_string());
+        let mut chars = s.trim().chars();
+        let suit = chars
+            .next_back()
+            .and_then(Suit::from_letter)
+            .ok_or_else(error)?;
+        let quantity = match chars.as_str() {
+            "10" => Quantity::Ten,
+            symbol if symbol.chars().count() == 1 => {
+                Quantity::from_symbol(symbol.chars().next().unwrap()).ok_or_else(error)?
+            }
+            _ => return Err(error()),
+        };
+        Ok(Card { suit, quantity })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseCardError(pub String);
+
+impl Display for ParseCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid card \"{}\".", self.0)
     }
 }
 
+impl std::error::Error for ParseCardError {}
+
 pub type Hand = [Card; 5];
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub type HoleCards = [Card; 2];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Suit {
     Spades,
     Clubs,
@@ -54,20 +108,41 @@ impl Suit {
     pub fn all() -> &'static [Suit] {
         &[Suit::Spades, Suit::Clubs, Suit::Diamonds, Suit::Hearts]
     }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Spades => '♠',
+            Suit::Clubs => '♣',
+            Suit::Diamonds => '♦',
+            Suit::Hearts => '♥',
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Spades => 's',
+            Suit::Clubs => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Hearts => 'h',
+        }
+    }
+
+    /// Accepts both letters and symbols.
+    pub fn from_letter(letter: char) -> Option<Suit> {
+        Suit::all()
+            .iter()
+            .find(|suit| suit.letter() == letter.to_ascii_lowercase() || suit.symbol() == letter)
+            .copied()
+    }
 }
 
 impl Display for Suit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Suit::Spades => write!(f, "♣"),
-            Suit::Clubs => write!(f, "♠"),
-            Suit::Diamonds => write!(f, "♦"),
-            Suit::Hearts => write!(f, "♥"),
-        }
+        self.render(f, style())
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, Ord, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Quantity {
     Two = 2,
     Three = 3,
@@ -102,6 +177,61 @@ impl Quantity {
             Quantity::Ace,
         ]
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantity::Two => "Two",
+            Quantity::Three => "Three",
+            Quantity::Four => "Four",
+            Quantity::Five => "Five",
+            Quantity::Six => "Six",
+            Quantity::Seven => "Seven",
+            Quantity::Eight => "Eight",
+            Quantity::Nine => "Nine",
+            Quantity::Ten => "Ten",
+            Quantity::Jack => "Jack",
+            Quantity::Queen => "Queen",
+            Quantity::King => "King",
+            Quantity::Ace => "Ace",
+        }
+    }
+
+    pub fn plural(&self) -> &'static str {
+        match self {
+            Quantity::Two => "Twos",
+            Quantity::Three => "Threes",
+            Quantity::Four => "Fours",
+            Quantity::Five => "Fives",
+            Quantity::Six => "Sixes",
+            Quantity::Seven => "Sevens",
+            Quantity::Eight => "Eights",
+            Quantity::Nine => "Nines",
+            Quantity::Ten => "Tens",
+            Quantity::Jack => "Jacks",
+            Quantity::Queen => "Queens",
+            Quantity::King => "Kings",
+            Quantity::Ace => "Aces",
+        }
+    }
+
+    /// Single character notation, where a ten is written as "T".
+    pub fn symbol(&self) -> char {
+        match self {
+            Quantity::Ten => 'T',
+            Quantity::Jack => 'J',
+            Quantity::Queen => 'Q',
+            Quantity::King => 'K',
+            Quantity::Ace => 'A',
+            _ => (b'0' + *self as u8) as char,
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Quantity> {
+        Quantity::all()
+            .iter()
+            .find(|quantity| quantity.symbol() == symbol.to_ascii_uppercase())
+            .copied()
+    }
 }
 
 impl Display for Quantity {
@@ -118,19 +248,3 @@ impl Display for Quantity {
         }
     }
 }
-
-impl PartialOrd for Quantity {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let a = *self as u8;
-        let b = *other as u8;
-        a.partial_cmp(&b)
-    }
-}
-
-impl PartialEq for Quantity {
-    fn eq(&self, other: &Self) -> bool {
-        let a = *self as u8;
-        let b = *other as u8;
-        a == b
-    }
-}
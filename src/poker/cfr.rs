@@ -0,0 +1,380 @@
+//! Counterfactual regret minimization for small two player zero-sum games.
+//!
+//! A game is described by a `GameTree`: chance nodes with the probability of every outcome,
+//! decision nodes grouped into information sets by what the player to act knows,
+//! and terminal nodes with the utility of each player. The solver runs vanilla CFR
+//! or CFR+, whose regrets never go below zero and whose average weighs later iterations more.
+//! The average strategy converges to a Nash equilibrium, and its exploitability
+//! measures how far from one it still is.
+
+use std::collections::HashMap;
+
+use crate::poker::game::Action;
+
+pub trait GameTree {
+    type State: Clone;
+
+    fn root(&self) -> Self::State;
+
+    fn is_terminal(&self, state: &Self::State) -> bool;
+
+    /// Chips won or lost by the player at a terminal state.
+    fn utility(&self, state: &Self::State, player: usize) -> f64;
+
+    fn is_chance(&self, state: &Self::State) -> bool;
+
+    /// States after every outcome of a chance node with their probabilities.
+    fn outcomes(&self, state: &Self::State) -> Vec<(Self::State, f64)>;
+
+    /// The player to act at a decision node, 0 or 1.
+    fn player(&self, state: &Self::State) -> usize;
+
+    fn actions(&self, state: &Self::State) -> Vec<Action>;
+
+    fn apply(&self, state: &Self::State, action: Action) -> Self::State;
+
+    /// Key of the information set: equal for the states the player to act cannot tell apart.
+    fn info_set(&self, state: &Self::State) -> String;
+}
+
+/// A strategy for every information set, as the probability of each action.
+/// Information sets that are missing are played uniformly at random.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    pub strategies: HashMap<String, Vec<(Action, f64)>>,
+}
+
+impl Profile {
+    pub fn get(&self, info_set: &str) -> Option<&[(Action, f64)]> {
+        self.strategies.get(info_set).map(Vec::as_slice)
+    }
+
+    pub fn probability(&self, info_set: &str, action: Action) -> Option<f64> {
+        self.get(info_set)?
+            .iter()
+            .find(|(other, _)| *other == action)
+            .map(|(_, probability)| *probability)
+    }
+
+    /// Probabilities of the actions in the given order.
+    fn distribution(&self, info_set: &str, actions: &[Action]) -> Vec<f64> {
+        let uniform = 1.0 / actions.len() as f64;
+        actions
+            .iter()
+            .map(|action| self.probability(info_set, *action).unwrap_or(uniform))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Node {
+    actions: Vec<Action>,
+    regrets: Vec<f64>,
+    /// Regrets of the current traversal, added once it is over so that
+    /// the strategy stays the same in every state of the information set.
+    pending: Vec<f64>,
+    strategy_sum: Vec<f64>,
+}
+
+impl Node {
+    /// Regret matching: play actions in proportion to their positive regrets.
+    fn strategy(&self) -> Vec<f64> {
+        let positive: f64 = self.regrets.iter().map(|regret| regret.max(0.0)).sum();
+        if positive > 0.0 {
+            self.regrets
+                .iter()
+                .map(|regret| regret.max(0.0) / positive)
+                .collect()
+        } else {
+            vec![1.0 / self.actions.len() as f64; self.actions.len()]
+        }
+    }
+
+    fn average(&self) -> Vec<f64> {
+        let total: f64 = self.strategy_sum.iter().sum();
+        if total > 0.0 {
+            self.strategy_sum.iter().map(|sum| sum / total).collect()
+        } else {
+            vec![1.0 / self.actions.len() as f64; self.actions.len()]
+        }
+    }
+}
+
+pub struct Solver<G: GameTree> {
+    pub game: G,
+    /// Runs CFR+ instead of vanilla CFR.
+    pub plus: bool,
+    nodes: HashMap<String, Node>,
+    iterations: usize,
+}
+
+impl<G: GameTree> Solver<G> {
+    pub fn cfr(game: G) -> Solver<G> {
+        Solver {
+            game,
+            plus: false,
+            nodes: HashMap::new(),
+            iterations: 0,
+        }
+    }
+
+    pub fn cfr_plus(game: G) -> Solver<G> {
+        Solver {
+            plus: true,
+            ..Solver::cfr(game)
+        }
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Information sets reached so far.
+    pub fn info_sets(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Runs iterations, each one updating the two players in turn.
+    pub fn train(&mut self, iterations: usize) {
+        let root = self.game.root();
+        for _ in 0..iterations {
+            self.iterations += 1;
+            for player in 0..2 {
+                self.traverse(&root, player, 1.0, 1.0);
+                for node in self.nodes.values_mut() {
+                    for (regret, pending) in node.regrets.iter_mut().zip(node.pending.iter_mut()) {
+                        *regret += *pending;
+                        if self.plus {
+                            *regret = regret.max(0.0);
+                        }
+                        *pending = 0.0;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The average strategy, which is the one that converges to an equilibrium.
+    pub fn average_strategy(&self) -> Profile {
+        Profile {
+            strategies: self
+                .nodes
+                .iter()
+                .map(|(key, node)| {
+                    let strategy = node.actions.iter().copied().zip(node.average()).collect();
+                    (key.clone(), strategy)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the counterfactual value of the state for the updated player,
+    /// given the reach probability of that player and of everyone else, chance included.
+    fn traverse(&mut self, state: &G::State, player: usize, own: f64, others: f64) -> f64 {
+        if self.game.is_terminal(state) {
+            return self.game.utility(state, player);
+        }
+        if self.game.is_chance(state) {
+            return self
+                .game
+                .outcomes(state)
+                .iter()
+                .map(|(next, probability)| {
+                    probability * self.traverse(next, player, own, others * probability)
+                })
+                .sum();
+        }
+
+        let key = self.game.info_set(state);
+        let actions = self.game.actions(state);
+        let strategy = self
+            .nodes
+            .entry(key.clone())
+            .or_insert_with(|| Node {
+                regrets: vec![0.0; actions.len()],
+                pending: vec![0.0; actions.len()],
+                strategy_sum: vec![0.0; actions.len()],
+                actions: actions.clone(),
+            })
+            .strategy();
+
+        if self.game.player(state) != player {
+            return actions
+                .iter()
+                .zip(strategy.iter())
+                .map(|(action, probability)| {
+                    let next = self.game.apply(state, *action);
+                    probability * self.traverse(&next, player, own, others * probability)
+                })
+                .sum();
+        }
+
+        let values: Vec<f64> = actions
+            .iter()
+            .zip(strategy.iter())
+            .map(|(action, probability)| {
+                let next = self.game.apply(state, *action);
+                self.traverse(&next, player, own * probability, others)
+            })
+            .collect();
+        let value: f64 = values.iter().zip(strategy.iter()).map(|(v, p)| v * p).sum();
+
+        // CFR+ weighs the average by the iteration.
+        let weight = if self.plus {
+            self.iterations as f64
+        } else {
+            1.0
+        };
+        let node = self.nodes.get_mut(&key).unwrap();
+        for (index, action_value) in values.iter().enumerate() {
+            node.pending[index] += others * (action_value - value);
+            node.strategy_sum[index] += weight * own * strategy[index];
+        }
+        value
+    }
+}
+
+/// Expected utility of each player when both play the profile.
+pub fn expected_values<G: GameTree>(game: &G, profile: &Profile) -> [f64; 2] {
+    let value = expected_value(game, profile, &game.root());
+    [value, -value]
+}
+
+fn expected_value<G: GameTree>(game: &G, profile: &Profile, state: &G::State) -> f64 {
+    if game.is_terminal(state) {
+        return game.utility(state, 0);
+    }
+    if game.is_chance(state) {
+        return game
+            .outcomes(state)
+            .iter()
+            .map(|(next, probability)| probability * expected_value(game, profile, next))
+            .sum();
+    }
+    let actions = game.actions(state);
+    let strategy = profile.distribution(&game.info_set(state), &actions);
+    actions
+        .iter()
+        .zip(strategy)
+        .map(|(action, probability)| {
+            probability * expected_value(game, profile, &game.apply(state, *action))
+        })
+        .sum()
+}
+
+/// Utility of the player's best response to the profile of the other player.
+pub fn best_response<G: GameTree>(game: &G, profile: &Profile, player: usize) -> f64 {
+    let mut response = BestResponse {
+        game,
+        profile,
+        player,
+        states: HashMap::new(),
+        choices: HashMap::new(),
+    };
+    let root = game.root();
+    response.collect(&root, 1.0);
+    response.value(&root)
+}
+
+/// How much a best response wins against the profile, averaged over the two seats.
+/// Zero exactly at an equilibrium.
+pub fn exploitability<G: GameTree>(game: &G, profile: &Profile) -> f64 {
+    (best_response(game, profile, 0) + best_response(game, profile, 1)) / 2.0
+}
+
+struct BestResponse<'a, G: GameTree> {
+    game: &'a G,
+    profile: &'a Profile,
+    player: usize,
+    /// States of every information set of the player, with the probability
+    /// that chance and the other player reach them.
+    states: HashMap<String, Vec<(G::State, f64)>>,
+    choices: HashMap<String, Action>,
+}
+
+impl<G: GameTree> BestResponse<'_, G> {
+    fn collect(&mut self, state: &G::State, reach: f64) {
+        if self.game.is_terminal(state) {
+            return;
+        }
+        if self.game.is_chance(state) {
+            for (next, probability) in self.game.outcomes(state) {
+                self.collect(&next, reach * probability);
+            }
+            return;
+        }
+        let actions = self.game.actions(state);
+        let key = self.game.info_set(state);
+        if self.game.player(state) == self.player {
+            self.states
+                .entry(key)
+                .or_default()
+                .push((state.clone(), reach));
+            for action in actions {
+                self.collect(&self.game.apply(state, action), reach);
+            }
+        } else {
+            let strategy = self.profile.distribution(&key, &actions);
+            for (action, probability) in actions.iter().zip(strategy) {
+                self.collect(&self.game.apply(state, *action), reach * probability);
+            }
+        }
+    }
+
+    fn value(&mut self, state: &G::State) -> f64 {
+        if self.game.is_terminal(state) {
+            return self.game.utility(state, self.player);
+        }
+        if self.game.is_chance(state) {
+            return self
+                .game
+                .outcomes(state)
+                .iter()
+                .map(|(next, probability)| probability * self.value(next))
+                .sum();
+        }
+        let actions = self.game.actions(state);
+        let key = self.game.info_set(state);
+        if self.game.player(state) == self.player {
+            let action = self.choose(&key, &actions);
+            return self.value(&self.game.apply(state, action));
+        }
+        let strategy = self.profile.distribution(&key, &actions);
+        actions
+            .iter()
+            .zip(strategy)
+            .map(|(action, probability)| probability * self.value(&self.game.apply(state, *action)))
+            .sum()
+    }
+
+    /// The action with the highest value summed over the states of the information set.
+    fn choose(&mut self, key: &str, actions: &[Action]) -> Action {
+        if let Some(action) = self.choices.get(key) {
+            return *action;
+        }
+        let states = self.states.get(key).cloned().unwrap_or_default();
+        let mut best = (actions[0], f64::NEG_INFINITY);
+        for action in actions {
+            let value: f64 = states
+                .iter()
+                .map(|(state, reach)| reach * self.value(&self.game.apply(state, *action)))
+                .sum();
+            if value > best.1 {
+                best = (*action, value);
+            }
+        }
+        self.choices.insert(key.to_string(), best.0);
+        best.0
+    }
+}
+
+/// Short code of an action for information set keys.
+pub(crate) fn code(action: Action) -> char {
+    match action {
+        Action::Fold => 'f',
+        Action::Check => 'k',
+        Action::Call => 'c',
+        Action::Bet(_) => 'b',
+        Action::Raise(_) => 'r',
+    }
+}
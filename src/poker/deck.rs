@@ -1,7 +1,9 @@
-use crate::poker::card::{Card, Quantity, Suit};
+use crate::poker::card::{Card, Hand, Quantity, Suit};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Deck {
     pub cards: Vec<Card>,
 }
@@ -22,10 +24,42 @@ impl Deck {
         Deck { cards }
     }
 
+    /// Card indices in the dealing order, for storing the deck compactly.
+    pub fn indices(&self) -> Vec<u8> {
+        self.cards.iter().map(Card::index).collect()
+    }
+
+    pub fn from_indices(indices: &[u8]) -> Option<Deck> {
+        let cards: Option<Vec<Card>> = indices
+            .iter()
+            .map(|index| Card::from_index(*index))
+            .collect();
+        cards.map(|cards| Deck { cards })
+    }
+
     pub fn deal(&mut self) -> Option<Card> {
         self.cards.pop()
     }
 
+    pub fn deal_hand(&mut self) -> Option<Hand> {
+        let mut hand: Hand = [Card::new(); 5];
+        for card in hand.iter_mut() {
+            *card = self.deal()?;
+        }
+        Some(hand)
+    }
+
+    /// Takes the card out of the deck, returns false if it was not there.
+    pub fn remove(&mut self, card: &Card) -> bool {
+        match self.cards.iter().position(|c| c == card) {
+            Some(position) => {
+                self.cards.remove(position);
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn shuffle(&mut self) {
         let mut rng = thread_rng();
         self.cards.shuffle(&mut rng);
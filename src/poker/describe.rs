@@ -0,0 +1,109 @@
+use crate::poker::card::{Hand, Quantity};
+use crate::poker::rules::{evaluate, Category};
+
+/// Describes the combination of the hand in words,
+/// e.g. "Full House, Kings full of Sevens" or "Ace-high Flush".
+pub fn describe(hand: &Hand) -> String {
+    let groups = groups(hand);
+    let kicker = |position: usize| groups[position].0;
+
+    match evaluate(hand).category() {
+        Category::FlushRoyal => String::from("Royal Flush"),
+        Category::StraightFlush => format!("Straight Flush, {}", straight_range(hand)),
+        Category::FourOfAKind => format!(
+            "Four of a Kind, {} with {} kicker",
+            kicker(0).plural(),
+            with_article(kicker(1))
+        ),
+        Category::FullHouse => format!(
+            "Full House, {} full of {}",
+            kicker(0).plural(),
+            kicker(1).plural()
+        ),
+        Category::Flush => format!("{}-high Flush", kicker(0).name()),
+        Category::Straight => format!("Straight, {}", straight_range(hand)),
+        Category::ThreeOfAKind => format!(
+            "Three of a Kind, {} with {} kicker",
+            kicker(0).plural(),
+            with_article(kicker(1))
+        ),
+        Category::TwoPairs => format!(
+            "Two Pair, {} and {} with {} kicker",
+            kicker(0).plural(),
+            kicker(1).plural(),
+            with_article(kicker(2))
+        ),
+        Category::Pair => format!(
+            "Pair of {} with {} kicker",
+            kicker(0).plural(),
+            with_article(kicker(1))
+        ),
+        Category::HighCard => {
+            let names: Vec<&str> = groups.iter().map(|(quantity, _)| quantity.name()).collect();
+            format!("High Card, {}", names.join("-"))
+        }
+    }
+}
+
+/// Lists the quantities of the hand from the most significant, e.g. "KKK77" or "AQT84".
+/// Straights start from their highest card, so a wheel is written as "5432A".
+pub fn short(hand: &Hand) -> String {
+    let mut quantities: Vec<Quantity> = Vec::with_capacity(5);
+    for (quantity, count) in groups(hand) {
+        for _ in 0..count {
+            quantities.push(quantity);
+        }
+    }
+    if is_wheel(hand) {
+        quantities.rotate_left(1);
+    }
+    quantities
+        .iter()
+        .map(|quantity| quantity.symbol())
+        .collect()
+}
+
+/// Quantities of the hand with their counts, larger groups and higher quantities first.
+fn groups(hand: &Hand) -> Vec<(Quantity, usize)> {
+    let mut groups: Vec<(Quantity, usize)> = Vec::with_capacity(5);
+    for card in hand {
+        match groups
+            .iter_mut()
+            .find(|(quantity, _)| *quantity == card.quantity)
+        {
+            Some((_, count)) => *count += 1,
+            None => groups.push((card.quantity, 1)),
+        }
+    }
+    groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    groups
+}
+
+fn is_wheel(hand: &Hand) -> bool {
+    let mut quantities: Vec<Quantity> = hand.iter().map(|card| card.quantity).collect();
+    quantities.sort();
+    quantities
+        == [
+            Quantity::Two,
+            Quantity::Three,
+            Quantity::Four,
+            Quantity::Five,
+            Quantity::Ace,
+        ]
+}
+
+fn straight_range(hand: &Hand) -> String {
+    if is_wheel(hand) {
+        return String::from("Ace to Five");
+    }
+    let low = hand.iter().map(|card| card.quantity).min().unwrap();
+    let high = hand.iter().map(|card| card.quantity).max().unwrap();
+    format!("{} to {}", low.name(), high.name())
+}
+
+fn with_article(quantity: Quantity) -> String {
+    match quantity {
+        Quantity::Ace | Quantity::Eight => format!("an {}", quantity.name()),
+        _ => format!("a {}", quantity.name()),
+    }
+}
@@ -0,0 +1,177 @@
+//! Multithreaded all-in equity simulator for Texas Hold'em.
+//!
+//! `exact` deals every possible remaining board,
+//! `monte_carlo` deals random boards from a seeded generator.
+
+use std::thread;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::deck::Deck;
+use crate::poker::evaluator::{evaluate_mask, mask};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Equity {
+    /// Share of boards won outright.
+    pub win: f64,
+    /// Share of boards where the pot is split.
+    pub tie: f64,
+    /// Expected share of the pot.
+    pub equity: f64,
+}
+
+/// Totals of one player over a number of boards.
+#[derive(Copy, Clone, Debug, Default)]
+struct Tally {
+    wins: u64,
+    ties: u64,
+    share: f64,
+}
+
+/// Computes the equity of every hand by dealing all remaining boards.
+/// Panics if the board has more than five cards or cards are dealt twice.
+pub fn exact(hands: &[HoleCards], board: &[Card], threads: usize) -> Vec<Equity> {
+    let remaining = remaining(hands, board);
+    let missing = 5 - board.len();
+    let threads = threads.max(1);
+
+    run(threads, hands.len(), |thread| {
+        let mut tallies = vec![Tally::default(); hands.len()];
+        let mut boards = 0;
+        if missing == 0 {
+            if thread == 0 {
+                showdown(hands, mask(board), &mut tallies);
+                boards = 1;
+            }
+            return (tallies, boards);
+        }
+        // Threads take turns on the first drawn card.
+        for first in (thread..remaining.len()).step_by(threads) {
+            let start = mask(board) | 1 << remaining[first].index();
+            for_each_subset(&remaining[first + 1..], missing - 1, start, &mut |board| {
+                showdown(hands, board, &mut tallies);
+                boards += 1;
+            });
+        }
+        (tallies, boards)
+    })
+}
+
+/// Estimates the equity of every hand from random boards.
+/// The same seed and number of threads always give the same result.
+pub fn monte_carlo(
+    hands: &[HoleCards],
+    board: &[Card],
+    trials: usize,
+    threads: usize,
+    seed: u64,
+) -> Vec<Equity> {
+    let remaining = remaining(hands, board);
+    let missing = 5 - board.len();
+    let threads = threads.max(1);
+
+    run(threads, hands.len(), |thread| {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(thread as u64));
+        let mut cards = remaining.clone();
+        let mut tallies = vec![Tally::default(); hands.len()];
+        let trials = trials / threads + usize::from(thread < trials % threads);
+
+        for _ in 0..trials {
+            let (drawn, _) = cards.partial_shuffle(&mut rng, missing);
+            showdown(hands, mask(board) | mask(drawn), &mut tallies);
+        }
+        (tallies, trials as u64)
+    })
+}
+
+/// Most players that can be dealt in from one deck.
+const MAX_PLAYERS: usize = 23;
+
+fn remaining(hands: &[HoleCards], board: &[Card]) -> Vec<Card> {
+    assert!(board.len() <= 5, "Board has more than five cards.");
+    assert!(hands.len() <= MAX_PLAYERS, "Too many players.");
+    let mut deck = Deck::create();
+    for card in hands.iter().flatten().chain(board.iter()) {
+        assert!(deck.remove(card), "Card {} is dealt twice.", card);
+    }
+    deck.cards
+}
+
+/// Runs the job on every thread and sums the tallies into equities.
+fn run<F>(threads: usize, players: usize, job: F) -> Vec<Equity>
+where
+    F: Fn(usize) -> (Vec<Tally>, u64) + Sync,
+{
+    let results: Vec<(Vec<Tally>, u64)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                let job = &job;
+                scope.spawn(move || job(thread))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Equity thread panicked."))
+            .collect()
+    });
+
+    let boards: u64 = results.iter().map(|(_, boards)| boards).sum();
+    (0..players)
+        .map(|player| {
+            let mut total = Tally::default();
+            for (tallies, _) in results.iter() {
+                total.wins += tallies[player].wins;
+                total.ties += tallies[player].ties;
+                total.share += tallies[player].share;
+            }
+            let boards = boards.max(1) as f64;
+            Equity {
+                win: total.wins as f64 / boards,
+                tie: total.ties as f64 / boards,
+                equity: total.share / boards,
+            }
+        })
+        .collect()
+}
+
+fn showdown(hands: &[HoleCards], board: u64, tallies: &mut [Tally]) {
+    let mut strengths = [0u32; MAX_PLAYERS];
+    let mut best = 0;
+    for (strength, hand) in strengths.iter_mut().zip(hands.iter()) {
+        *strength = evaluate_mask(board | mask(hand));
+        best = best.max(*strength);
+    }
+
+    let winners = strengths[..hands.len()]
+        .iter()
+        .filter(|strength| **strength == best)
+        .count();
+    for (tally, strength) in tallies.iter_mut().zip(strengths.iter()) {
+        if *strength != best {
+            continue;
+        }
+        if winners == 1 {
+            tally.wins += 1;
+        } else {
+            tally.ties += 1;
+        }
+        tally.share += 1.0 / winners as f64;
+    }
+}
+
+/// Calls `f` with the mask of every subset of `size` cards added to `start`.
+pub(crate) fn for_each_subset<F: FnMut(u64)>(cards: &[Card], size: usize, start: u64, f: &mut F) {
+    if size == 0 {
+        f(start);
+        return;
+    }
+    for i in 0..cards.len() {
+        if cards.len() - i < size {
+            break;
+        }
+        for_each_subset(&cards[i + 1..], size - 1, start | 1 << cards[i].index(), f);
+    }
+}
@@ -0,0 +1,165 @@
+//! Fast evaluation of the best five card hand out of five to seven cards.
+//!
+//! Strengths use the same encoding as `Evaluation::strength`:
+//! the category followed by the quantities of the five cards
+//! from the most significant one, four bits each.
+
+use crate::poker::card::Card;
+use crate::poker::rules::Category;
+
+/// Set of cards with a bit per `Card::index`.
+pub fn mask(cards: &[Card]) -> u64 {
+    cards.iter().fold(0, |mask, card| mask | 1 << card.index())
+}
+
+pub fn evaluate(cards: &[Card]) -> u32 {
+    evaluate_mask(mask(cards))
+}
+
+pub fn category(strength: u32) -> Category {
+    Category::all()[(strength >> 20) as usize]
+}
+
+/// Evaluates a set of cards given as a mask of card indices.
+pub fn evaluate_mask(mask: u64) -> u32 {
+    let mut suits = [0u16; 4];
+    let mut ranks: u16 = 0;
+    let mut pairs: u16 = 0;
+    let mut trips: u16 = 0;
+    let mut quads: u16 = 0;
+
+    for rank in 0..13 {
+        let cards = (mask >> (rank * 4)) & 0xF;
+        if cards == 0 {
+            continue;
+        }
+        let bit = 1 << rank;
+        ranks |= bit;
+        for (suit, suit_ranks) in suits.iter_mut().enumerate() {
+            if cards & (1 << suit) != 0 {
+                *suit_ranks |= bit;
+            }
+        }
+        match cards.count_ones() {
+            2 => pairs |= bit,
+            3 => trips |= bit,
+            4 => quads |= bit,
+            _ => {}
+        }
+    }
+
+    let mut flush = None;
+    for suit_ranks in suits.iter() {
+        if suit_ranks.count_ones() >= 5 {
+            if let Some(high) = straight_high(*suit_ranks) {
+                let category = if high == 12 {
+                    Category::FlushRoyal
+                } else {
+                    Category::StraightFlush
+                };
+                return encode(category, &straight(high));
+            }
+            flush = Some(*suit_ranks);
+        }
+    }
+
+    if quads != 0 {
+        let quad = top(quads);
+        let kicker = top(ranks & !(1 << quad));
+        return encode(Category::FourOfAKind, &[quad, quad, quad, quad, kicker]);
+    }
+
+    if trips != 0 {
+        let trip = top(trips);
+        let rest = (trips & !(1 << trip)) | pairs;
+        if rest != 0 {
+            let pair = top(rest);
+            return encode(Category::FullHouse, &[trip, trip, trip, pair, pair]);
+        }
+    }
+
+    if let Some(flush) = flush {
+        return encode(Category::Flush, &highest(flush, 5));
+    }
+
+    if let Some(high) = straight_high(ranks) {
+        return encode(Category::Straight, &straight(high));
+    }
+
+    if trips != 0 {
+        let trip = top(trips);
+        let kickers = highest(ranks & !(1 << trip), 2);
+        return encode(
+            Category::ThreeOfAKind,
+            &[trip, trip, trip, kickers[0], kickers[1]],
+        );
+    }
+
+    if pairs.count_ones() >= 2 {
+        let first = top(pairs);
+        let second = top(pairs & !(1 << first));
+        let kicker = top(ranks & !(1 << first) & !(1 << second));
+        return encode(Category::TwoPairs, &[first, first, second, second, kicker]);
+    }
+
+    if pairs != 0 {
+        let pair = top(pairs);
+        let kickers = highest(ranks & !(1 << pair), 3);
+        return encode(
+            Category::Pair,
+            &[pair, pair, kickers[0], kickers[1], kickers[2]],
+        );
+    }
+
+    encode(Category::HighCard, &highest(ranks, 5))
+}
+
+/// Bits of the quantities present in the mask, with a two as the lowest bit.
+pub(crate) fn ranks(mask: u64) -> u16 {
+    (0..13)
+        .filter(|rank| mask >> (rank * 4) & 0xF != 0)
+        .fold(0, |ranks, rank| ranks | 1 << rank)
+}
+
+/// Highest rank of a five card straight in the rank bits, where an ace can be low.
+/// Ranks count from zero for a two, a wheel returns the rank of the five.
+pub(crate) fn straight_high(ranks: u16) -> Option<u32> {
+    // Shifts the ranks up by one to put a low ace below the two.
+    let bits = (ranks as u32) << 1 | (ranks as u32 >> 12 & 1);
+    let runs = bits & bits >> 1 & bits >> 2 & bits >> 3 & bits >> 4;
+    if runs == 0 {
+        return None;
+    }
+    Some(31 - runs.leading_zeros() + 3)
+}
+
+/// Ranks of a straight, an ace of a wheel is written as -1.
+fn straight(high: u32) -> [i32; 5] {
+    let high = high as i32;
+    [high, high - 1, high - 2, high - 3, high - 4]
+}
+
+fn top(ranks: u16) -> i32 {
+    if ranks == 0 {
+        return -2;
+    }
+    15 - ranks.leading_zeros() as i32
+}
+
+/// The highest ranks of the bits, missing ones are padded with -2 so they encode as zero.
+fn highest(mut ranks: u16, count: usize) -> [i32; 5] {
+    let mut result = [-2; 5];
+    for slot in result.iter_mut().take(count) {
+        *slot = top(ranks);
+        if *slot >= 0 {
+            ranks &= !(1 << *slot);
+        }
+    }
+    result
+}
+
+fn encode(category: Category, ranks: &[i32]) -> u32 {
+    ranks.iter().fold(category as u32, |strength, rank| {
+        strength << 4 | (rank + 2) as u32
+    })
+}
@@ -0,0 +1,800 @@
+//! Single table No Limit Texas Hold'em.
+//!
+//! A `Game` is one hand: it posts antes and blinds, deals, takes the actions of the players
+//! in turn, runs out the board when nobody can bet any more and splits the pots at showdown.
+//! A `Table` keeps the seats and the stacks between hands and moves the button.
+
+use std::fmt::Display;
+
+use crate::poker::board::Street;
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::deck::Deck;
+use crate::poker::evaluator::{evaluate_mask, mask};
+
+/// Amounts of bets and raises are the total the player has put in on the street.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u64),
+    Raise(u64),
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Fold => write!(f, "folds"),
+            Action::Check => write!(f, "checks"),
+            Action::Call => write!(f, "calls"),
+            Action::Bet(amount) => write!(f, "bets {}", amount),
+            Action::Raise(amount) => write!(f, "raises to {}", amount),
+        }
+    }
+}
+
+/// Something that happened in a hand. Players are given by their index in the game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Ante {
+        player: usize,
+        amount: u64,
+    },
+    SmallBlind {
+        player: usize,
+        amount: u64,
+    },
+    BigBlind {
+        player: usize,
+        amount: u64,
+    },
+    Act {
+        player: usize,
+        action: Action,
+        /// Chips the action put in.
+        amount: u64,
+        all_in: bool,
+    },
+    /// The part of a bet nobody called, given back to the player.
+    Uncalled {
+        player: usize,
+        amount: u64,
+    },
+    /// Cards added to the board.
+    Deal {
+        street: Street,
+        cards: Vec<Card>,
+    },
+    Show {
+        player: usize,
+        hole: HoleCards,
+    },
+    /// Chips won from a pot, the main pot being the first one.
+    Collect {
+        player: usize,
+        amount: u64,
+        pot: usize,
+    },
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Blinds {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    /// Paid by every player before the deal.
+    pub ante: u64,
+}
+
+impl Blinds {
+    pub fn new(small_blind: u64, big_blind: u64, ante: u64) -> Blinds {
+        Blinds {
+            small_blind,
+            big_blind,
+            ante,
+        }
+    }
+}
+
+impl Display for Blinds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.small_blind, self.big_blind)?;
+        if self.ante > 0 {
+            write!(f, " ante {}", self.ante)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Player {
+    pub seat: usize,
+    pub name: String,
+    /// Chips behind, not yet put into the pot.
+    pub stack: u64,
+    pub hole: HoleCards,
+    /// Chips put in on the current street.
+    pub bet: u64,
+    /// Chips put in during the whole hand, antes included.
+    pub invested: u64,
+    pub folded: bool,
+}
+
+impl Player {
+    pub fn is_all_in(&self) -> bool {
+        !self.folded && self.stack == 0
+    }
+
+    /// Whether the player can still bet.
+    pub fn is_active(&self) -> bool {
+        !self.folded && self.stack > 0
+    }
+}
+
+/// What the player to act may do.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Legal {
+    pub check: bool,
+    /// Chips needed to call, zero when there is nothing to call.
+    pub call: u64,
+    /// Smallest and largest total a bet or raise may have, `None` when the player cannot raise.
+    pub raise: Option<(u64, u64)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ActionError {
+    HandOver,
+    CannotCheck,
+    NothingToCall,
+    /// Bets open a street, after a bet the action is a raise.
+    CannotBet,
+    NothingToRaise,
+    CannotRaise,
+    TooSmall(u64),
+    TooLarge(u64),
+}
+
+impl Display for ActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionError::HandOver => write!(f, "The hand is over."),
+            ActionError::CannotCheck => write!(f, "Cannot check facing a bet."),
+            ActionError::NothingToCall => write!(f, "There is nothing to call."),
+            ActionError::CannotBet => write!(f, "Cannot bet after a bet, raise instead."),
+            ActionError::NothingToRaise => write!(f, "There is nothing to raise, bet instead."),
+            ActionError::CannotRaise => write!(f, "Raising is not allowed."),
+            ActionError::TooSmall(min) => write!(f, "The amount must be at least {}.", min),
+            ActionError::TooLarge(max) => write!(f, "The amount must be at most {}.", max),
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u64,
+    /// Indices of the players who could win the pot.
+    pub eligible: Vec<usize>,
+    pub winners: Vec<usize>,
+}
+
+/// One hand of No Limit Hold'em.
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub blinds: Blinds,
+    pub players: Vec<Player>,
+    /// Index of the player on the button.
+    pub button: usize,
+    pub board: Vec<Card>,
+    pub street: Street,
+    blind_positions: (usize, usize),
+    deck: Deck,
+    current_bet: u64,
+    last_raise: u64,
+    to_act: Option<usize>,
+    pending: Vec<bool>,
+    can_raise: Vec<bool>,
+    pots: Vec<Pot>,
+    won: Vec<u64>,
+    events: Vec<Event>,
+}
+
+impl Game {
+    /// Starts a hand with players given by seat, name and stack, in seat order.
+    /// Cards are dealt from the end of the deck.
+    /// Panics if fewer than two players have chips or the deck runs out.
+    pub fn new(
+        players: Vec<(usize, String, u64)>,
+        button: usize,
+        blinds: Blinds,
+        deck: Deck,
+    ) -> Game {
+        assert!(
+            players.iter().filter(|(_, _, stack)| *stack > 0).count() >= 2,
+            "A hand needs two players with chips."
+        );
+        let count = players.len();
+        let mut game = Game {
+            blinds,
+            players: players
+                .into_iter()
+                .map(|(seat, name, stack)| Player {
+                    seat,
+                    name,
+                    stack,
+                    hole: [Card::new(); 2],
+                    bet: 0,
+                    invested: 0,
+                    folded: stack == 0,
+                })
+                .collect(),
+            button: button % count,
+            board: Vec::with_capacity(5),
+            street: Street::Preflop,
+            blind_positions: (0, 0),
+            deck,
+            current_bet: 0,
+            last_raise: blinds.big_blind,
+            to_act: None,
+            pending: vec![false; count],
+            can_raise: vec![true; count],
+            pots: Vec::new(),
+            won: vec![0; count],
+            events: Vec::new(),
+        };
+        game.start();
+        game
+    }
+
+    fn start(&mut self) {
+        for (index, player) in self.players.iter_mut().enumerate() {
+            let ante = self.blinds.ante.min(player.stack);
+            if player.folded || ante == 0 {
+                continue;
+            }
+            player.stack -= ante;
+            player.invested += ante;
+            self.events.push(Event::Ante {
+                player: index,
+                amount: ante,
+            });
+        }
+
+        let (small, big) = if self.in_hand() == 2 {
+            (
+                self.button_in_hand(),
+                self.next_in_hand(self.button_in_hand()),
+            )
+        } else {
+            let small = self.next_in_hand(self.button);
+            (small, self.next_in_hand(small))
+        };
+        self.blind_positions = (small, big);
+        let amount = self.post(small, self.blinds.small_blind);
+        if amount > 0 {
+            self.events.push(Event::SmallBlind {
+                player: small,
+                amount,
+            });
+        }
+        let amount = self.post(big, self.blinds.big_blind);
+        if amount > 0 {
+            self.events.push(Event::BigBlind {
+                player: big,
+                amount,
+            });
+        }
+        self.current_bet = self.blinds.big_blind;
+
+        for round in 0..2 {
+            for offset in 1..=self.players.len() {
+                let index = (self.button + offset) % self.players.len();
+                if !self.players[index].folded {
+                    let card = self.deck.deal().expect("The deck ran out of cards.");
+                    self.players[index].hole[round] = card;
+                }
+            }
+        }
+
+        let first = if self.in_hand() == 2 {
+            small
+        } else {
+            self.next_in_hand(big)
+        };
+        self.open_betting(first);
+    }
+
+    /// Indices of the small and the big blind: heads-up the button posts the small blind.
+    pub fn blind_positions(&self) -> (usize, usize) {
+        self.blind_positions
+    }
+
+    fn button_in_hand(&self) -> usize {
+        if self.players[self.button].folded {
+            self.next_in_hand(self.button)
+        } else {
+            self.button
+        }
+    }
+
+    /// Returns the chips posted, fewer than asked when the stack is short.
+    fn post(&mut self, index: usize, amount: u64) -> u64 {
+        let player = &mut self.players[index];
+        let amount = amount.min(player.stack);
+        player.stack -= amount;
+        player.bet += amount;
+        player.invested += amount;
+        amount
+    }
+
+    /// Number of players who have not folded.
+    pub fn in_hand(&self) -> usize {
+        self.players.iter().filter(|player| !player.folded).count()
+    }
+
+    fn next_in_hand(&self, from: usize) -> usize {
+        (1..=self.players.len())
+            .map(|offset| (from + offset) % self.players.len())
+            .find(|index| !self.players[*index].folded)
+            .unwrap_or(from)
+    }
+
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.to_act.is_none()
+    }
+
+    /// All chips put in, including the bets of the current street.
+    pub fn pot(&self) -> u64 {
+        self.players.iter().map(|player| player.invested).sum()
+    }
+
+    pub fn current_bet(&self) -> u64 {
+        self.current_bet
+    }
+
+    /// Pots with their winners, empty until the hand is over.
+    pub fn pots(&self) -> &[Pot] {
+        &self.pots
+    }
+
+    /// Chips won by every player, zero until the hand is over.
+    pub fn winnings(&self) -> &[u64] {
+        &self.won
+    }
+
+    /// Everything that happened in the hand so far, in order.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn legal(&self) -> Option<Legal> {
+        let index = self.to_act?;
+        let player = &self.players[index];
+        let to_call = self.current_bet.saturating_sub(player.bet);
+        let others_active = self
+            .players
+            .iter()
+            .enumerate()
+            .any(|(other, player)| other != index && player.is_active());
+
+        let max = player.bet + player.stack;
+        let raise = if self.can_raise[index] && player.stack > to_call && others_active {
+            Some(((self.current_bet + self.last_raise).min(max), max))
+        } else {
+            None
+        };
+        Some(Legal {
+            check: to_call == 0,
+            call: to_call.min(player.stack),
+            raise,
+        })
+    }
+
+    pub fn act(&mut self, action: Action) -> Result<(), ActionError> {
+        let index = self.to_act.ok_or(ActionError::HandOver)?;
+        let legal = self.legal().ok_or(ActionError::HandOver)?;
+        let before = self.players[index].stack;
+
+        match action {
+            Action::Fold => self.players[index].folded = true,
+            Action::Check if !legal.check => return Err(ActionError::CannotCheck),
+            Action::Check => {}
+            Action::Call if legal.check => return Err(ActionError::NothingToCall),
+            Action::Call => self.put(index, legal.call),
+            Action::Bet(_) if self.current_bet > 0 => return Err(ActionError::CannotBet),
+            Action::Raise(_) if self.current_bet == 0 => return Err(ActionError::NothingToRaise),
+            Action::Bet(to) | Action::Raise(to) => {
+                let (min, max) = legal.raise.ok_or(ActionError::CannotRaise)?;
+                if to > max {
+                    return Err(ActionError::TooLarge(max));
+                }
+                if to < min || to <= self.current_bet {
+                    return Err(ActionError::TooSmall(min));
+                }
+                let increase = to - self.current_bet;
+                let opening = self.current_bet == 0;
+                self.put(index, to - self.players[index].bet);
+                self.current_bet = to;
+
+                // A raise smaller than the last one, which is only allowed all in,
+                // does not give the players who already acted another chance to raise.
+                let full = increase >= self.last_raise || opening;
+                if increase >= self.last_raise {
+                    self.last_raise = increase;
+                }
+                for (other, player) in self.players.iter().enumerate() {
+                    if other != index && player.is_active() {
+                        self.pending[other] = true;
+                        if full {
+                            self.can_raise[other] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let player = &self.players[index];
+        self.events.push(Event::Act {
+            player: index,
+            action,
+            amount: before - player.stack,
+            all_in: player.is_all_in(),
+        });
+        self.pending[index] = false;
+        self.can_raise[index] = false;
+        self.advance(index);
+        Ok(())
+    }
+
+    fn put(&mut self, index: usize, amount: u64) {
+        let player = &mut self.players[index];
+        let amount = amount.min(player.stack);
+        player.stack -= amount;
+        player.bet += amount;
+        player.invested += amount;
+    }
+
+    fn advance(&mut self, from: usize) {
+        if self.in_hand() == 1 {
+            self.return_uncalled();
+            let winner = self
+                .players
+                .iter()
+                .position(|player| !player.folded)
+                .unwrap();
+            let amount = self.pot();
+            self.award(vec![Pot {
+                amount,
+                eligible: vec![winner],
+                winners: vec![winner],
+            }]);
+            return;
+        }
+        match self.next_pending(from) {
+            Some(next) => self.to_act = Some(next),
+            None => self.end_street(),
+        }
+    }
+
+    fn next_pending(&self, from: usize) -> Option<usize> {
+        let highest = self
+            .players
+            .iter()
+            .map(|player| player.bet)
+            .max()
+            .unwrap_or(0);
+        let active = self
+            .players
+            .iter()
+            .filter(|player| player.is_active())
+            .count();
+        (1..=self.players.len())
+            .map(|offset| (from + offset) % self.players.len())
+            .find(|index| {
+                let player = &self.players[*index];
+                // The last player who can bet has nothing to do once the all in bets are matched.
+                self.pending[*index]
+                    && player.is_active()
+                    && !(active == 1 && player.bet >= highest)
+            })
+    }
+
+    fn open_betting(&mut self, first: usize) {
+        for (pending, player) in self.pending.iter_mut().zip(self.players.iter()) {
+            *pending = player.is_active();
+        }
+        // Starts the search just before the first player so that they are found first.
+        let before = (first + self.players.len() - 1) % self.players.len();
+        match self.next_pending(before) {
+            Some(next) => self.to_act = Some(next),
+            None => self.end_street(),
+        }
+    }
+
+    /// Gives back the part of the highest bet that nobody called.
+    fn return_uncalled(&mut self) {
+        let (top, highest) = match self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(index, player)| (index, player.bet))
+            .max_by_key(|(_, bet)| *bet)
+        {
+            Some(top) => top,
+            None => return,
+        };
+        let second = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != top)
+            .map(|(_, player)| player.bet)
+            .max()
+            .unwrap_or(0);
+        if highest > second {
+            let player = &mut self.players[top];
+            player.stack += highest - second;
+            player.bet = second;
+            player.invested -= highest - second;
+            self.events.push(Event::Uncalled {
+                player: top,
+                amount: highest - second,
+            });
+        }
+    }
+
+    fn end_street(&mut self) {
+        self.return_uncalled();
+        for player in self.players.iter_mut() {
+            player.bet = 0;
+        }
+        self.current_bet = 0;
+        self.last_raise = self.blinds.big_blind;
+        for can_raise in self.can_raise.iter_mut() {
+            *can_raise = true;
+        }
+
+        let next = match self.street {
+            Street::Preflop => Street::Flop,
+            Street::Flop => Street::Turn,
+            Street::Turn => Street::River,
+            Street::River => {
+                self.showdown();
+                return;
+            }
+        };
+        let count = if next == Street::Flop { 3 } else { 1 };
+        let mut cards = Vec::with_capacity(count);
+        for _ in 0..count {
+            let card = self.deck.deal().expect("The deck ran out of cards.");
+            cards.push(card);
+        }
+        self.board.extend_from_slice(&cards);
+        self.street = next;
+        self.events.push(Event::Deal {
+            street: next,
+            cards,
+        });
+        let first = self.next_in_hand(self.button);
+        let first = if self.players[first].is_active() {
+            first
+        } else {
+            self.next_active(first).unwrap_or(first)
+        };
+        self.open_betting(first);
+    }
+
+    fn next_active(&self, from: usize) -> Option<usize> {
+        (1..=self.players.len())
+            .map(|offset| (from + offset) % self.players.len())
+            .find(|index| self.players[*index].is_active())
+    }
+
+    fn showdown(&mut self) {
+        for (index, player) in self.players.iter().enumerate() {
+            if !player.folded {
+                self.events.push(Event::Show {
+                    player: index,
+                    hole: player.hole,
+                });
+            }
+        }
+        let board = mask(&self.board);
+        let strengths: Vec<u32> = self
+            .players
+            .iter()
+            .map(|player| {
+                if player.folded {
+                    0
+                } else {
+                    evaluate_mask(board | mask(&player.hole))
+                }
+            })
+            .collect();
+
+        let mut levels: Vec<u64> = self
+            .players
+            .iter()
+            .filter(|player| !player.folded)
+            .map(|player| player.invested)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous = 0;
+        for level in levels {
+            let amount: u64 = self
+                .players
+                .iter()
+                .map(|player| player.invested.min(level) - player.invested.min(previous))
+                .sum();
+            previous = level;
+            if amount == 0 {
+                continue;
+            }
+            let eligible: Vec<usize> = (0..self.players.len())
+                .filter(|index| {
+                    !self.players[*index].folded && self.players[*index].invested >= level
+                })
+                .collect();
+            let best = eligible
+                .iter()
+                .map(|index| strengths[*index])
+                .max()
+                .unwrap();
+            let winners = eligible
+                .iter()
+                .copied()
+                .filter(|index| strengths[*index] == best)
+                .collect();
+            pots.push(Pot {
+                amount,
+                eligible,
+                winners,
+            });
+        }
+        self.award(pots);
+    }
+
+    /// Splits the pots, odd chips go to the first winners to the left of the button.
+    fn award(&mut self, pots: Vec<Pot>) {
+        for (number, pot) in pots.iter().enumerate() {
+            let mut winners = pot.winners.clone();
+            let count = self.players.len();
+            let button = self.button;
+            winners.sort_by_key(|index| (index + count - button - 1) % count);
+            let share = pot.amount / winners.len() as u64;
+            let odd = pot.amount % winners.len() as u64;
+            for (position, winner) in winners.iter().enumerate() {
+                let amount = share + u64::from((position as u64) < odd);
+                self.players[*winner].stack += amount;
+                self.won[*winner] += amount;
+                self.events.push(Event::Collect {
+                    player: *winner,
+                    amount,
+                    pot: number,
+                });
+            }
+        }
+        self.pots = pots;
+        self.to_act = None;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Seat {
+    pub name: String,
+    pub stack: u64,
+}
+
+/// Seats and stacks that last from one hand to the next.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub seats: Vec<Option<Seat>>,
+    /// Seat of the button.
+    pub button: usize,
+}
+
+impl Table {
+    pub fn new(size: usize) -> Table {
+        Table {
+            seats: vec![None; size],
+            button: 0,
+        }
+    }
+
+    /// Seats a player, returns false if the seat is taken or does not exist.
+    pub fn sit(&mut self, seat: usize, name: &str, stack: u64) -> bool {
+        match self.seats.get_mut(seat) {
+            Some(place @ None) => {
+                *place = Some(Seat {
+                    name: name.to_string(),
+                    stack,
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn leave(&mut self, seat: usize) -> Option<Seat> {
+        self.seats.get_mut(seat).and_then(Option::take)
+    }
+
+    /// Number of seated players.
+    pub fn players(&self) -> usize {
+        self.seats.iter().filter(|seat| seat.is_some()).count()
+    }
+
+    pub fn empty_seats(&self) -> Vec<usize> {
+        (0..self.seats.len())
+            .filter(|seat| self.seats[*seat].is_none())
+            .collect()
+    }
+
+    /// Deals a hand to the players with chips, starting with the button on an occupied seat.
+    /// Panics if fewer than two players have chips.
+    pub fn start(&mut self, blinds: Blinds, deck: Deck) -> Game {
+        if !self.has_chips(self.button) {
+            self.button = self.next_with_chips(self.button);
+        }
+        let players: Vec<(usize, String, u64)> = self
+            .seats
+            .iter()
+            .enumerate()
+            .filter_map(|(index, seat)| seat.as_ref().map(|seat| (index, seat)))
+            .filter(|(_, seat)| seat.stack > 0)
+            .map(|(index, seat)| (index, seat.name.clone(), seat.stack))
+            .collect();
+        let button = players
+            .iter()
+            .position(|(seat, _, _)| *seat == self.button)
+            .unwrap_or(0);
+        Game::new(players, button, blinds, deck)
+    }
+
+    /// Writes the stacks of a finished hand back to the seats and moves the button.
+    pub fn finish(&mut self, game: &Game) {
+        for player in game.players.iter() {
+            if let Some(Some(seat)) = self.seats.get_mut(player.seat) {
+                seat.stack = player.stack;
+            }
+        }
+        self.button = self.next_with_chips(self.button);
+    }
+
+    /// Plays a whole hand, asking `decide` for the action of the player to act.
+    /// An illegal action is replaced by a check, or a fold when checking is not possible.
+    pub fn play<F: FnMut(&Game) -> Action>(
+        &mut self,
+        blinds: Blinds,
+        deck: Deck,
+        mut decide: F,
+    ) -> Game {
+        let mut game = self.start(blinds, deck);
+        while !game.is_finished() {
+            let action = decide(&game);
+            if game.act(action).is_err() && game.act(Action::Check).is_err() {
+                game.act(Action::Fold).expect("Folding is always allowed.");
+            }
+        }
+        self.finish(&game);
+        game
+    }
+
+    fn has_chips(&self, seat: usize) -> bool {
+        matches!(self.seats.get(seat), Some(Some(seat)) if seat.stack > 0)
+    }
+
+    fn next_with_chips(&self, from: usize) -> usize {
+        (1..=self.seats.len())
+            .map(|offset| (from + offset) % self.seats.len())
+            .find(|seat| self.has_chips(*seat))
+            .unwrap_or(from)
+    }
+}
@@ -0,0 +1,420 @@
+//! Hand histories: the structured record of a hand and its text export.
+//!
+//! A `Game` records an `Event` for every ante, blind, action, street dealt, hand shown
+//! and pot collected. A `HandHistory` adds the seats and the stacks at the start of the hand,
+//! and its `Display` writes the text format of PokerStars that review tools import,
+//! with cards in the ASCII notation of `Card`, e.g. "Ah Td".
+
+use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::poker::board::Street;
+use crate::poker::card::{Card, Hand, HoleCards};
+use crate::poker::describe::describe;
+use crate::poker::evaluator::evaluate;
+use crate::poker::game::{Action, Blinds, Game};
+use crate::poker::render::{Render, Style};
+
+pub use crate::poker::game::Event;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Seated {
+    /// Seat number from zero, written from one.
+    pub seat: usize,
+    pub name: String,
+    /// Chips at the start of the hand.
+    pub stack: u64,
+    /// `None` when the cards are not known.
+    pub hole: Option<HoleCards>,
+    /// Whether the player has chips but was not dealt in.
+    pub sitting_out: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandHistory {
+    pub id: u64,
+    pub table: String,
+    /// Seats of the table.
+    pub size: usize,
+    /// Start of the hand in seconds since the Unix epoch.
+    pub time: u64,
+    pub blinds: Blinds,
+    /// Index of the player on the button.
+    pub button: usize,
+    /// Index of the player whose hole cards are dealt face up in the text, the others
+    /// only appear when they are shown.
+    pub hero: Option<usize>,
+    /// Players in seat order, the ones without chips included so that events can index them.
+    pub players: Vec<Seated>,
+    pub board: Vec<Card>,
+    pub events: Vec<Event>,
+}
+
+impl HandHistory {
+    /// History of a game with every hole card known, numbered 0 and started now, without a hero.
+    pub fn of(game: &Game) -> HandHistory {
+        let players: Vec<Seated> = game
+            .players
+            .iter()
+            .zip(game.winnings())
+            .map(|(player, won)| {
+                let stack = player.stack + player.invested - won;
+                Seated {
+                    seat: player.seat,
+                    name: player.name.clone(),
+                    stack,
+                    hole: if stack > 0 { Some(player.hole) } else { None },
+                    sitting_out: false,
+                }
+            })
+            .collect();
+        let size = players
+            .iter()
+            .map(|player| player.seat + 1)
+            .max()
+            .unwrap_or(0)
+            .max(2);
+        HandHistory {
+            id: 0,
+            table: String::from("Table"),
+            size,
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            blinds: game.blinds,
+            button: game.button,
+            hero: None,
+            players,
+            board: game.board.clone(),
+            events: game.events().to_vec(),
+        }
+    }
+
+    /// Chips won by every player.
+    pub fn winnings(&self) -> Vec<u64> {
+        let mut won = vec![0; self.players.len()];
+        for event in self.events.iter() {
+            if let Event::Collect { player, amount, .. } = event {
+                won[*player] += amount;
+            }
+        }
+        won
+    }
+
+    /// Amount of every pot, the main pot first.
+    pub fn pots(&self) -> Vec<u64> {
+        let mut pots = Vec::new();
+        for event in self.events.iter() {
+            if let Event::Collect { amount, pot, .. } = event {
+                if pots.len() <= *pot {
+                    pots.resize(pot + 1, 0);
+                }
+                pots[*pot] += amount;
+            }
+        }
+        pots
+    }
+
+    /// Name of the combination the player makes with the board, e.g. "Pair of Aces with a King kicker".
+    pub fn description(&self, hole: &HoleCards) -> Option<String> {
+        let mut cards = hole.to_vec();
+        cards.extend_from_slice(&self.board);
+        best_hand(&cards).map(|hand| describe(&hand))
+    }
+
+    fn name(&self, player: usize) -> &str {
+        &self.players[player].name
+    }
+
+    fn pot_name(&self, pot: usize) -> String {
+        match (self.pots().len(), pot) {
+            (1, _) => String::from("pot"),
+            (_, 0) => String::from("main pot"),
+            (_, side) => format!("side pot-{}", side),
+        }
+    }
+
+    /// What happened to the player, for the summary.
+    fn result(&self, player: usize) -> String {
+        let won = self.winnings()[player];
+        let mut street = Street::Preflop;
+        let mut invested = 0;
+        for event in self.events.iter() {
+            match event {
+                Event::Deal { street: next, .. } => street = *next,
+                Event::Ante {
+                    player: other,
+                    amount,
+                }
+                | Event::SmallBlind {
+                    player: other,
+                    amount,
+                }
+                | Event::BigBlind {
+                    player: other,
+                    amount,
+                }
+                | Event::Act {
+                    player: other,
+                    amount,
+                    ..
+                } if *other == player => {
+                    invested += amount;
+                    if let Event::Act {
+                        action: Action::Fold,
+                        ..
+                    } = event
+                    {
+                        return match street {
+                            Street::Preflop if invested == 0 => {
+                                String::from("folded before Flop (didn't bet)")
+                            }
+                            Street::Preflop => String::from("folded before Flop"),
+                            street => format!("folded on the {}", street),
+                        };
+                    }
+                }
+                Event::Show {
+                    player: other,
+                    hole,
+                } if *other == player => {
+                    let description = self.description(hole).unwrap_or_default();
+                    return if won > 0 {
+                        format!(
+                            "showed [{}] and won ({}) with {}",
+                            cards(hole),
+                            won,
+                            description
+                        )
+                    } else {
+                        format!("showed [{}] and lost with {}", cards(hole), description)
+                    };
+                }
+                _ => {}
+            }
+        }
+        if won > 0 {
+            format!("collected ({})", won)
+        } else {
+            String::from("mucked")
+        }
+    }
+}
+
+/// The text format of PokerStars.
+impl Display for HandHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "PokerStars Hand #{}:  Hold'em No Limit ({}/{}) - {} UTC",
+            self.id,
+            self.blinds.small_blind,
+            self.blinds.big_blind,
+            date(self.time)
+        )?;
+        writeln!(
+            f,
+            "Table '{}' {}-max Seat #{} is the button",
+            self.table,
+            self.size,
+            self.players[self.button].seat + 1
+        )?;
+        for player in self.players.iter().filter(|player| player.stack > 0) {
+            write!(
+                f,
+                "Seat {}: {} ({} in chips)",
+                player.seat + 1,
+                player.name,
+                player.stack
+            )?;
+            if player.sitting_out {
+                write!(f, " is sitting out")?;
+            }
+            writeln!(f)?;
+        }
+
+        let mut dealt = false;
+        let mut shown = false;
+        let mut board: Vec<Card> = Vec::with_capacity(5);
+        let mut highest = 0;
+        for event in self.events.iter() {
+            let forced = matches!(
+                event,
+                Event::Ante { .. } | Event::SmallBlind { .. } | Event::BigBlind { .. }
+            );
+            if !forced && !dealt {
+                dealt = true;
+                writeln!(f, "*** HOLE CARDS ***")?;
+                if let Some(hero) = self.hero.map(|hero| &self.players[hero]) {
+                    if let Some(hole) = hero.hole {
+                        writeln!(f, "Dealt to {} [{}]", hero.name, cards(&hole))?;
+                    }
+                }
+            }
+            match event {
+                Event::Ante { player, amount } => {
+                    writeln!(f, "{}: posts the ante {}", self.name(*player), amount)?
+                }
+                Event::SmallBlind { player, amount } => {
+                    highest = highest.max(*amount);
+                    writeln!(f, "{}: posts small blind {}", self.name(*player), amount)?
+                }
+                Event::BigBlind { player, amount } => {
+                    highest = highest.max(*amount);
+                    writeln!(f, "{}: posts big blind {}", self.name(*player), amount)?
+                }
+                Event::Act {
+                    player,
+                    action,
+                    amount,
+                    all_in,
+                } => {
+                    write!(f, "{}: ", self.name(*player))?;
+                    match action {
+                        Action::Fold => write!(f, "folds")?,
+                        Action::Check => write!(f, "checks")?,
+                        Action::Call => write!(f, "calls {}", amount)?,
+                        Action::Bet(to) => write!(f, "bets {}", to)?,
+                        Action::Raise(to) => write!(f, "raises {} to {}", to - highest, to)?,
+                    }
+                    if let Action::Bet(to) | Action::Raise(to) = action {
+                        highest = *to;
+                    }
+                    if *all_in {
+                        write!(f, " and is all-in")?;
+                    }
+                    writeln!(f)?;
+                }
+                Event::Uncalled { player, amount } => writeln!(
+                    f,
+                    "Uncalled bet ({}) returned to {}",
+                    amount,
+                    self.name(*player)
+                )?,
+                Event::Deal { street, cards: new } => {
+                    write!(f, "*** {} ***", street.name().to_uppercase())?;
+                    if !board.is_empty() {
+                        write!(f, " [{}]", cards(&board))?;
+                    }
+                    writeln!(f, " [{}]", cards(new))?;
+                    board.extend_from_slice(new);
+                    highest = 0;
+                }
+                Event::Show { player, hole } => {
+                    if !shown {
+                        shown = true;
+                        writeln!(f, "*** SHOW DOWN ***")?;
+                    }
+                    write!(f, "{}: shows [{}]", self.name(*player), cards(hole))?;
+                    match self.description(hole) {
+                        Some(description) => writeln!(f, " ({})", description)?,
+                        None => writeln!(f)?,
+                    }
+                }
+                Event::Collect {
+                    player,
+                    amount,
+                    pot,
+                } => writeln!(
+                    f,
+                    "{} collected {} from {}",
+                    self.name(*player),
+                    amount,
+                    self.pot_name(*pot)
+                )?,
+            }
+        }
+
+        writeln!(f, "*** SUMMARY ***")?;
+        let pots = self.pots();
+        write!(f, "Total pot {}", pots.iter().sum::<u64>())?;
+        if pots.len() > 1 {
+            for (pot, amount) in pots.iter().enumerate() {
+                let name = self.pot_name(pot);
+                write!(f, " {}{} {}.", name[..1].to_uppercase(), &name[1..], amount)?;
+            }
+        }
+        writeln!(f, " | Rake 0")?;
+        if !self.board.is_empty() {
+            writeln!(f, "Board [{}]", cards(&self.board))?;
+        }
+        let blinds = self
+            .events
+            .iter()
+            .fold((None, None), |blinds, event| match event {
+                Event::SmallBlind { player, .. } => (Some(*player), blinds.1),
+                Event::BigBlind { player, .. } => (blinds.0, Some(*player)),
+                _ => blinds,
+            });
+        for (index, player) in self.players.iter().enumerate() {
+            if player.stack == 0 || player.sitting_out {
+                continue;
+            }
+            write!(f, "Seat {}: {}", player.seat + 1, player.name)?;
+            if index == self.button {
+                write!(f, " (button)")?;
+            }
+            if blinds.0 == Some(index) {
+                write!(f, " (small blind)")?;
+            }
+            if blinds.1 == Some(index) {
+                write!(f, " (big blind)")?;
+            }
+            writeln!(f, " {}", self.result(index))?;
+        }
+        Ok(())
+    }
+}
+
+/// Cards separated by spaces in ASCII notation, e.g. "Ah Kd 2c".
+fn cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| card.styled(Style::Ascii).to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// The best five cards out of five to seven, `None` with fewer than five.
+fn best_hand(cards: &[Card]) -> Option<Hand> {
+    if cards.len() < 5 {
+        return None;
+    }
+    (0u32..1 << cards.len())
+        .filter(|subset| subset.count_ones() == 5)
+        .map(|subset| {
+            let mut hand = [Card::new(); 5];
+            let chosen = (0..cards.len()).filter(|index| subset & 1 << index != 0);
+            for (slot, index) in hand.iter_mut().zip(chosen) {
+                *slot = cards[index];
+            }
+            hand
+        })
+        .max_by_key(|hand| evaluate(hand))
+}
+
+/// Date and time in UTC written as "2024/03/01 18:30:00".
+fn date(time: u64) -> String {
+    let days = time / 86400;
+    let seconds = time % 86400;
+    // Civil date from the days since the epoch, with years starting in March.
+    let shifted = days + 719468;
+    let era = shifted / 146097;
+    let day_of_era = shifted % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{}/{:02}/{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
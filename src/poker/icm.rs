@@ -0,0 +1,243 @@
+//! Independent Chip Model: converts chip stacks into shares of the prize pool.
+//!
+//! The Malmuth-Harville model finishes players one place at a time from the first,
+//! each of the remaining players taking the place with the probability of their share of the chips.
+//! Small fields are computed exactly over every order of the paid places,
+//! large ones are estimated from sampled finishing orders.
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::poker::index::StartingHand;
+use crate::poker::preflop::PreflopTable;
+use crate::poker::range::Range;
+
+/// Most partial finishing orders that are computed exactly.
+const EXACT_STATES: usize = 100_000;
+const TRIALS: usize = 200_000;
+
+/// The prize pool equity of every player, computed exactly when the field is small enough.
+/// Players without chips get nothing and payouts for places nobody can take are dropped.
+pub fn equities(stacks: &[f64], payouts: &[f64]) -> Vec<f64> {
+    let alive: Vec<usize> = (0..stacks.len())
+        .filter(|player| stacks[*player] > 0.0)
+        .collect();
+    let places = payouts.len().min(alive.len());
+    let states: f64 = (0..places)
+        .map(|placed| binomial(alive.len(), placed))
+        .sum();
+    if alive.len() > 64 || states > EXACT_STATES as f64 {
+        return monte_carlo(stacks, payouts, TRIALS, 0);
+    }
+    // The exact model only needs the players with chips.
+    let chips: Vec<f64> = alive.iter().map(|player| stacks[*player]).collect();
+    let mut result = vec![0.0; stacks.len()];
+    for (player, equity) in alive.iter().zip(exact(&chips, payouts)) {
+        result[*player] = equity;
+    }
+    result
+}
+
+/// Sums the probability of every order of the paid places.
+/// Orders with the same set of placed players are merged, so the work grows with
+/// the number of such sets rather than the number of orders.
+/// Panics for more than 64 players.
+pub fn exact(stacks: &[f64], payouts: &[f64]) -> Vec<f64> {
+    assert!(stacks.len() <= 64, "Too many players for an exact model.");
+    let total: f64 = stacks.iter().filter(|stack| **stack > 0.0).sum();
+    let mut result = vec![0.0; stacks.len()];
+
+    // Probability of every set of players taking the places above the current one.
+    let mut layer: HashMap<u64, f64> = HashMap::new();
+    layer.insert(0, 1.0);
+    for payout in payouts.iter() {
+        let mut next = HashMap::with_capacity(layer.len() * stacks.len());
+        for (placed, probability) in layer {
+            let remaining: f64 = total - chips(stacks, placed);
+            if remaining <= 0.0 {
+                continue;
+            }
+            for (player, stack) in stacks.iter().enumerate() {
+                if *stack <= 0.0 || placed & 1 << player != 0 {
+                    continue;
+                }
+                let chance = probability * stack / remaining;
+                result[player] += chance * payout;
+                *next.entry(placed | 1 << player).or_insert(0.0) += chance;
+            }
+        }
+        layer = next;
+    }
+    result
+}
+
+/// Estimates the equities from finishing orders drawn with the model.
+pub fn monte_carlo(stacks: &[f64], payouts: &[f64], trials: usize, seed: u64) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut result = vec![0.0; stacks.len()];
+    let total: f64 = stacks.iter().filter(|stack| **stack > 0.0).sum();
+
+    for _ in 0..trials {
+        let mut remaining = total;
+        let mut placed = vec![false; stacks.len()];
+        for payout in payouts.iter() {
+            if remaining <= 0.0 {
+                break;
+            }
+            let mut pick = rng.gen::<f64>() * remaining;
+            let mut winner = None;
+            for (player, stack) in stacks.iter().enumerate() {
+                if *stack <= 0.0 || placed[player] {
+                    continue;
+                }
+                winner = Some(player);
+                if pick < *stack {
+                    break;
+                }
+                pick -= stack;
+            }
+            // Rounding can leave the pick just above the last stack, which then wins.
+            let winner = winner.unwrap();
+            placed[winner] = true;
+            remaining -= stacks[winner];
+            result[winner] += payout;
+        }
+    }
+    for equity in result.iter_mut() {
+        *equity /= trials.max(1) as f64;
+    }
+    result
+}
+
+fn chips(stacks: &[f64], players: u64) -> f64 {
+    stacks
+        .iter()
+        .enumerate()
+        .filter(|(player, stack)| players & 1 << player != 0 && **stack > 0.0)
+        .map(|(_, stack)| stack)
+        .sum()
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |result, i| result * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Prize pool equities of the two choices of a decision.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Decision {
+    pub fold: f64,
+    /// Value of pushing for the pusher, or of calling for the caller.
+    pub play: f64,
+}
+
+impl Decision {
+    pub fn gain(&self) -> f64 {
+        self.play - self.fold
+    }
+}
+
+/// A preflop all-in of one player called or folded to by another.
+///
+/// Stacks are counted at the start of the hand and `posted` holds the blinds and antes.
+/// When the caller folds the pusher collects everything posted, and when the pusher
+/// folds the caller does, as when the big blind is the caller.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllIn {
+    pub stacks: Vec<f64>,
+    pub posted: Vec<f64>,
+    pub payouts: Vec<f64>,
+    pub pusher: usize,
+    pub caller: usize,
+}
+
+impl AllIn {
+    /// Stacks after one of the two players folds and the other one takes the pot.
+    pub fn fold_stacks(&self, winner: usize) -> Vec<f64> {
+        let mut stacks: Vec<f64> = self
+            .stacks
+            .iter()
+            .zip(self.posted.iter())
+            .map(|(stack, posted)| stack - posted)
+            .collect();
+        stacks[winner] += self.posted.iter().sum::<f64>();
+        stacks
+    }
+
+    /// Stacks after the call when the showdown is won by the given player.
+    pub fn showdown_stacks(&self, winner: usize) -> Vec<f64> {
+        let loser = if winner == self.pusher {
+            self.caller
+        } else {
+            self.pusher
+        };
+        let risked = self.stacks[self.pusher].min(self.stacks[self.caller]);
+        let mut stacks = self.fold_stacks(winner);
+        // The posted chips of the two players are part of what they risk.
+        stacks[winner] -= self.posted[loser];
+        stacks[loser] += self.posted[loser];
+        stacks[winner] += risked;
+        stacks[loser] -= risked;
+        stacks
+    }
+
+    /// Value of calling for the caller, who wins the showdown with the given equity.
+    pub fn call_value(&self, equity: f64) -> Decision {
+        Decision {
+            fold: equities(&self.fold_stacks(self.pusher), &self.payouts)[self.caller],
+            play: self.showdown_value(self.caller, equity),
+        }
+    }
+
+    /// Value of pushing for the pusher, given how often the push is called
+    /// and the equity of the pusher when it is.
+    pub fn push_value(&self, call_probability: f64, equity: f64) -> Decision {
+        let folded = equities(&self.fold_stacks(self.pusher), &self.payouts)[self.pusher];
+        Decision {
+            fold: equities(&self.fold_stacks(self.caller), &self.payouts)[self.pusher],
+            play: (1.0 - call_probability) * folded
+                + call_probability * self.showdown_value(self.pusher, equity),
+        }
+    }
+
+    /// The showdown equity the caller needs for calling to be worth as much as folding.
+    pub fn required_equity(&self) -> f64 {
+        let fold = equities(&self.fold_stacks(self.pusher), &self.payouts)[self.caller];
+        let win = equities(&self.showdown_stacks(self.caller), &self.payouts)[self.caller];
+        let lose = equities(&self.showdown_stacks(self.pusher), &self.payouts)[self.caller];
+        ((fold - lose) / (win - lose)).clamp(0.0, 1.0)
+    }
+
+    /// Value of calling a push from the given range with a starting hand.
+    pub fn call_value_with(
+        &self,
+        table: &PreflopTable,
+        hand: StartingHand,
+        push: &Range,
+    ) -> Decision {
+        self.call_value(table.equity_against(hand, push).unwrap_or(0.5))
+    }
+
+    /// Value of pushing a starting hand into the given calling range.
+    pub fn push_value_with(
+        &self,
+        table: &PreflopTable,
+        hand: StartingHand,
+        call: &Range,
+    ) -> Decision {
+        let equity = table.equity_against(hand, call).unwrap_or(0.5);
+        self.push_value(call.share(), equity)
+    }
+
+    fn showdown_value(&self, player: usize, equity: f64) -> f64 {
+        let other = if player == self.pusher {
+            self.caller
+        } else {
+            self.pusher
+        };
+        let win = equities(&self.showdown_stacks(player), &self.payouts)[player];
+        let lose = equities(&self.showdown_stacks(other), &self.payouts)[player];
+        equity * win + (1.0 - equity) * lose
+    }
+}
@@ -0,0 +1,587 @@
+//! Parsing of text hand histories from PokerStars and GGPoker into `HandHistory`.
+//!
+//! Files hold hands one after another, each starting with a line like
+//! `PokerStars Hand #123: Hold'em No Limit ($0.05/$0.10 USD) - 2024/03/01 18:30:00 ET`
+//! or `Poker Hand #RC123: Hold'em No Limit ($0.05/$0.10) - 2024/03/01 18:30:00`.
+//! Amounts of cash games are converted to cents, tournament chips are kept as they are.
+//! Times are read as written, whatever the time zone. Chat, connection and other lines
+//! that do not change the hand are skipped; errors give the line they were found on.
+//! Hands run twice are rejected.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::poker::board::Street;
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::game::{Action, Blinds};
+use crate::poker::history::{Event, HandHistory, Seated};
+
+/// An error in a hand history, on a line counted from 1 or on line 0 for the file as a whole.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "Line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses every hand of a text, e.g. a whole history file.
+pub fn parse(text: &str) -> Result<Vec<HandHistory>, ParseError> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim_end())
+        .collect();
+    let mut hands = Vec::new();
+    let mut start = None;
+    for (number, line) in lines.iter().enumerate() {
+        if is_header(line) {
+            if let Some(start) = start {
+                hands.push(parse_hand(&lines[start..number], start)?);
+            }
+            start = Some(number);
+        } else if start.is_none() && !line.is_empty() {
+            return Err(ParseError {
+                line: number + 1,
+                message: String::from("Expected the first line of a hand."),
+            });
+        }
+    }
+    if let Some(start) = start {
+        hands.push(parse_hand(&lines[start..], start)?);
+    }
+    Ok(hands)
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<HandHistory>, ParseError> {
+    let text = fs::read_to_string(path).map_err(|error| ParseError {
+        line: 0,
+        message: error.to_string(),
+    })?;
+    parse(&text)
+}
+
+/// Parses a text holding exactly one hand.
+impl FromStr for HandHistory {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut hands = parse(s)?;
+        match hands.len() {
+            1 => Ok(hands.remove(0)),
+            count => Err(ParseError {
+                line: 0,
+                message: format!("Expected one hand, found {}.", count),
+            }),
+        }
+    }
+}
+
+fn is_header(line: &str) -> bool {
+    (line.starts_with("PokerStars ") && line.contains(" Hand #"))
+        || line.starts_with("Poker Hand #")
+}
+
+/// Parses the lines of one hand, the first one being line `offset` of the text counted from 0.
+fn parse_hand(lines: &[&str], offset: usize) -> Result<HandHistory, ParseError> {
+    let error = |index: usize, message: String| ParseError {
+        line: offset + index + 1,
+        message,
+    };
+    let mut parser = Parser {
+        history: HandHistory {
+            id: 0,
+            table: String::new(),
+            size: 0,
+            time: 0,
+            blinds: Blinds::default(),
+            button: 0,
+            hero: None,
+            players: Vec::new(),
+            board: Vec::new(),
+            events: Vec::new(),
+        },
+        scale: 1,
+        button: 0,
+        bets: Vec::new(),
+        dealt: Vec::new(),
+        summary: false,
+    };
+    parser
+        .header(lines[0])
+        .map_err(|message| error(0, message))?;
+    let table = lines
+        .get(1)
+        .ok_or_else(|| error(0, String::from("The table line is missing.")))?;
+    parser.table(table).map_err(|message| error(1, message))?;
+    for (index, line) in lines.iter().enumerate().skip(2) {
+        parser.line(line).map_err(|message| error(index, message))?;
+    }
+    parser.finish().map_err(|message| error(0, message))
+}
+
+struct Parser {
+    history: HandHistory,
+    /// Chips per unit of the amounts written: 100 for cash games, counted in cents.
+    scale: u64,
+    /// Seat of the button as written, counted from 1.
+    button: usize,
+    /// Chips put in by every player on the current street.
+    bets: Vec<u64>,
+    /// Board cards of the streets dealt so far.
+    dealt: Vec<Card>,
+    summary: bool,
+}
+
+impl Parser {
+    fn header(&mut self, line: &str) -> Result<(), String> {
+        let (_, rest) = line.split_once('#').unwrap();
+        let (id, rest) = rest
+            .split_once(':')
+            .ok_or_else(|| String::from("Expected a colon after the hand number."))?;
+        let digits: String = id.chars().filter(char::is_ascii_digit).collect();
+        self.history.id = digits
+            .parse()
+            .map_err(|_| format!("Invalid hand number \"{}\".", id))?;
+        if !rest.contains("Hold'em No Limit") {
+            return Err(String::from("Only No Limit Hold'em hands are supported."));
+        }
+
+        let mut groups = rest
+            .split('(')
+            .skip(1)
+            .filter_map(|group| group.split_once(')'));
+        let blinds = loop {
+            let (group, _) = groups
+                .next()
+                .ok_or_else(|| String::from("The blinds are missing."))?;
+            let group = group.split_whitespace().next().unwrap_or_default();
+            if let Some((small, big)) = group.split_once('/') {
+                if group.contains(['$', '€', '£']) {
+                    self.scale = 100;
+                }
+                break (self.amount(small)?, self.amount(big)?);
+            }
+        };
+        self.history.blinds = Blinds::new(blinds.0, blinds.1, 0);
+
+        let words: Vec<&str> = rest.split_whitespace().collect();
+        self.history.time = words
+            .windows(2)
+            .find_map(|pair| timestamp(pair[0], pair[1].trim_end_matches(']')))
+            .unwrap_or(0);
+        Ok(())
+    }
+
+    fn table(&mut self, line: &str) -> Result<(), String> {
+        let expected =
+            || String::from("Expected a line like \"Table 'Name' 6-max Seat #1 is the button\".");
+        let rest = line.strip_prefix("Table '").ok_or_else(expected)?;
+        let (name, rest) = rest.rsplit_once('\'').ok_or_else(expected)?;
+        self.history.table = name.to_string();
+        for word in rest.split_whitespace() {
+            if let Some(size) = word.strip_suffix("-max") {
+                self.history.size = size
+                    .parse()
+                    .map_err(|_| format!("Invalid table size \"{}\".", word))?;
+            } else if let Some(seat) = word.strip_prefix('#') {
+                self.button = seat
+                    .parse()
+                    .map_err(|_| format!("Invalid button seat \"{}\".", word))?;
+            }
+        }
+        if self.button == 0 {
+            return Err(String::from("The button seat is missing."));
+        }
+        Ok(())
+    }
+
+    fn line(&mut self, line: &str) -> Result<(), String> {
+        if self.summary {
+            return self.summary(line);
+        }
+        if let Some(section) = line.strip_prefix("*** ") {
+            let section = section.trim_end_matches(" ***");
+            let street = match section.split(" *** ").next().unwrap() {
+                "HOLE CARDS" | "SHOW DOWN" | "SHOWDOWN" => return Ok(()),
+                "SUMMARY" => {
+                    self.summary = true;
+                    return Ok(());
+                }
+                "FLOP" => Street::Flop,
+                "TURN" => Street::Turn,
+                "RIVER" => Street::River,
+                other if other.starts_with("FIRST ") || other.starts_with("SECOND ") => {
+                    return Err(String::from("Hands run twice are not supported."))
+                }
+                other => return Err(format!("Unsupported section \"{}\".", other)),
+            };
+            let (_, last) = line
+                .rsplit_once('[')
+                .ok_or_else(|| String::from("The cards of the street are missing."))?;
+            let cards = parse_cards(last.trim_end_matches(']'))?;
+            let expected = if street == Street::Flop { 3 } else { 1 };
+            if cards.len() != expected || Street::of(&self.dealt) != Some(previous(street)) {
+                return Err(format!("Unexpected cards for the {}.", street));
+            }
+            self.dealt.extend_from_slice(&cards);
+            self.bets.iter_mut().for_each(|bet| *bet = 0);
+            self.history.events.push(Event::Deal { street, cards });
+            return Ok(());
+        }
+        if line.starts_with("Seat ") {
+            return self.seat(line);
+        }
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            let (player, rest) = self
+                .player(rest)
+                .ok_or_else(|| format!("Unknown player in \"{}\".", line))?;
+            if let Some(hole) = hole_cards(rest)? {
+                self.history.players[player].hole = Some(hole);
+                self.history.hero = Some(player);
+            }
+            return Ok(());
+        }
+        if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let (amount, name) = rest.split_once(") returned to ").ok_or_else(|| {
+                String::from("Expected \"Uncalled bet (amount) returned to name\".")
+            })?;
+            let amount = self.amount(amount)?;
+            let player = self
+                .index(name)
+                .ok_or_else(|| format!("Unknown player \"{}\".", name))?;
+            self.bets[player] = self.bets[player].saturating_sub(amount);
+            self.history.events.push(Event::Uncalled { player, amount });
+            return Ok(());
+        }
+        match self.player(line) {
+            Some((player, rest)) => match rest.strip_prefix(": ") {
+                Some(action) => self.action(player, action),
+                None => self.collect(player, rest.trim_start()),
+            },
+            None => match line.split_once(": ") {
+                Some((name, action)) if is_action(action) => {
+                    Err(format!("Unknown player \"{}\".", name))
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    fn seat(&mut self, line: &str) -> Result<(), String> {
+        let expected = || String::from("Expected a line like \"Seat 1: Name (1000 in chips)\".");
+        let (seat, rest) = line["Seat ".len()..]
+            .split_once(": ")
+            .ok_or_else(expected)?;
+        let seat: usize = seat.parse().map_err(|_| expected())?;
+        let (name, rest) = rest.rsplit_once(" (").ok_or_else(expected)?;
+        let (chips, status) = rest.split_once(" in chips").ok_or_else(expected)?;
+        if seat == 0
+            || self
+                .history
+                .players
+                .iter()
+                .any(|player| player.seat + 1 == seat)
+        {
+            return Err(format!("Seat {} is taken twice.", seat));
+        }
+        let stack = self.amount(chips)?;
+        self.history.players.push(Seated {
+            seat: seat - 1,
+            name: name.to_string(),
+            stack,
+            hole: None,
+            sitting_out: status.ends_with(" is sitting out") || status.ends_with(" out of hand"),
+        });
+        self.bets.push(0);
+        Ok(())
+    }
+
+    fn action(&mut self, player: usize, text: &str) -> Result<(), String> {
+        let (text, all_in) = match text.strip_suffix(" and is all-in") {
+            Some(text) => (text, true),
+            None => (text, false),
+        };
+        let (verb, rest) = text.split_once(' ').unwrap_or((text, ""));
+        let bet = self.bets[player];
+        let (action, amount) = match (verb, rest) {
+            ("posts", rest) => return self.post(player, rest),
+            ("folds", rest) => {
+                if let Some(hole) = hole_cards(rest)? {
+                    self.history.players[player].hole = Some(hole);
+                }
+                (Action::Fold, 0)
+            }
+            ("checks", "") => (Action::Check, 0),
+            ("calls", amount) => (Action::Call, self.amount(amount)?),
+            ("bets", amount) => {
+                let to = self.amount(amount)?;
+                (Action::Bet(to), to.saturating_sub(bet))
+            }
+            ("raises", rest) => {
+                let (_, to) = rest
+                    .split_once(" to ")
+                    .ok_or_else(|| String::from("Expected \"raises amount to total\"."))?;
+                let to = self.amount(to)?;
+                if to <= bet {
+                    return Err(format!("Raise to {} is not above the bet of {}.", to, bet));
+                }
+                (Action::Raise(to), to - bet)
+            }
+            ("shows", rest) => {
+                let hole = hole_cards(rest)?
+                    .ok_or_else(|| String::from("The shown cards are missing."))?;
+                self.history.players[player].hole.get_or_insert(hole);
+                self.history.events.push(Event::Show { player, hole });
+                return Ok(());
+            }
+            ("mucks", _) | ("doesn't", _) | ("is", _) | ("sits", _) | ("has", _) => return Ok(()),
+            _ => return Err(format!("Unknown action \"{}\".", text)),
+        };
+        self.bet(player, amount, rest.rsplit(" to ").next().unwrap())?;
+        self.history.events.push(Event::Act {
+            player,
+            action,
+            amount,
+            all_in,
+        });
+        Ok(())
+    }
+
+    fn post(&mut self, player: usize, text: &str) -> Result<(), String> {
+        let (kind, written) = text
+            .rsplit_once(' ')
+            .ok_or_else(|| String::from("The posted amount is missing."))?;
+        let amount = self.amount(written)?;
+        let event = match kind {
+            "the ante" => {
+                let blinds = &mut self.history.blinds;
+                blinds.ante = blinds.ante.max(amount);
+                self.history.events.push(Event::Ante { player, amount });
+                return Ok(());
+            }
+            "small blind" => Event::SmallBlind { player, amount },
+            // Dead small blinds are counted as part of the big blind.
+            "big blind" | "small & big blinds" => Event::BigBlind { player, amount },
+            _ => return Err(format!("Unknown blind \"{}\".", kind)),
+        };
+        self.bet(player, amount, written)?;
+        self.history.events.push(event);
+        Ok(())
+    }
+
+    /// Adds chips to the bet of the player on the street, the amount being written as `text`.
+    fn bet(&mut self, player: usize, amount: u64, text: &str) -> Result<(), String> {
+        self.bets[player] = self.bets[player]
+            .checked_add(amount)
+            .ok_or_else(|| format!("Invalid amount \"{}\".", text))?;
+        Ok(())
+    }
+
+    /// Lines like "collected 300 from main pot". Other lines about the player are skipped.
+    fn collect(&mut self, player: usize, text: &str) -> Result<(), String> {
+        let rest = match text.strip_prefix("collected ") {
+            Some(rest) => rest,
+            None => return Ok(()),
+        };
+        let (amount, pot) = rest
+            .split_once(" from ")
+            .ok_or_else(|| String::from("Expected \"collected amount from pot\"."))?;
+        let amount = self.amount(amount)?;
+        let pot = match pot {
+            "pot" | "main pot" => 0,
+            "side pot" => 1,
+            side => side
+                .strip_prefix("side pot-")
+                .and_then(|number| number.parse().ok())
+                .ok_or_else(|| format!("Unknown pot \"{}\".", side))?,
+        };
+        self.history.events.push(Event::Collect {
+            player,
+            amount,
+            pot,
+        });
+        Ok(())
+    }
+
+    /// The summary repeats the hand, only the board and the cards shown at the end are read.
+    fn summary(&mut self, line: &str) -> Result<(), String> {
+        if let Some(board) = line.strip_prefix("Board [") {
+            let board = parse_cards(board.trim_end_matches(']'))?;
+            if board != self.dealt {
+                return Err(String::from("The board does not match the cards dealt."));
+            }
+        } else if let Some(rest) = line.strip_prefix("Seat ") {
+            let seat = rest.split(':').next().unwrap();
+            let cards = ["showed [", "mucked ["]
+                .iter()
+                .find_map(|marker| rest.split_once(marker).map(|(_, cards)| cards));
+            if let (Ok(seat), Some(cards)) = (seat.parse::<usize>(), cards) {
+                let (cards, _) = cards
+                    .split_once(']')
+                    .ok_or_else(|| String::from("Expected cards in brackets."))?;
+                let hole = two_cards(parse_cards(cards)?)?;
+                if let Some(player) = self
+                    .history
+                    .players
+                    .iter_mut()
+                    .find(|player| player.seat + 1 == seat)
+                {
+                    player.hole.get_or_insert(hole);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<HandHistory, String> {
+        let history = &mut self.history;
+        if history.players.len() < 2 {
+            return Err(String::from("The hand has fewer than two seated players."));
+        }
+        // The button may be on an empty seat, then the last player before it has it.
+        let button = self.button - 1;
+        history.button = history
+            .players
+            .iter()
+            .rposition(|player| player.seat <= button)
+            .unwrap_or(history.players.len() - 1);
+        if history.size == 0 {
+            history.size = history
+                .players
+                .iter()
+                .map(|player| player.seat + 1)
+                .max()
+                .unwrap();
+        }
+        history.board = self.dealt;
+        Ok(self.history)
+    }
+
+    /// Index of the seated player whose name starts the text, the longest name first,
+    /// and the text after the name.
+    fn player<'a>(&self, text: &'a str) -> Option<(usize, &'a str)> {
+        self.history
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, player)| {
+                text.strip_prefix(player.name.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', ' ']))
+            })
+            .max_by_key(|(_, player)| player.name.len())
+            .map(|(index, player)| (index, &text[player.name.len()..]))
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.history
+            .players
+            .iter()
+            .position(|player| player.name == name)
+    }
+
+    /// Amounts like "1500", "$0.25" or "€10", in cents for cash games.
+    fn amount(&self, text: &str) -> Result<u64, String> {
+        let invalid = || format!("Invalid amount \"{}\".", text);
+        let number = text.trim().trim_start_matches(['$', '€', '£']);
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let whole: u64 = whole.parse().map_err(|_| invalid())?;
+        let fraction: u64 = match (self.scale, fraction.len()) {
+            (_, 0) => 0,
+            (100, 1) => fraction.parse::<u64>().map_err(|_| invalid())? * 10,
+            (100, 2) => fraction.parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        };
+        whole
+            .checked_mul(self.scale)
+            .and_then(|chips| chips.checked_add(fraction))
+            .ok_or_else(invalid)
+    }
+}
+
+/// Whether the text after a name and a colon is an action, to catch unknown players.
+fn is_action(text: &str) -> bool {
+    [
+        "posts ", "folds", "checks", "calls ", "bets ", "raises ", "shows ",
+    ]
+    .iter()
+    .any(|verb| text.starts_with(verb))
+}
+
+fn previous(street: Street) -> Street {
+    match street {
+        Street::Flop | Street::Preflop => Street::Preflop,
+        Street::Turn => Street::Flop,
+        Street::River => Street::Turn,
+    }
+}
+
+/// Cards separated by spaces, e.g. "Ah Kd 2c".
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    text.split_whitespace()
+        .map(|card| card.parse::<Card>().map_err(|error| error.to_string()))
+        .collect()
+}
+
+fn two_cards(cards: Vec<Card>) -> Result<HoleCards, String> {
+    match cards[..] {
+        [first, second] => Ok([first, second]),
+        _ => Err(format!("Expected two hole cards, found {}.", cards.len())),
+    }
+}
+
+/// Hole cards in brackets at the start of the text, e.g. " [Ah Kd] (a pair of Aces)".
+fn hole_cards(text: &str) -> Result<Option<HoleCards>, String> {
+    let cards = match text.trim_start().strip_prefix('[') {
+        Some(cards) => cards,
+        None => return Ok(None),
+    };
+    let (cards, _) = cards
+        .split_once(']')
+        .ok_or_else(|| String::from("Expected cards in brackets."))?;
+    two_cards(parse_cards(cards)?).map(Some)
+}
+
+/// Seconds since the Unix epoch of a date like "2024/03/01" and a time like "18:30:00".
+fn timestamp(date: &str, time: &str) -> Option<u64> {
+    let numbers = |text: &str, separator: char| -> Option<Vec<u64>> {
+        text.split(separator)
+            .map(|number| number.parse().ok())
+            .collect()
+    };
+    let (date, time) = (numbers(date, '/')?, numbers(time, ':')?);
+    let (year, month, day) = match date[..] {
+        [year, month, day] if (1..=12).contains(&month) && (1..=31).contains(&day) => {
+            (year, month, day)
+        }
+        _ => return None,
+    };
+    let seconds = match time[..] {
+        [hours, minutes, seconds] => hours * 3600 + minutes * 60 + seconds,
+        _ => return None,
+    };
+    // Days since the epoch of the civil date, with years starting in March.
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146097 + day_of_era).checked_sub(719468)?;
+    Some(days * 86400 + seconds)
+}
@@ -0,0 +1,212 @@
+//! Stable numeric encodings of cards and hands for tables and compact storage.
+//!
+//! Cards are numbered from 0 to 51 by quantity and then by suit,
+//! so the twos come first and the ace of hearts is 51.
+//! Sets of cards are numbered with the combinatorial number system:
+//! every five card hand gets an index below 2,598,960
+//! and every pair of hole cards an index below 1326.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::poker::card::{Card, Hand, HoleCards, Quantity, Suit};
+
+pub const HANDS: u32 = 2_598_960;
+pub const HOLE_CARDS: u16 = 1326;
+pub const STARTING_HANDS: u8 = 169;
+
+/// Number of ways to choose `k` items out of `n`.
+pub fn binomial(n: u32, k: u32) -> u32 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1u64, |result, i| result * (n - i) as u64 / (i + 1) as u64) as u32
+}
+
+/// Index of a set of cards among all sets of the same size, independent of the card order.
+pub fn set_index(cards: &[Card]) -> u32 {
+    let mut indices: Vec<u32> = cards.iter().map(|card| card.index() as u32).collect();
+    indices.sort_unstable();
+    indices
+        .iter()
+        .enumerate()
+        .map(|(i, index)| binomial(*index, i as u32 + 1))
+        .sum()
+}
+
+/// Inverse of `set_index`, returns the cards sorted by their indices.
+pub fn set_from_index(mut index: u32, size: u32) -> Option<Vec<Card>> {
+    if index >= binomial(52, size) {
+        return None;
+    }
+    let mut cards = Vec::with_capacity(size as usize);
+    let mut candidate = 52;
+    for k in (1..=size).rev() {
+        // Finds the largest card index whose binomial fits into the remainder.
+        candidate -= 1;
+        while binomial(candidate, k) > index {
+            candidate -= 1;
+        }
+        index -= binomial(candidate, k);
+        cards.push(Card::from_index(candidate as u8).unwrap());
+    }
+    cards.reverse();
+    Some(cards)
+}
+
+pub fn hand_index(hand: &Hand) -> u32 {
+    set_index(hand)
+}
+
+pub fn hand_from_index(index: u32) -> Option<Hand> {
+    set_from_index(index, 5).map(|cards| [cards[0], cards[1], cards[2], cards[3], cards[4]])
+}
+
+pub fn hole_index(cards: &HoleCards) -> u16 {
+    set_index(cards) as u16
+}
+
+pub fn hole_from_index(index: u16) -> Option<HoleCards> {
+    set_from_index(index as u32, 2).map(|cards| [cards[0], cards[1]])
+}
+
+/// One of the 169 classes of hole cards, such as "AA", "AKs" or "72o".
+///
+/// Indices follow a 13 by 13 grid with twos first:
+/// pairs lie on the diagonal, suited hands below it and offsuit hands above it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StartingHand {
+    pub high: Quantity,
+    pub low: Quantity,
+    pub suited: bool,
+}
+
+impl StartingHand {
+    pub fn of(cards: &HoleCards) -> StartingHand {
+        let (high, low) = if cards[0].quantity >= cards[1].quantity {
+            (cards[0], cards[1])
+        } else {
+            (cards[1], cards[0])
+        };
+        StartingHand {
+            high: high.quantity,
+            low: low.quantity,
+            suited: high.suit == low.suit,
+        }
+    }
+
+    pub fn all() -> Vec<StartingHand> {
+        (0..STARTING_HANDS)
+            .map(|index| StartingHand::from_index(index).unwrap())
+            .collect()
+    }
+
+    pub fn index(&self) -> u8 {
+        let high = self.high as u8 - 2;
+        let low = self.low as u8 - 2;
+        if self.suited {
+            high * 13 + low
+        } else {
+            low * 13 + high
+        }
+    }
+
+    pub fn from_index(index: u8) -> Option<StartingHand> {
+        if index >= STARTING_HANDS {
+            return None;
+        }
+        let row = Quantity::all()[index as usize / 13];
+        let column = Quantity::all()[index as usize % 13];
+        Some(StartingHand {
+            high: row.max(column),
+            low: row.min(column),
+            suited: row > column,
+        })
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// Every pair of hole cards of the class: 6 for a pair, 4 suited or 12 offsuit.
+    pub fn combos(&self) -> Vec<HoleCards> {
+        let mut combos = Vec::with_capacity(12);
+        for (i, first) in Suit::all().iter().enumerate() {
+            for (j, second) in Suit::all().iter().enumerate() {
+                let valid = if self.is_pair() {
+                    i < j
+                } else if self.suited {
+                    i == j
+                } else {
+                    i != j
+                };
+                if valid {
+                    combos.push([
+                        Card {
+                            quantity: self.high,
+                            suit: *first,
+                        },
+                        Card {
+                            quantity: self.low,
+                            suit: *second,
+                        },
+                    ]);
+                }
+            }
+        }
+        combos
+    }
+}
+
+impl Display for StartingHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.high.symbol(), self.low.symbol())?;
+        if self.is_pair() {
+            Ok(())
+        } else if self.suited {
+            write!(f, "s")
+        } else {
+            write!(f, "o")
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseStartingHandError(pub String);
+
+impl Display for ParseStartingHandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid starting hand \"{}\".", self.0)
+    }
+}
+
+impl std::error::Error for ParseStartingHandError {}
+
+/// Parses classes written as "AA", "AKs", "T9o" or "AK", which is offsuit.
+impl FromStr for StartingHand {
+    type Err = ParseStartingHandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseStartingHandError(s.to_string());
+        let chars: Vec<char> = s.trim().chars().collect();
+        if chars.len() < 2 || chars.len() > 3 {
+            return Err(error());
+        }
+
+        let first = Quantity::from_symbol(chars[0]).ok_or_else(error)?;
+        let second = Quantity::from_symbol(chars[1]).ok_or_else(error)?;
+        let suited = match chars.get(2) {
+            Some('s') | Some('S') if first != second => true,
+            Some('o') | Some('O') if first != second => false,
+            None => false,
+            _ => return Err(error()),
+        };
+
+        Ok(StartingHand {
+            high: first.max(second),
+            low: first.min(second),
+            suited,
+        })
+    }
+}
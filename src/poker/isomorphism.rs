@@ -0,0 +1,119 @@
+//! Suit isomorphism: hands that only differ by renaming the suits play the same,
+//! so precomputed results can be shared between them.
+
+use crate::poker::card::{Card, Suit};
+use crate::poker::index::{binomial, set_index};
+
+/// A renaming of the suits, indexed by the position of a suit in `Suit::all()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SuitMapping {
+    suits: [Suit; 4],
+}
+
+impl SuitMapping {
+    pub fn identity() -> SuitMapping {
+        SuitMapping {
+            suits: [Suit::Spades, Suit::Clubs, Suit::Diamonds, Suit::Hearts],
+        }
+    }
+
+    /// All 24 permutations of the suits.
+    pub fn all() -> Vec<SuitMapping> {
+        let mut mappings = Vec::with_capacity(24);
+        permute(&mut Suit::all().to_vec(), 0, &mut mappings);
+        mappings
+    }
+
+    pub fn suit(&self, suit: Suit) -> Suit {
+        self.suits[position(suit)]
+    }
+
+    /// Renames the suit of the card.
+    pub fn apply(&self, card: &Card) -> Card {
+        Card {
+            quantity: card.quantity,
+            suit: self.suit(card.suit),
+        }
+    }
+
+    /// Translates a card of the renamed hand back to the original suits.
+    pub fn invert(&self, card: &Card) -> Card {
+        let original = self
+            .suits
+            .iter()
+            .position(|suit| *suit == card.suit)
+            .unwrap();
+        Card {
+            quantity: card.quantity,
+            suit: Suit::all()[original],
+        }
+    }
+}
+
+fn position(suit: Suit) -> usize {
+    Suit::all().iter().position(|s| *s == suit).unwrap()
+}
+
+fn permute(suits: &mut Vec<Suit>, start: usize, mappings: &mut Vec<SuitMapping>) {
+    if start == suits.len() {
+        mappings.push(SuitMapping {
+            suits: [suits[0], suits[1], suits[2], suits[3]],
+        });
+        return;
+    }
+    for i in start..suits.len() {
+        suits.swap(start, i);
+        permute(suits, start + 1, mappings);
+        suits.swap(start, i);
+    }
+}
+
+/// The representative of all suit relabellings of hole cards and a board.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Canonical {
+    /// Hole cards with canonical suits, sorted by card index.
+    pub hole: Vec<Card>,
+    /// Board cards with canonical suits, sorted by card index.
+    pub board: Vec<Card>,
+    /// Maps the original suits to the canonical ones, `invert` translates results back.
+    pub mapping: SuitMapping,
+    /// Equal for all isomorphic hands with the same number of cards, but not dense.
+    pub index: u64,
+}
+
+/// Chooses the relabelling whose sorted card indices are the smallest,
+/// comparing the hole cards first and the board second.
+/// The order of cards within the hole cards and within the board does not matter.
+pub fn canonicalize(hole: &[Card], board: &[Card]) -> Canonical {
+    let relabel = |mapping: &SuitMapping, cards: &[Card]| {
+        let mut cards: Vec<Card> = cards.iter().map(|card| mapping.apply(card)).collect();
+        cards.sort_by_key(Card::index);
+        cards
+    };
+    let key = |cards: &[Card]| cards.iter().map(Card::index).collect::<Vec<u8>>();
+
+    // The number of hole cards is the same for every relabelling,
+    // so comparing the concatenated indices compares the hole cards first.
+    let mut best: Option<(Vec<u8>, SuitMapping)> = None;
+    for mapping in SuitMapping::all() {
+        let mut key = key(&relabel(&mapping, hole));
+        key.extend(relabel(&mapping, board).iter().map(Card::index));
+
+        if best.as_ref().is_none_or(|(best, _)| key < *best) {
+            best = Some((key, mapping));
+        }
+    }
+
+    let (_, mapping) = best.unwrap();
+    let hole = relabel(&mapping, hole);
+    let board = relabel(&mapping, board);
+    let index = set_index(&hole) as u64 * binomial(52, board.len() as u32) as u64
+        + set_index(&board) as u64;
+
+    Canonical {
+        hole,
+        board,
+        mapping,
+        index,
+    }
+}
@@ -0,0 +1,125 @@
+//! Kuhn poker: three cards, one card each, an ante of one chip and a single bet of one chip.
+//!
+//! The first player checks or bets. After a check the second player checks or bets,
+//! and a bet is called or folded to. The higher card wins at showdown.
+//! In equilibrium the first player loses 1/18 of a chip per hand.
+
+use crate::poker::card::{Card, Quantity, Suit};
+use crate::poker::cfr::{code, GameTree};
+use crate::poker::deck::Deck;
+use crate::poker::game::Action;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KuhnState {
+    /// Cards of the two players, empty before the deal.
+    pub cards: Vec<Card>,
+    pub history: Vec<Action>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kuhn {
+    pub deck: Deck,
+}
+
+impl Kuhn {
+    /// The jack, queen and king of spades.
+    pub fn new() -> Kuhn {
+        let cards = [Quantity::Jack, Quantity::Queen, Quantity::King]
+            .iter()
+            .map(|quantity| Card {
+                quantity: *quantity,
+                suit: Suit::Spades,
+            })
+            .collect();
+        Kuhn {
+            deck: Deck { cards },
+        }
+    }
+}
+
+impl Default for Kuhn {
+    fn default() -> Self {
+        Kuhn::new()
+    }
+}
+
+impl GameTree for Kuhn {
+    type State = KuhnState;
+
+    fn root(&self) -> KuhnState {
+        KuhnState {
+            cards: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    fn is_terminal(&self, state: &KuhnState) -> bool {
+        matches!(
+            state.history[..],
+            [Action::Check, Action::Check] | [.., Action::Call] | [.., Action::Fold]
+        )
+    }
+
+    fn utility(&self, state: &KuhnState, player: usize) -> f64 {
+        let last = state.history.len() - 1;
+        let (winner, amount) = match state.history[last] {
+            // The player before the one who folded wins the ante.
+            Action::Fold => ((last + 1) % 2, 1.0),
+            Action::Call => (showdown(&state.cards), 2.0),
+            _ => (showdown(&state.cards), 1.0),
+        };
+        if winner == player {
+            amount
+        } else {
+            -amount
+        }
+    }
+
+    fn is_chance(&self, state: &KuhnState) -> bool {
+        state.cards.is_empty()
+    }
+
+    fn outcomes(&self, _: &KuhnState) -> Vec<(KuhnState, f64)> {
+        let cards = &self.deck.cards;
+        let deals = (cards.len() * (cards.len() - 1)) as f64;
+        let mut outcomes = Vec::new();
+        for first in cards.iter() {
+            for second in cards.iter().filter(|card| *card != first) {
+                let state = KuhnState {
+                    cards: vec![*first, *second],
+                    history: Vec::new(),
+                };
+                outcomes.push((state, 1.0 / deals));
+            }
+        }
+        outcomes
+    }
+
+    fn player(&self, state: &KuhnState) -> usize {
+        state.history.len() % 2
+    }
+
+    fn actions(&self, state: &KuhnState) -> Vec<Action> {
+        match state.history.last() {
+            Some(Action::Bet(_)) => vec![Action::Fold, Action::Call],
+            _ => vec![Action::Check, Action::Bet(1)],
+        }
+    }
+
+    fn apply(&self, state: &KuhnState, action: Action) -> KuhnState {
+        let mut next = state.clone();
+        next.history.push(action);
+        next
+    }
+
+    fn info_set(&self, state: &KuhnState) -> String {
+        let history: String = state.history.iter().map(|action| code(*action)).collect();
+        let card = state.cards[self.player(state)];
+        format!("{} {}", card.quantity.symbol(), history)
+    }
+}
+
+/// Index of the player with the higher card.
+fn showdown(cards: &[Card]) -> usize {
+    usize::from(cards[1].quantity > cards[0].quantity)
+}
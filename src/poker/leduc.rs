@@ -0,0 +1,202 @@
+//! Leduc Hold'em: six cards, a private card each and one public card.
+//!
+//! The deck has two jacks, two queens and two kings. Both players ante one chip.
+//! There are two betting rounds with bets of two and then four chips and at most
+//! a bet and a raise in each round, the first player acting first in both.
+//! The public card is dealt between the rounds. A player who pairs it wins,
+//! otherwise the higher card wins and equal cards split the pot.
+
+use crate::poker::card::{Card, Quantity, Suit};
+use crate::poker::cfr::{code, GameTree};
+use crate::poker::deck::Deck;
+use crate::poker::game::Action;
+
+const ANTE: u64 = 1;
+const BETS: [u64; 2] = [2, 4];
+/// Bets and raises in one round.
+const MAX_BETS: usize = 2;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeducState {
+    /// Private cards of the two players, empty before the deal.
+    pub hole: Vec<Card>,
+    pub board: Option<Card>,
+    /// Actions of each round so far, amounts being the totals of the round.
+    pub rounds: Vec<Vec<Action>>,
+    /// Chips put in during the current round.
+    pub street: [u64; 2],
+    /// Chips put in during the whole hand.
+    pub committed: [u64; 2],
+}
+
+impl LeducState {
+    fn actions(&self) -> &[Action] {
+        self.rounds.last().map_or(&[], Vec::as_slice)
+    }
+
+    fn is_round_over(&self) -> bool {
+        matches!(
+            self.actions(),
+            [Action::Check, Action::Check] | [.., Action::Call] | [.., Action::Fold]
+        )
+    }
+
+    fn folded(&self) -> bool {
+        self.actions().last() == Some(&Action::Fold)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Leduc {
+    pub deck: Deck,
+}
+
+impl Leduc {
+    /// Jacks, queens and kings of spades and hearts.
+    pub fn new() -> Leduc {
+        let mut cards = Vec::new();
+        for quantity in [Quantity::Jack, Quantity::Queen, Quantity::King] {
+            for suit in [Suit::Spades, Suit::Hearts] {
+                cards.push(Card { quantity, suit });
+            }
+        }
+        Leduc {
+            deck: Deck { cards },
+        }
+    }
+}
+
+impl Default for Leduc {
+    fn default() -> Self {
+        Leduc::new()
+    }
+}
+
+impl GameTree for Leduc {
+    type State = LeducState;
+
+    fn root(&self) -> LeducState {
+        LeducState {
+            hole: Vec::new(),
+            board: None,
+            rounds: Vec::new(),
+            street: [0; 2],
+            committed: [ANTE; 2],
+        }
+    }
+
+    fn is_terminal(&self, state: &LeducState) -> bool {
+        state.folded() || (state.rounds.len() == 2 && state.is_round_over())
+    }
+
+    fn utility(&self, state: &LeducState, player: usize) -> f64 {
+        let other = 1 - player;
+        let winner = if state.folded() {
+            // The player who did not fold wins.
+            Some(state.actions().len() % 2)
+        } else {
+            showdown(&state.hole, state.board.unwrap())
+        };
+        match winner {
+            Some(winner) if winner == player => state.committed[other] as f64,
+            Some(_) => -(state.committed[player] as f64),
+            None => 0.0,
+        }
+    }
+
+    fn is_chance(&self, state: &LeducState) -> bool {
+        state.hole.is_empty() || (state.board.is_none() && state.is_round_over())
+    }
+
+    fn outcomes(&self, state: &LeducState) -> Vec<(LeducState, f64)> {
+        let cards = &self.deck.cards;
+        let mut outcomes = Vec::new();
+        if state.hole.is_empty() {
+            let deals = (cards.len() * (cards.len() - 1)) as f64;
+            for first in cards.iter() {
+                for second in cards.iter().filter(|card| *card != first) {
+                    let mut next = state.clone();
+                    next.hole = vec![*first, *second];
+                    next.rounds.push(Vec::new());
+                    outcomes.push((next, 1.0 / deals));
+                }
+            }
+        } else {
+            let remaining: Vec<&Card> = cards
+                .iter()
+                .filter(|card| !state.hole.contains(card))
+                .collect();
+            for card in remaining.iter() {
+                let mut next = state.clone();
+                next.board = Some(**card);
+                next.rounds.push(Vec::new());
+                next.street = [0; 2];
+                outcomes.push((next, 1.0 / remaining.len() as f64));
+            }
+        }
+        outcomes
+    }
+
+    fn player(&self, state: &LeducState) -> usize {
+        state.actions().len() % 2
+    }
+
+    fn actions(&self, state: &LeducState) -> Vec<Action> {
+        let player = self.player(state);
+        let size = BETS[state.rounds.len() - 1];
+        let bets = state
+            .actions()
+            .iter()
+            .filter(|action| matches!(action, Action::Bet(_) | Action::Raise(_)))
+            .count();
+        let facing = state.street[1 - player];
+        if facing == state.street[player] {
+            vec![Action::Check, Action::Bet(size)]
+        } else if bets < MAX_BETS {
+            vec![Action::Fold, Action::Call, Action::Raise(facing + size)]
+        } else {
+            vec![Action::Fold, Action::Call]
+        }
+    }
+
+    fn apply(&self, state: &LeducState, action: Action) -> LeducState {
+        let player = self.player(state);
+        let mut next = state.clone();
+        let added = match action {
+            Action::Call => state.street[1 - player] - state.street[player],
+            Action::Bet(to) | Action::Raise(to) => to - state.street[player],
+            Action::Fold | Action::Check => 0,
+        };
+        next.street[player] += added;
+        next.committed[player] += added;
+        next.rounds.last_mut().unwrap().push(action);
+        next
+    }
+
+    fn info_set(&self, state: &LeducState) -> String {
+        let rounds: Vec<String> = state
+            .rounds
+            .iter()
+            .map(|round| round.iter().map(|action| code(*action)).collect())
+            .collect();
+        let hole = state.hole[self.player(state)].quantity.symbol();
+        let board = state.board.map_or(' ', |card| card.quantity.symbol());
+        format!("{}{} {}", hole, board, rounds.join("/"))
+    }
+}
+
+/// The player who pairs the board or has the higher card, `None` for a split pot.
+fn showdown(hole: &[Card], board: Card) -> Option<usize> {
+    let paired: Vec<bool> = hole
+        .iter()
+        .map(|card| card.quantity == board.quantity)
+        .collect();
+    if paired[0] != paired[1] {
+        return Some(usize::from(paired[1]));
+    }
+    match hole[0].quantity.cmp(&hole[1].quantity) {
+        std::cmp::Ordering::Greater => Some(0),
+        std::cmp::Ordering::Less => Some(1),
+        std::cmp::Ordering::Equal => None,
+    }
+}
@@ -0,0 +1,265 @@
+//! Outs of a drawing hand on the flop or the turn.
+//!
+//! An out is an unseen card that improves the hand to a better category,
+//! or that puts it ahead of a known opponent hand.
+//! Improvements that the board makes for everyone, such as a fourth card
+//! to a straight on the board, are not counted.
+
+use std::fmt::Display;
+
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::deck::Deck;
+use crate::poker::equity::for_each_subset;
+use crate::poker::evaluator::{category, evaluate_mask, mask, ranks, straight_high};
+use crate::poker::rules::Category;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Draw {
+    Flush,
+    /// Straight draws completed by two quantities, double gutshots included.
+    OpenEnded,
+    Gutshot,
+    /// Three of a kind made with a pocket pair.
+    Set,
+    /// Any other improvement, such as pairing a hole card.
+    Other,
+    /// Flush on the flop that needs both the turn and the river.
+    BackdoorFlush,
+    /// Straight on the flop that needs both the turn and the river.
+    BackdoorStraight,
+}
+
+impl Draw {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Draw::Flush => "Flush draw",
+            Draw::OpenEnded => "Open-ended straight draw",
+            Draw::Gutshot => "Gutshot",
+            Draw::Set => "Set",
+            Draw::Other => "Other",
+            Draw::BackdoorFlush => "Backdoor flush draw",
+            Draw::BackdoorStraight => "Backdoor straight draw",
+        }
+    }
+}
+
+impl Display for Draw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Out {
+    pub card: Card,
+    pub draw: Draw,
+    /// Category of the hand after the card is dealt.
+    pub category: Category,
+}
+
+/// A runner-runner draw with the chance of hitting it by the river.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Backdoor {
+    pub draw: Draw,
+    pub probability: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outs {
+    /// Category of the hand on the current board.
+    pub category: Category,
+    pub outs: Vec<Out>,
+    /// Backdoor draws, only found on the flop.
+    pub backdoors: Vec<Backdoor>,
+    /// Number of cards that can still be dealt.
+    pub unseen: usize,
+    /// Rule of 2 and 4: four percent per out on the flop and two on the turn.
+    pub estimate: f64,
+    /// Exact chance to improve, or to finish ahead of the opponent, by the river.
+    pub probability: f64,
+}
+
+impl Outs {
+    pub fn cards(&self, draw: Draw) -> Vec<Card> {
+        self.outs
+            .iter()
+            .filter(|out| out.draw == draw)
+            .map(|out| out.card)
+            .collect()
+    }
+
+    /// Outs grouped by draw, in the order of `Draw`.
+    pub fn groups(&self) -> Vec<(Draw, Vec<Card>)> {
+        let mut draws: Vec<Draw> = self.outs.iter().map(|out| out.draw).collect();
+        draws.sort_unstable();
+        draws.dedup();
+        draws
+            .into_iter()
+            .map(|draw| (draw, self.cards(draw)))
+            .collect()
+    }
+}
+
+/// Finds the cards that improve the hand to a better category.
+/// Panics if the board does not have three or four cards or cards are dealt twice.
+pub fn outs(hole: &HoleCards, board: &[Card]) -> Outs {
+    analyze(hole, board, None)
+}
+
+/// Finds the cards that put the hand ahead of the opponent.
+/// A hand that is already ahead has no outs.
+pub fn outs_against(hole: &HoleCards, board: &[Card], opponent: &HoleCards) -> Outs {
+    analyze(hole, board, Some(opponent))
+}
+
+fn analyze(hole: &HoleCards, board: &[Card], opponent: Option<&HoleCards>) -> Outs {
+    assert!(
+        board.len() == 3 || board.len() == 4,
+        "Outs need a board of three or four cards."
+    );
+    let mut deck = Deck::create();
+    for card in hole
+        .iter()
+        .chain(board)
+        .chain(opponent.into_iter().flatten())
+    {
+        assert!(deck.remove(card), "Card {} is dealt twice.", card);
+    }
+    let unseen = deck.cards;
+
+    let hole_mask = mask(hole);
+    let board_mask = mask(board);
+    let current = evaluate_mask(hole_mask | board_mask);
+    let ahead = |board: u64| {
+        let strength = evaluate_mask(hole_mask | board);
+        match opponent {
+            Some(opponent) => strength > evaluate_mask(mask(opponent) | board),
+            None => {
+                category(strength) > category(current)
+                    && category(strength) > category(evaluate_mask(board))
+            }
+        }
+    };
+
+    let mut outs = Vec::new();
+    if !(opponent.is_some() && ahead(board_mask)) {
+        for card in unseen.iter() {
+            let board = board_mask | 1 << card.index();
+            if ahead(board) {
+                let strength = evaluate_mask(hole_mask | board);
+                outs.push(Out {
+                    card: *card,
+                    draw: classify(hole, hole_mask | board_mask, card, category(strength)),
+                    category: category(strength),
+                });
+            }
+        }
+    }
+
+    let mut hits = 0;
+    let mut runouts = 0;
+    for_each_subset(&unseen, 5 - board.len(), board_mask, &mut |board| {
+        if ahead(board) {
+            hits += 1;
+        }
+        runouts += 1;
+    });
+
+    let backdoors = if board.len() == 3 {
+        backdoors(hole, board_mask, &unseen)
+    } else {
+        Vec::new()
+    };
+    let percent = if board.len() == 3 { 4.0 } else { 2.0 };
+
+    Outs {
+        category: category(current),
+        estimate: (outs.len() as f64 * percent / 100.0).min(1.0),
+        outs,
+        backdoors,
+        unseen: unseen.len(),
+        probability: hits as f64 / runouts as f64,
+    }
+}
+
+/// The draw that a single card completes, judged by the cards before it.
+fn classify(hole: &HoleCards, cards: u64, card: &Card, category: Category) -> Draw {
+    let flush = matches!(
+        category,
+        Category::Flush | Category::StraightFlush | Category::FlushRoyal
+    );
+    if flush && suited(cards, card.index() as u32 % 4) >= 4 {
+        return Draw::Flush;
+    }
+
+    let straight = matches!(
+        category,
+        Category::Straight | Category::StraightFlush | Category::FlushRoyal
+    );
+    if straight {
+        let ranks = ranks(cards);
+        let completing = (0..13)
+            .filter(|rank| ranks & 1 << rank == 0 && straight_high(ranks | 1 << rank).is_some())
+            .count();
+        return if completing >= 2 {
+            Draw::OpenEnded
+        } else {
+            Draw::Gutshot
+        };
+    }
+
+    if hole[0].quantity == hole[1].quantity && card.quantity == hole[0].quantity {
+        return Draw::Set;
+    }
+    Draw::Other
+}
+
+/// Number of cards of the suit, given by its position in `Suit::all()`.
+fn suited(cards: u64, suit: u32) -> u32 {
+    (0..13)
+        .filter(|rank| cards >> (rank * 4 + suit) & 1 != 0)
+        .count() as u32
+}
+
+fn backdoors(hole: &HoleCards, board: u64, unseen: &[Card]) -> Vec<Backdoor> {
+    let cards = mask(hole) | board;
+    let flush_suits: Vec<u32> = (0..4)
+        .filter(|suit| suited(cards, *suit) == 3 && suited(mask(hole), *suit) > 0)
+        .collect();
+    let straight = |cards: u64| straight_high(ranks(cards)).is_some();
+
+    let mut flushes = 0;
+    let mut straights = 0;
+    let mut runouts = 0;
+    for (i, turn) in unseen.iter().enumerate() {
+        for river in unseen[i + 1..].iter() {
+            runouts += 1;
+            let suit = turn.index() as u32 % 4;
+            if suit == river.index() as u32 % 4 && flush_suits.contains(&suit) {
+                flushes += 1;
+            }
+
+            let turn = 1 << turn.index();
+            let river = 1 << river.index();
+            if straight(cards | turn | river)
+                && !straight(cards | turn)
+                && !straight(cards | river)
+                && !straight(board | turn | river)
+            {
+                straights += 1;
+            }
+        }
+    }
+
+    [
+        (Draw::BackdoorFlush, flushes),
+        (Draw::BackdoorStraight, straights),
+    ]
+    .iter()
+    .filter(|(_, hits)| *hits > 0)
+    .map(|(draw, hits)| Backdoor {
+        draw: *draw,
+        probability: *hits as f64 / runouts as f64,
+    })
+    .collect()
+}
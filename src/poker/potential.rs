@@ -0,0 +1,132 @@
+//! Hand strength and hand potential on the flop and the turn.
+//!
+//! Hand strength (HS) is the share of opponent holdings the hand beats now.
+//! Positive potential (PPOT) is the chance that a hand which is behind gets ahead by the river,
+//! negative potential (NPOT) the chance that a hand which is ahead falls behind.
+//! Ties count as half in all of them, following Billings et al.
+
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::deck::Deck;
+use crate::poker::equity::for_each_subset;
+use crate::poker::evaluator::{evaluate_mask, mask};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Potential {
+    pub hs: f64,
+    pub ppot: f64,
+    pub npot: f64,
+    /// Effective hand strength: `hs * (1 - npot) + (1 - hs) * ppot`.
+    pub ehs: f64,
+}
+
+impl Potential {
+    /// The optimistic effective hand strength, `hs + (1 - hs) * ppot`, which ignores NPOT.
+    pub fn optimistic(&self) -> f64 {
+        self.hs + (1.0 - self.hs) * self.ppot
+    }
+}
+
+const AHEAD: usize = 0;
+const TIED: usize = 1;
+const BEHIND: usize = 2;
+
+/// Computes the metrics against every holding an opponent can have, all equally likely.
+/// Panics if the board does not have three or four cards or cards are dealt twice.
+pub fn potential(hole: &HoleCards, board: &[Card]) -> Potential {
+    let mut deck = Deck::create();
+    for card in hole.iter().chain(board) {
+        deck.remove(card);
+    }
+    let mut range = Vec::with_capacity(1081);
+    for (i, first) in deck.cards.iter().enumerate() {
+        for second in deck.cards[i + 1..].iter() {
+            range.push(([*first, *second], 1.0));
+        }
+    }
+    potential_against(hole, board, &range)
+}
+
+/// Computes the metrics against weighted opponent holdings.
+/// Holdings that share a card with the hole cards or the board are skipped.
+pub fn potential_against(
+    hole: &HoleCards,
+    board: &[Card],
+    range: &[(HoleCards, f64)],
+) -> Potential {
+    assert!(
+        board.len() == 3 || board.len() == 4,
+        "Hand potential needs a board of three or four cards."
+    );
+    let mut deck = Deck::create();
+    for card in hole.iter().chain(board) {
+        assert!(deck.remove(card), "Card {} is dealt twice.", card);
+    }
+
+    // The strength of the hole cards on every runout is the same for all opponents.
+    let hole_mask = mask(hole);
+    let board_mask = mask(board);
+    let mut runouts = Vec::new();
+    for_each_subset(&deck.cards, 5 - board.len(), board_mask, &mut |board| {
+        runouts.push((board, evaluate_mask(hole_mask | board)));
+    });
+
+    let current = evaluate_mask(hole_mask | board_mask);
+    let mut totals = [0.0; 3];
+    let mut transitions = [[0.0; 3]; 3];
+    for (opponent, weight) in range.iter() {
+        let opponent_mask = mask(opponent);
+        if opponent_mask & (hole_mask | board_mask) != 0 || *weight <= 0.0 {
+            continue;
+        }
+        let now = compare(current, evaluate_mask(opponent_mask | board_mask));
+        for (board, strength) in runouts.iter() {
+            if board & opponent_mask != 0 {
+                continue;
+            }
+            let river = compare(*strength, evaluate_mask(opponent_mask | board));
+            totals[now] += weight;
+            transitions[now][river] += weight;
+        }
+    }
+
+    let all: f64 = totals.iter().sum();
+    if all == 0.0 {
+        return Potential::default();
+    }
+    let hs = (totals[AHEAD] + totals[TIED] / 2.0) / all;
+    let ppot = ratio(
+        transitions[BEHIND][AHEAD]
+            + transitions[BEHIND][TIED] / 2.0
+            + transitions[TIED][AHEAD] / 2.0,
+        totals[BEHIND] + totals[TIED] / 2.0,
+    );
+    let npot = ratio(
+        transitions[AHEAD][BEHIND]
+            + transitions[TIED][BEHIND] / 2.0
+            + transitions[AHEAD][TIED] / 2.0,
+        totals[AHEAD] + totals[TIED] / 2.0,
+    );
+
+    Potential {
+        hs,
+        ppot,
+        npot,
+        ehs: hs * (1.0 - npot) + (1.0 - hs) * ppot,
+    }
+}
+
+fn compare(strength: u32, opponent: u32) -> usize {
+    match strength.cmp(&opponent) {
+        std::cmp::Ordering::Greater => AHEAD,
+        std::cmp::Ordering::Equal => TIED,
+        std::cmp::Ordering::Less => BEHIND,
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
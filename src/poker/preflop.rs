@@ -0,0 +1,284 @@
+//! Heads-up all-in preflop equities between the 169 starting hand classes.
+//!
+//! Equities are exact: every board is dealt for every pair of hole cards,
+//! and pairs of hole cards that only differ by suits are computed once.
+//! A class against a class is the average over all their combos that do not share a card.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::poker::card::HoleCards;
+use crate::poker::equity::exact;
+use crate::poker::evaluator::{evaluate_mask, mask};
+use crate::poker::index::{StartingHand, STARTING_HANDS};
+use crate::poker::isomorphism::canonicalize;
+use crate::poker::range::Range;
+
+const MAGIC: &[u8; 4] = b"RPPF";
+const VERSION: u32 = 1;
+const SIZE: usize = STARTING_HANDS as usize;
+
+/// Equity of every starting hand class against every other one, looked up in constant time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreflopTable {
+    equities: Vec<f32>,
+}
+
+impl PreflopTable {
+    pub fn generate(threads: usize) -> PreflopTable {
+        PreflopTable::generate_with(threads, |_, _| {})
+    }
+
+    /// Generates the table, calling `progress` with the number of computed
+    /// and the total number of distinct matchups.
+    pub fn generate_with<F>(threads: usize, progress: F) -> PreflopTable
+    where
+        F: Fn(usize, usize) + Sync,
+    {
+        let mut matchups = Matchups::default();
+        let mut classes: Vec<(usize, usize, Vec<usize>)> = Vec::new();
+        for hero in 0..SIZE {
+            for villain in hero..SIZE {
+                let ids = matchups.add_classes(hero as u8, villain as u8);
+                classes.push((hero, villain, ids));
+            }
+        }
+
+        let results = matchups.compute(threads, &progress);
+        let mut equities = vec![0.0; SIZE * SIZE];
+        for (hero, villain, ids) in classes {
+            let total: f64 = ids.iter().map(|id| results[*id]).sum();
+            let equity = total / ids.len() as f64;
+            equities[hero * SIZE + villain] = equity as f32;
+            equities[villain * SIZE + hero] = (1.0 - equity) as f32;
+        }
+        PreflopTable { equities }
+    }
+
+    /// Estimates every matchup from random boards, which takes seconds instead of hours.
+    /// The pairs of combos of two classes take turns, so every pair is dealt
+    /// when there are enough trials. The result does not depend on the number of threads.
+    pub fn estimate(trials: usize, threads: usize, seed: u64) -> PreflopTable {
+        let classes: Vec<(usize, usize)> = (0..SIZE)
+            .flat_map(|hero| (hero..SIZE).map(move |villain| (hero, villain)))
+            .collect();
+
+        let results = parallel(classes.len(), threads, &|_, _| {}, |id| {
+            let (hero, villain) = classes[id];
+            let hero = StartingHand::from_index(hero as u8).unwrap();
+            let villain = StartingHand::from_index(villain as u8).unwrap();
+            let mut pairs = Vec::new();
+            for first in hero.combos() {
+                for second in villain.combos() {
+                    if !first.iter().any(|card| second.contains(card)) {
+                        pairs.push([first, second]);
+                    }
+                }
+            }
+
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(id as u64));
+            let mut total = 0.0;
+            for trial in 0..trials.max(1) {
+                let hands = &pairs[trial % pairs.len()];
+                let dealt = mask(&hands[0]) | mask(&hands[1]);
+                let mut board = dealt;
+                while board.count_ones() < 9 {
+                    board |= 1 << rng.gen_range(0..52);
+                }
+                let board = board & !dealt;
+                let first = evaluate_mask(board | mask(&hands[0]));
+                let second = evaluate_mask(board | mask(&hands[1]));
+                total += match first.cmp(&second) {
+                    std::cmp::Ordering::Greater => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Less => 0.0,
+                };
+            }
+            total / trials.max(1) as f64
+        });
+
+        let mut equities = vec![0.0; SIZE * SIZE];
+        for ((hero, villain), equity) in classes.into_iter().zip(results) {
+            equities[hero * SIZE + villain] = equity as f32;
+            equities[villain * SIZE + hero] = (1.0 - equity) as f32;
+        }
+        PreflopTable { equities }
+    }
+
+    pub fn equity(&self, hero: StartingHand, villain: StartingHand) -> f64 {
+        self.equities[hero.index() as usize * SIZE + villain.index() as usize] as f64
+    }
+
+    /// Equity of a class against a range, weighting every class of the range
+    /// by the combos left after removing the cards of the hero.
+    /// Returns `None` when the range is empty.
+    pub fn equity_against(&self, hero: StartingHand, range: &Range) -> Option<f64> {
+        let mut total = 0.0;
+        let mut equity = 0.0;
+        for villain in range.classes() {
+            let combos: usize = hero
+                .combos()
+                .iter()
+                .map(|first| {
+                    villain
+                        .combos()
+                        .iter()
+                        .filter(|second| !first.iter().any(|card| second.contains(card)))
+                        .count()
+                })
+                .sum();
+            let weight = range.weight(villain) * combos as f64;
+            equity += weight * self.equity(hero, villain);
+            total += weight;
+        }
+        if total == 0.0 {
+            None
+        } else {
+            Some(equity / total)
+        }
+    }
+
+    /// Writes a header followed by the equities as little endian floats,
+    /// rows by the hero class index.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(SIZE as u32).to_le_bytes())?;
+        for equity in self.equities.iter() {
+            writer.write_all(&equity.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<PreflopTable> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("Not a preflop equity table."));
+        }
+        if read_u32(reader)? != VERSION {
+            return Err(invalid("Unsupported preflop equity table version."));
+        }
+        if read_u32(reader)? as usize != SIZE {
+            return Err(invalid("Unexpected number of starting hands."));
+        }
+
+        let mut equities = Vec::with_capacity(SIZE * SIZE);
+        for _ in 0..SIZE * SIZE {
+            let mut bytes = [0; 4];
+            reader.read_exact(&mut bytes)?;
+            equities.push(f32::from_le_bytes(bytes));
+        }
+        Ok(PreflopTable { equities })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<PreflopTable> {
+        PreflopTable::read(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Exact equity of one class against another, averaged over their combos.
+pub fn class_equity(hero: StartingHand, villain: StartingHand, threads: usize) -> f64 {
+    let mut matchups = Matchups::default();
+    let ids = matchups.add_classes(hero.index(), villain.index());
+    let results = matchups.compute(threads, &|_, _| {});
+    ids.iter().map(|id| results[*id]).sum::<f64>() / ids.len() as f64
+}
+
+/// Distinct pairs of hole cards up to suit isomorphism.
+#[derive(Default)]
+struct Matchups {
+    ids: HashMap<u64, usize>,
+    hands: Vec<[HoleCards; 2]>,
+}
+
+impl Matchups {
+    /// Returns the matchup of every pair of combos of the classes that do not share a card.
+    fn add_classes(&mut self, hero: u8, villain: u8) -> Vec<usize> {
+        let hero = StartingHand::from_index(hero).unwrap();
+        let villain = StartingHand::from_index(villain).unwrap();
+        let mut ids = Vec::new();
+        for first in hero.combos() {
+            for second in villain.combos() {
+                if first.iter().any(|card| second.contains(card)) {
+                    continue;
+                }
+                let key = canonicalize(&first, &second).index;
+                let next = self.hands.len();
+                let id = *self.ids.entry(key).or_insert(next);
+                if id == next {
+                    self.hands.push([first, second]);
+                }
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    /// Computes the equity of the first hand of every matchup,
+    /// spreading the matchups over the threads.
+    fn compute<F: Fn(usize, usize) + Sync>(&self, threads: usize, progress: &F) -> Vec<f64> {
+        parallel(self.hands.len(), threads, progress, |id| {
+            exact(&self.hands[id], &[], 1)[0].equity
+        })
+    }
+}
+
+/// Runs the job for every id from zero to `total`, taking the next id on whichever thread is free.
+fn parallel<F, J>(total: usize, threads: usize, progress: &F, job: J) -> Vec<f64>
+where
+    F: Fn(usize, usize) + Sync,
+    J: Fn(usize) -> f64 + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+
+    let results: Vec<Vec<(usize, f64)>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads.max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let id = next.fetch_add(1, Ordering::Relaxed);
+                        if id >= total {
+                            return results;
+                        }
+                        results.push((id, job(id)));
+                        progress(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Preflop thread panicked."))
+            .collect()
+    });
+
+    let mut values = vec![0.0; total];
+    for (id, value) in results.into_iter().flatten() {
+        values[id] = value;
+    }
+    values
+}
@@ -0,0 +1,152 @@
+//! Heads-up push/fold equilibrium for short stacks.
+//!
+//! The small blind either moves all in or folds, and the big blind either calls or folds.
+//! Both players are assumed to have the effective stack, blinds and antes included.
+//! The equilibrium is found by fictitious play: each player repeatedly plays the best response
+//! to the average strategy of the other one, weighting classes by the number of combos
+//! that are left after card removal.
+
+use crate::poker::index::{StartingHand, STARTING_HANDS};
+use crate::poker::preflop::PreflopTable;
+use crate::poker::range::Range;
+
+const SIZE: usize = STARTING_HANDS as usize;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PushFold {
+    pub stack: f64,
+    pub small_blind: f64,
+    pub big_blind: f64,
+    /// Ante paid by each player.
+    pub ante: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    /// Classes the small blind moves all in with.
+    pub push: Range,
+    /// Classes the big blind calls with.
+    pub call: Range,
+    /// Expected chips the small blind wins per hand, against what it has before posting.
+    pub value: f64,
+}
+
+impl PushFold {
+    /// A game without antes with the stack given in big blinds.
+    pub fn in_big_blinds(stack: f64) -> PushFold {
+        PushFold {
+            stack,
+            small_blind: 0.5,
+            big_blind: 1.0,
+            ante: 0.0,
+        }
+    }
+
+    pub fn solve(&self, table: &PreflopTable, iterations: usize) -> Solution {
+        let hands = StartingHand::all();
+        let combos = combos(&hands);
+        let equities: Vec<f64> = hands
+            .iter()
+            .flat_map(|hero| {
+                hands
+                    .iter()
+                    .map(move |villain| table.equity(*hero, *villain))
+            })
+            .collect();
+
+        let mut push = vec![0.0; SIZE];
+        let mut call = vec![0.0; SIZE];
+        for iteration in 0..iterations.max(1) {
+            let step = 1.0 / (iteration + 1) as f64;
+            let push_response = self.push_response(&call, &combos, &equities);
+            let call_response = self.call_response(&push, &combos, &equities);
+            for class in 0..SIZE {
+                push[class] += (response(push_response[class]) - push[class]) * step;
+                call[class] += (response(call_response[class]) - call[class]) * step;
+            }
+        }
+
+        // The final ranges are pure best responses to the average strategies.
+        let push_values = self.push_response(&call, &combos, &equities);
+        let call_values = self.call_response(&push, &combos, &equities);
+        let mut solution = Solution {
+            push: Range::empty(),
+            call: Range::empty(),
+            value: 0.0,
+        };
+        let mut total = 0.0;
+        for (class, hand) in hands.iter().enumerate() {
+            solution.push.set(*hand, response(push_values[class]));
+            solution.call.set(*hand, response(call_values[class]));
+
+            let weight = hand.combos().len() as f64;
+            solution.value += weight * push_values[class].max(0.0);
+            total += weight;
+        }
+        solution.value = solution.value / total - self.small_blind - self.ante;
+        solution
+    }
+
+    /// Gain of pushing over folding for every class of the small blind.
+    fn push_response(&self, call: &[f64], combos: &[f64], equities: &[f64]) -> Vec<f64> {
+        (0..SIZE)
+            .map(|hero| {
+                let mut total = 0.0;
+                let mut value = 0.0;
+                for villain in 0..SIZE {
+                    let weight = combos[hero * SIZE + villain];
+                    let called = self.stack * (2.0 * equities[hero * SIZE + villain] - 1.0);
+                    let folded = self.big_blind + self.ante;
+                    value += weight * (call[villain] * called + (1.0 - call[villain]) * folded);
+                    total += weight;
+                }
+                value / total + self.small_blind + self.ante
+            })
+            .collect()
+    }
+
+    /// Gain of calling over folding for every class of the big blind.
+    fn call_response(&self, push: &[f64], combos: &[f64], equities: &[f64]) -> Vec<f64> {
+        (0..SIZE)
+            .map(|hero| {
+                let mut total = 0.0;
+                let mut value = 0.0;
+                for villain in 0..SIZE {
+                    let weight = combos[hero * SIZE + villain] * push[villain];
+                    value += weight * self.stack * (2.0 * equities[hero * SIZE + villain] - 1.0);
+                    total += weight;
+                }
+                if total == 0.0 {
+                    // Nothing is pushed, so any call is as good as a fold.
+                    return 0.0;
+                }
+                value / total + self.big_blind + self.ante
+            })
+            .collect()
+    }
+}
+
+fn response(gain: f64) -> f64 {
+    if gain >= 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Number of pairs of combos of two classes that do not share a card.
+fn combos(hands: &[StartingHand]) -> Vec<f64> {
+    let mut combos = vec![0.0; SIZE * SIZE];
+    for (i, hero) in hands.iter().enumerate() {
+        for (j, villain) in hands.iter().enumerate() {
+            for first in hero.combos() {
+                combos[i * SIZE + j] += villain
+                    .combos()
+                    .iter()
+                    .filter(|second| !first.iter().any(|card| second.contains(card)))
+                    .count() as f64;
+            }
+        }
+    }
+    combos
+}
@@ -0,0 +1,291 @@
+//! Ranges of starting hands with the usual notation, such as "22+, A2s+, KTo+, 76s-54s".
+//!
+//! Every starting hand class has a weight between 0 and 1.
+//! "AK" without a suffix means both the suited and the offsuit class,
+//! and a weight other than 1 is written after a colon, as in "AKo:0.5".
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::poker::card::{HoleCards, Quantity};
+use crate::poker::index::{StartingHand, HOLE_CARDS, STARTING_HANDS};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Range {
+    weights: Vec<f64>,
+}
+
+impl Range {
+    pub fn empty() -> Range {
+        Range {
+            weights: vec![0.0; STARTING_HANDS as usize],
+        }
+    }
+
+    pub fn full() -> Range {
+        Range {
+            weights: vec![1.0; STARTING_HANDS as usize],
+        }
+    }
+
+    pub fn weight(&self, hand: StartingHand) -> f64 {
+        self.weights[hand.index() as usize]
+    }
+
+    pub fn set(&mut self, hand: StartingHand, weight: f64) {
+        self.weights[hand.index() as usize] = weight.clamp(0.0, 1.0);
+    }
+
+    pub fn contains(&self, hand: StartingHand) -> bool {
+        self.weight(hand) > 0.0
+    }
+
+    /// Classes with a positive weight, by their index.
+    pub fn classes(&self) -> Vec<StartingHand> {
+        StartingHand::all()
+            .into_iter()
+            .filter(|hand| self.contains(*hand))
+            .collect()
+    }
+
+    /// Every pair of hole cards in the range with its weight.
+    pub fn combos(&self) -> Vec<(HoleCards, f64)> {
+        self.classes()
+            .into_iter()
+            .flat_map(|hand| {
+                let weight = self.weight(hand);
+                hand.combos().into_iter().map(move |hole| (hole, weight))
+            })
+            .collect()
+    }
+
+    /// Weighted share of all 1326 pairs of hole cards.
+    pub fn share(&self) -> f64 {
+        StartingHand::all()
+            .into_iter()
+            .map(|hand| self.weight(hand) * hand.combos().len() as f64)
+            .sum::<f64>()
+            / HOLE_CARDS as f64
+    }
+
+    /// The range as a 13 by 13 chart with aces in the top left corner,
+    /// suited hands above the diagonal and offsuit hands below it.
+    /// Cells show the class, a percentage for partial weights or a dot when left out.
+    pub fn grid(&self) -> String {
+        let mut grid = String::new();
+        for row in Quantity::all().iter().rev() {
+            let cells: Vec<String> = Quantity::all()
+                .iter()
+                .rev()
+                .map(|column| {
+                    let hand = StartingHand {
+                        high: (*row).max(*column),
+                        low: (*row).min(*column),
+                        suited: row > column,
+                    };
+                    match self.weight(hand) {
+                        weight if weight >= 1.0 => format!("{:<3}", hand.to_string()),
+                        weight if weight > 0.0 => format!("{:>2}%", (weight * 100.0).round()),
+                        _ => String::from(" . "),
+                    }
+                })
+                .collect();
+            grid.push_str(cells.join(" ").trim_end());
+            grid.push('\n');
+        }
+        grid
+    }
+}
+
+/// Writes the classes with a full weight grouped into runs, then partial weights one by one.
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let full = |hand: StartingHand| self.weight(hand) >= 1.0;
+        let mut parts = Vec::new();
+
+        let pairs: Vec<StartingHand> = Quantity::all()
+            .iter()
+            .rev()
+            .map(|quantity| StartingHand {
+                high: *quantity,
+                low: *quantity,
+                suited: false,
+            })
+            .collect();
+        runs(&pairs, &full, &mut parts);
+
+        for suited in [true, false] {
+            for high in Quantity::all().iter().rev() {
+                let hands: Vec<StartingHand> = Quantity::all()
+                    .iter()
+                    .rev()
+                    .filter(|low| *low < high)
+                    .map(|low| StartingHand {
+                        high: *high,
+                        low: *low,
+                        suited,
+                    })
+                    .collect();
+                runs(&hands, &full, &mut parts);
+            }
+        }
+
+        for hand in StartingHand::all().into_iter().rev() {
+            let weight = self.weight(hand);
+            if weight > 0.0 && weight < 1.0 {
+                parts.push(format!("{}:{}", hand, weight));
+            }
+        }
+        f.write_str(&parts.join(", "))
+    }
+}
+
+/// Groups consecutive hands from the strongest down, where the first hand is the top of its row.
+fn runs<F: Fn(StartingHand) -> bool>(
+    hands: &[StartingHand],
+    included: &F,
+    parts: &mut Vec<String>,
+) {
+    let mut start = 0;
+    while start < hands.len() {
+        if !included(hands[start]) {
+            start += 1;
+            continue;
+        }
+        let mut end = start;
+        while end + 1 < hands.len() && included(hands[end + 1]) {
+            end += 1;
+        }
+        parts.push(if start == end {
+            hands[start].to_string()
+        } else if start == 0 {
+            format!("{}+", hands[end])
+        } else {
+            format!("{}-{}", hands[start], hands[end])
+        });
+        start = end + 1;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRangeError(pub String);
+
+impl Display for ParseRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid range \"{}\".", self.0)
+    }
+}
+
+impl std::error::Error for ParseRangeError {}
+
+impl FromStr for Range {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::empty();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let error = || ParseRangeError(part.to_string());
+            let (notation, weight) = match part.split_once(':') {
+                Some((notation, weight)) => {
+                    let weight: f64 = weight.trim().parse().map_err(|_| error())?;
+                    if !(0.0..=1.0).contains(&weight) {
+                        return Err(error());
+                    }
+                    (notation.trim(), weight)
+                }
+                None => (part, 1.0),
+            };
+            for hand in expand(notation).ok_or_else(error)? {
+                range.set(hand, weight);
+            }
+        }
+        Ok(range)
+    }
+}
+
+/// Classes of one part of a range, such as "QQ+", "A2s+", "K9o-K6o" or "AK".
+fn expand(notation: &str) -> Option<Vec<StartingHand>> {
+    if let Some(base) = notation.strip_suffix('+') {
+        let mut hands = Vec::new();
+        for hand in classes(base)? {
+            if hand.is_pair() {
+                hands.extend(between(hand, pair(Quantity::Ace)));
+            } else {
+                let top = Quantity::all()[hand.high as usize - 3];
+                hands.extend(between(hand, StartingHand { low: top, ..hand }));
+            }
+        }
+        return Some(hands);
+    }
+
+    if let Some((first, last)) = notation.split_once('-') {
+        let first = classes(first)?;
+        let last = classes(last)?;
+        if first.len() != last.len() {
+            return None;
+        }
+        let mut hands = Vec::new();
+        for (first, last) in first.into_iter().zip(last) {
+            let pairs = first.is_pair() && last.is_pair();
+            let same_row = first.high == last.high;
+            let same_gap = first.high as u8 - first.low as u8 == last.high as u8 - last.low as u8;
+            if first.suited != last.suited || !(pairs || same_row || same_gap) {
+                return None;
+            }
+            hands.extend(between(first, last));
+        }
+        return Some(hands);
+    }
+
+    classes(notation)
+}
+
+/// One class, or both the suited and the offsuit class when the suffix is missing.
+fn classes(notation: &str) -> Option<Vec<StartingHand>> {
+    let hand: StartingHand = notation.trim().parse().ok()?;
+    if notation.trim().chars().count() == 2 && !hand.is_pair() {
+        return Some(vec![
+            StartingHand {
+                suited: true,
+                ..hand
+            },
+            hand,
+        ]);
+    }
+    Some(vec![hand])
+}
+
+fn pair(quantity: Quantity) -> StartingHand {
+    StartingHand {
+        high: quantity,
+        low: quantity,
+        suited: false,
+    }
+}
+
+/// Hands from one to the other, both included: pairs, hands with the same high card
+/// or hands with the same gap, such as "76s-54s".
+fn between(first: StartingHand, last: StartingHand) -> Vec<StartingHand> {
+    let (from, to) = if first.low <= last.low {
+        (first, last)
+    } else {
+        (last, first)
+    };
+    let gap = from.high as u8 - from.low as u8;
+    Quantity::all()
+        .iter()
+        .filter(|quantity| **quantity >= from.low && **quantity <= to.low)
+        .map(|quantity| {
+            let high = if from.high == to.high {
+                from.high
+            } else {
+                Quantity::all()[(*quantity as u8 + gap - 2) as usize]
+            };
+            StartingHand {
+                high,
+                low: *quantity,
+                suited: from.suited,
+            }
+        })
+        .collect()
+}
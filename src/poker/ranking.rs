@@ -0,0 +1,176 @@
+//! Ranks every holding on a board, from the nuts down.
+
+use std::cmp::{Ordering, Reverse};
+use std::fmt::Display;
+
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::deck::Deck;
+use crate::poker::evaluator::{category, evaluate_mask, mask};
+use crate::poker::rules::Category;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Holding {
+    pub hole: HoleCards,
+    pub strength: u32,
+    /// Position among the different strengths on the board, the nuts are 1.
+    pub rank: usize,
+}
+
+impl Holding {
+    pub fn category(&self) -> Category {
+        category(self.strength)
+    }
+}
+
+/// How hole cards compare with the holdings an opponent can have.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RelativeStrength {
+    pub strength: u32,
+    /// Absolute rank on the board, the nuts are 1.
+    pub rank: usize,
+    /// Opponent combos that beat the hole cards.
+    pub beaten_by: usize,
+    pub ties: usize,
+    pub beats: usize,
+    /// Share of opponent combos beaten, counting ties as half.
+    pub percentile: f64,
+}
+
+impl RelativeStrength {
+    pub fn is_nuts(&self) -> bool {
+        self.rank == 1
+    }
+}
+
+impl Display for RelativeStrength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.rank {
+            1 => write!(f, "Nuts")?,
+            rank => write!(f, "{}{} nuts", rank, suffix(rank))?,
+        }
+        write!(
+            f,
+            ", beats {}, ties {}, loses to {} ({:.1}%)",
+            self.beats,
+            self.ties,
+            self.beaten_by,
+            self.percentile * 100.0
+        )
+    }
+}
+
+fn suffix(number: usize) -> &'static str {
+    match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// All holdings on a board sorted from the strongest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ranking {
+    pub board: Vec<Card>,
+    holdings: Vec<Holding>,
+}
+
+impl Ranking {
+    /// Evaluates every pair of cards left in the deck, 1,081 of them on the river.
+    /// Panics if the board does not have three to five cards or a card is dealt twice.
+    pub fn new(board: &[Card]) -> Ranking {
+        assert!(
+            (3..=5).contains(&board.len()),
+            "A board has three to five cards."
+        );
+        let mut deck = Deck::create();
+        for card in board {
+            assert!(deck.remove(card), "Card {} is dealt twice.", card);
+        }
+
+        let board_mask = mask(board);
+        let mut holdings = Vec::with_capacity(deck.cards.len() * (deck.cards.len() - 1) / 2);
+        for (i, first) in deck.cards.iter().enumerate() {
+            for second in deck.cards[i + 1..].iter() {
+                let hole = [*first, *second];
+                holdings.push(Holding {
+                    hole,
+                    strength: evaluate_mask(board_mask | mask(&hole)),
+                    rank: 0,
+                });
+            }
+        }
+        holdings.sort_by_key(|holding| Reverse(holding.strength));
+
+        let mut rank = 0;
+        let mut previous = None;
+        for holding in holdings.iter_mut() {
+            if previous != Some(holding.strength) {
+                rank += 1;
+                previous = Some(holding.strength);
+            }
+            holding.rank = rank;
+        }
+
+        Ranking {
+            board: board.to_vec(),
+            holdings,
+        }
+    }
+
+    pub fn holdings(&self) -> &[Holding] {
+        &self.holdings
+    }
+
+    /// Holdings of the given absolute rank, the nuts are 1.
+    pub fn with_rank(&self, rank: usize) -> Vec<Holding> {
+        self.holdings
+            .iter()
+            .filter(|holding| holding.rank == rank)
+            .copied()
+            .collect()
+    }
+
+    pub fn nuts(&self) -> Vec<Holding> {
+        self.with_rank(1)
+    }
+
+    /// Compares the hole cards with every holding that does not share a card with them.
+    /// Panics if the hole cards are on the board.
+    pub fn strength(&self, hole: &HoleCards) -> RelativeStrength {
+        let hole_mask = mask(hole);
+        assert_eq!(
+            hole_mask & mask(&self.board),
+            0,
+            "Hole cards are on the board."
+        );
+        let strength = evaluate_mask(mask(&self.board) | hole_mask);
+
+        let mut rank = 1;
+        let (mut beaten_by, mut ties, mut beats) = (0, 0, 0);
+        for holding in self.holdings.iter() {
+            if holding.strength > strength {
+                rank = holding.rank + 1;
+            }
+            if mask(&holding.hole) & hole_mask != 0 {
+                continue;
+            }
+            match holding.strength.cmp(&strength) {
+                Ordering::Greater => beaten_by += 1,
+                Ordering::Equal => ties += 1,
+                Ordering::Less => beats += 1,
+            }
+        }
+
+        let total = (beaten_by + ties + beats).max(1) as f64;
+        RelativeStrength {
+            strength,
+            rank,
+            beaten_by,
+            ties,
+            beats,
+            percentile: (beats as f64 + ties as f64 / 2.0) / total,
+        }
+    }
+}
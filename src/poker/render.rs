@@ -0,0 +1,110 @@
+use std::fmt::{Display, Formatter, Result};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::poker::card::{Card, Quantity, Suit};
+
+/// How cards are written by `Display`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Letters only, e.g. "As" or "Td".
+    Ascii,
+    /// Suit symbols, e.g. "A♠" or "10♦".
+    Unicode,
+    /// Suit symbols colored for a terminal with a four color deck.
+    Ansi,
+    /// Single playing card characters from U+1F0A1.
+    PlayingCards,
+}
+
+static STYLE: AtomicU8 = AtomicU8::new(Style::Unicode as u8);
+
+/// Sets the style used by `Display` of cards, suits and combinations.
+pub fn set_style(style: Style) {
+    STYLE.store(style as u8, Ordering::Relaxed);
+}
+
+pub fn style() -> Style {
+    match STYLE.load(Ordering::Relaxed) {
+        0 => Style::Ascii,
+        2 => Style::Ansi,
+        3 => Style::PlayingCards,
+        _ => Style::Unicode,
+    }
+}
+
+/// Formatting with an explicit style instead of the global one.
+pub trait Render {
+    fn render(&self, f: &mut Formatter<'_>, style: Style) -> Result;
+
+    fn styled(&self, style: Style) -> Styled<'_, Self> {
+        Styled { value: self, style }
+    }
+}
+
+/// Displays a value in the given style, e.g. `card.styled(Style::Ascii).to_string()`.
+pub struct Styled<'a, T: ?Sized> {
+    value: &'a T,
+    style: Style,
+}
+
+impl<T: Render + ?Sized> Display for Styled<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.value.render(f, self.style)
+    }
+}
+
+impl Render for Suit {
+    fn render(&self, f: &mut Formatter<'_>, style: Style) -> Result {
+        match style {
+            Style::Ascii => write!(f, "{}", self.letter()),
+            Style::Unicode | Style::PlayingCards => write!(f, "{}", self.symbol()),
+            Style::Ansi => write!(f, "{}{}{}", color(*self), self.symbol(), RESET),
+        }
+    }
+}
+
+impl Render for Card {
+    fn render(&self, f: &mut Formatter<'_>, style: Style) -> Result {
+        match style {
+            Style::Ascii => write!(f, "{}{}", self.quantity.symbol(), self.suit.letter()),
+            Style::Unicode => write!(f, "{}{}", self.quantity, self.suit.symbol()),
+            Style::Ansi => write!(
+                f,
+                "{}{}{}{}",
+                color(self.suit),
+                self.quantity,
+                self.suit.symbol(),
+                RESET
+            ),
+            Style::PlayingCards => write!(f, "{}", playing_card(self)),
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+fn color(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Spades => "\x1b[39m",
+        Suit::Clubs => "\x1b[32m",
+        Suit::Diamonds => "\x1b[34m",
+        Suit::Hearts => "\x1b[31m",
+    }
+}
+
+fn playing_card(card: &Card) -> char {
+    let suit = match card.suit {
+        Suit::Spades => 0x1F0A0,
+        Suit::Hearts => 0x1F0B0,
+        Suit::Diamonds => 0x1F0C0,
+        Suit::Clubs => 0x1F0D0,
+    };
+    // The block has a knight between the jack and the queen.
+    let quantity = match card.quantity {
+        Quantity::Ace => 1,
+        Quantity::Queen => 13,
+        Quantity::King => 14,
+        quantity => quantity as u32,
+    };
+    std::char::from_u32(suit + quantity).unwrap()
+}
@@ -0,0 +1,364 @@
+//! Step by step replay of a recorded hand.
+//!
+//! The `Replayer` rebuilds the table after every event of a `HandHistory`: stacks, bets,
+//! the pot, the board, the cards shown and the player to act. It can move forward and back
+//! one event at a time or jump to any step. The hand is seen by its hero: the other hole
+//! cards stay hidden until they are shown or the players left are all in, and a history
+//! without a hero shows every card it knows. The equity of every player still in the hand
+//! is computed when a street starts, between the players whose hole cards the history
+//! records, hidden or not: from every remaining board after the flop and from random
+//! boards before it.
+
+use std::fmt::Display;
+
+use crate::poker::board::Street;
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::equity::{exact, monte_carlo};
+use crate::poker::game::Action;
+use crate::poker::history::{Event, HandHistory};
+
+/// Random boards for the equities before the flop.
+const PREFLOP_TRIALS: usize = 20000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeatState {
+    pub seat: usize,
+    pub name: String,
+    /// Chips behind, not yet put into the pot.
+    pub stack: u64,
+    /// Chips put in on the current street.
+    pub bet: u64,
+    /// Chips put in during the whole hand, antes included.
+    pub invested: u64,
+    pub folded: bool,
+    pub all_in: bool,
+    /// Hole cards visible so far, `None` when they are not known or still hidden.
+    pub hole: Option<HoleCards>,
+    /// Whether the cards were shown at showdown.
+    pub shown: bool,
+    /// Chance to win the hand against the others left from the start of the street,
+    /// ties shared. Side pots are not taken into account. `None` when the player folded
+    /// or the history records fewer than two hands.
+    pub equity: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableState {
+    pub street: Street,
+    pub board: Vec<Card>,
+    /// Chips in the middle, the bets of the current street included.
+    pub pot: u64,
+    pub players: Vec<SeatState>,
+    /// Index of the player whose action comes next.
+    pub to_act: Option<usize>,
+}
+
+pub struct Replayer {
+    pub history: HandHistory,
+    /// The table before the first event and after every event.
+    states: Vec<TableState>,
+    position: usize,
+}
+
+impl Replayer {
+    pub fn new(history: HandHistory) -> Replayer {
+        let mut state = TableState {
+            street: Street::Preflop,
+            board: Vec::new(),
+            pot: 0,
+            players: history
+                .players
+                .iter()
+                .enumerate()
+                .map(|(index, player)| SeatState {
+                    seat: player.seat,
+                    name: player.name.clone(),
+                    stack: player.stack,
+                    bet: 0,
+                    invested: 0,
+                    folded: player.stack == 0 || player.sitting_out,
+                    all_in: false,
+                    hole: player
+                        .hole
+                        .filter(|_| history.hero.is_none_or(|hero| hero == index)),
+                    shown: false,
+                    equity: None,
+                })
+                .collect(),
+            to_act: None,
+        };
+        let holes: Vec<Option<HoleCards>> =
+            history.players.iter().map(|player| player.hole).collect();
+        state.update_equities(&holes);
+
+        let mut states = Vec::with_capacity(history.events.len() + 1);
+        for event in history.events.iter() {
+            states.push(state.clone());
+            state.apply(event, &holes);
+        }
+        states.push(state);
+        // The player to act is the one of the next event.
+        for (state, event) in states.iter_mut().zip(history.events.iter()) {
+            if let Event::Act { player, .. } = event {
+                state.to_act = Some(*player);
+            }
+        }
+
+        Replayer {
+            history,
+            states,
+            position: 0,
+        }
+    }
+
+    /// Number of steps, one more than the events.
+    pub fn steps(&self) -> usize {
+        self.states.len()
+    }
+
+    /// The current step, 0 before the first event.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn state(&self) -> &TableState {
+        &self.states[self.position]
+    }
+
+    /// The event that led to the current step, `None` at the start.
+    pub fn event(&self) -> Option<&Event> {
+        self.position
+            .checked_sub(1)
+            .map(|index| &self.history.events[index])
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position + 1 == self.states.len()
+    }
+
+    /// Moves to the next step, returns false at the end.
+    pub fn forward(&mut self) -> bool {
+        if self.is_at_end() {
+            return false;
+        }
+        self.position += 1;
+        true
+    }
+
+    /// Moves to the previous step, returns false at the start.
+    pub fn back(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        true
+    }
+
+    /// Jumps to a step, the last one if it is past the end.
+    pub fn seek(&mut self, step: usize) {
+        self.position = step.min(self.states.len() - 1);
+    }
+
+    /// Words for an event, e.g. "Ann raises to 100 and is all-in".
+    pub fn describe(&self, event: &Event) -> String {
+        let name = |player: &usize| &self.history.players[*player].name;
+        match event {
+            Event::Ante { player, amount } => format!("{} posts the ante {}", name(player), amount),
+            Event::SmallBlind { player, amount } => {
+                format!("{} posts small blind {}", name(player), amount)
+            }
+            Event::BigBlind { player, amount } => {
+                format!("{} posts big blind {}", name(player), amount)
+            }
+            Event::Act {
+                player,
+                action,
+                all_in,
+                ..
+            } => {
+                let all_in = if *all_in { " and is all-in" } else { "" };
+                format!("{} {}{}", name(player), action, all_in)
+            }
+            Event::Uncalled { player, amount } => {
+                format!("Uncalled bet of {} returned to {}", amount, name(player))
+            }
+            Event::Deal { street, cards } => format!("{} {}", street, list(cards)),
+            Event::Show { player, hole } => format!("{} shows {}", name(player), list(hole)),
+            Event::Collect { player, amount, .. } => {
+                format!("{} collects {}", name(player), amount)
+            }
+        }
+    }
+}
+
+/// The table at the current step, as shown by the terminal replayer.
+impl Display for Replayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state();
+        writeln!(
+            f,
+            "Hand #{} {} - {} - step {}/{}",
+            self.history.id,
+            self.history.blinds,
+            state.street,
+            self.position,
+            self.steps() - 1
+        )?;
+        writeln!(f, "Board: {:<20} Pot: {}", list(&state.board), state.pot)?;
+        for (index, player) in state.players.iter().enumerate() {
+            if self.history.players[index].stack == 0 {
+                continue;
+            }
+            let button = if index == self.history.button {
+                "D"
+            } else {
+                " "
+            };
+            write!(
+                f,
+                "{} Seat {:<2} {:<16} {:>8}",
+                button,
+                player.seat + 1,
+                player.name,
+                player.stack
+            )?;
+            let hole = player
+                .hole
+                .map_or(String::from("?? ??"), |hole| list(&hole));
+            write!(f, "  {:<7}", hole)?;
+            if self.history.players[index].sitting_out {
+                write!(f, "  sitting out")?;
+            } else if player.folded {
+                write!(f, "  folded")?;
+            } else {
+                if player.bet > 0 {
+                    write!(f, "  bet {}", player.bet)?;
+                }
+                if player.all_in {
+                    write!(f, "  all-in")?;
+                }
+                if let Some(equity) = player.equity {
+                    write!(f, "  {:.1}%", equity * 100.0)?;
+                }
+            }
+            if state.to_act == Some(index) {
+                write!(f, "  <- to act")?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(event) = self.event() {
+            writeln!(f, "{}", self.describe(event))?;
+        }
+        Ok(())
+    }
+}
+
+impl TableState {
+    /// Plays the event, `holes` being every hole card the history records.
+    fn apply(&mut self, event: &Event, holes: &[Option<HoleCards>]) {
+        match event {
+            Event::Ante { player, amount } => {
+                let seat = &mut self.players[*player];
+                seat.stack = seat.stack.saturating_sub(*amount);
+                seat.invested += amount;
+                seat.all_in = seat.stack == 0;
+                self.pot += amount;
+            }
+            Event::SmallBlind { player, amount }
+            | Event::BigBlind { player, amount }
+            | Event::Act { player, amount, .. } => {
+                let seat = &mut self.players[*player];
+                seat.stack = seat.stack.saturating_sub(*amount);
+                seat.bet += amount;
+                seat.invested += amount;
+                seat.all_in = seat.stack == 0;
+                self.pot += amount;
+                if let Event::Act {
+                    action: Action::Fold,
+                    ..
+                } = event
+                {
+                    seat.folded = true;
+                    seat.equity = None;
+                }
+            }
+            Event::Uncalled { player, amount } => {
+                let seat = &mut self.players[*player];
+                seat.stack += amount;
+                seat.bet = seat.bet.saturating_sub(*amount);
+                seat.invested = seat.invested.saturating_sub(*amount);
+                seat.all_in = false;
+                self.pot = self.pot.saturating_sub(*amount);
+            }
+            Event::Deal { street, cards } => {
+                self.street = *street;
+                self.board.extend_from_slice(cards);
+                for seat in self.players.iter_mut() {
+                    seat.bet = 0;
+                }
+                self.update_equities(holes);
+            }
+            Event::Show { player, hole } => {
+                let seat = &mut self.players[*player];
+                seat.hole = Some(*hole);
+                seat.shown = true;
+            }
+            Event::Collect { player, amount, .. } => {
+                self.players[*player].stack += amount;
+                self.pot = self.pot.saturating_sub(*amount);
+            }
+        }
+        // Once nobody can bet any more, the cards of the players left are turned up.
+        let left: Vec<&SeatState> = self.players.iter().filter(|seat| !seat.folded).collect();
+        let highest = left.iter().map(|seat| seat.bet).max().unwrap_or(0);
+        let betting: Vec<&&SeatState> = left.iter().filter(|seat| !seat.all_in).collect();
+        let called = betting.iter().all(|seat| seat.bet == highest);
+        if left.len() > 1 && betting.len() <= 1 && called {
+            for (seat, hole) in self.players.iter_mut().zip(holes) {
+                if !seat.folded && seat.hole.is_none() {
+                    seat.hole = *hole;
+                }
+            }
+        }
+    }
+
+    /// Equities of the players in the hand whose hole cards are recorded.
+    fn update_equities(&mut self, holes: &[Option<HoleCards>]) {
+        let known: Vec<usize> = (0..self.players.len())
+            .filter(|index| !self.players[*index].folded && holes[*index].is_some())
+            .collect();
+        let hands: Vec<HoleCards> = known.iter().map(|index| holes[*index].unwrap()).collect();
+        for seat in self.players.iter_mut() {
+            seat.equity = None;
+        }
+        let mut cards: Vec<Card> = hands
+            .iter()
+            .flatten()
+            .chain(self.board.iter())
+            .copied()
+            .collect();
+        let count = cards.len();
+        cards.sort_by_key(Card::index);
+        cards.dedup();
+        // Histories with the same card twice have no equities.
+        if known.len() < 2 || cards.len() != count {
+            return;
+        }
+        let equities = if self.board.is_empty() {
+            monte_carlo(&hands, &self.board, PREFLOP_TRIALS, 1, 0)
+        } else {
+            exact(&hands, &self.board, 1)
+        };
+        for (index, equity) in known.iter().zip(equities) {
+            self.players[*index].equity = Some(equity.equity);
+        }
+    }
+}
+
+fn list(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(Card::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,579 @@
+//! Heads-up river subgames solved with CFR+ over whole ranges at once.
+//!
+//! The first player is out of position and acts first. Bets and raises are given as
+//! shares of the pot and capped by the effective stack. Values are chips won on the river,
+//! so a player who wins the pot at showdown gets the starting pot and the chips of the other.
+//! Showdowns compare every combo with the opposing range in one pass over the combos
+//! sorted by their rank on the board, removing the combos that share a card.
+
+use std::collections::HashMap;
+
+use crate::poker::card::{Card, HoleCards};
+use crate::poker::game::Action;
+use crate::poker::index::hole_index;
+use crate::poker::range::Range;
+use crate::poker::ranking::Ranking;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct River {
+    pub board: Vec<Card>,
+    /// Ranges of the player out of position and of the one in position.
+    pub ranges: [Range; 2],
+    pub pot: u64,
+    /// Effective stack at the start of the river.
+    pub stack: u64,
+    /// Bets as shares of the pot.
+    pub bet_sizes: Vec<f64>,
+    /// Raises as shares of the pot after calling.
+    pub raise_sizes: Vec<f64>,
+    /// Whether moving all in is always one of the bets and raises.
+    pub all_in: bool,
+    /// Raises allowed after the first bet.
+    pub max_raises: usize,
+}
+
+/// A point of the betting, terminal when it has no actions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    /// The player to act.
+    pub player: usize,
+    pub actions: Vec<Action>,
+    pub children: Vec<usize>,
+    /// The node before and the action that led here.
+    pub parent: Option<(usize, usize)>,
+    /// Chips put in on the river by each player.
+    pub invested: [u64; 2],
+    pub folded: Option<usize>,
+}
+
+impl Node {
+    pub fn is_terminal(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Combo {
+    hole: HoleCards,
+    weight: f64,
+    /// Rank of the hand on the board, 1 for the nuts.
+    rank: usize,
+    cards: [usize; 2],
+}
+
+/// The average strategy and value of one combo at a node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComboStrategy {
+    pub hole: HoleCards,
+    /// Weight of the combo in the range of the player at the node.
+    pub reach: f64,
+    pub frequencies: Vec<(Action, f64)>,
+    /// Chips the combo expects to win from the node on, `None` when no opposing combo is left.
+    pub ev: Option<f64>,
+}
+
+pub struct RiverSolver {
+    pub river: River,
+    pub tree: Vec<Node>,
+    combos: [Vec<Combo>; 2],
+    /// Index of the same combo in the other range, for every combo of each player.
+    same: [Vec<Option<usize>>; 2],
+    regrets: Vec<Vec<f64>>,
+    strategy_sum: Vec<Vec<f64>>,
+    iterations: usize,
+}
+
+impl RiverSolver {
+    /// Builds the tree and ranks the combos of both ranges that do not touch the board.
+    /// Panics if the board does not have five cards or a card is dealt twice.
+    pub fn new(river: River) -> RiverSolver {
+        assert_eq!(river.board.len(), 5, "A river board has five cards.");
+        let ranking = Ranking::new(&river.board);
+        let ranks: HashMap<u16, usize> = ranking
+            .holdings()
+            .iter()
+            .map(|holding| (hole_index(&holding.hole), holding.rank))
+            .collect();
+        let combos = [0, 1].map(|player| {
+            river.ranges[player]
+                .combos()
+                .into_iter()
+                .filter_map(|(hole, weight)| {
+                    let rank = *ranks.get(&hole_index(&hole))?;
+                    Some(Combo {
+                        hole,
+                        weight,
+                        rank,
+                        cards: [hole[0].index() as usize, hole[1].index() as usize],
+                    })
+                })
+                .collect::<Vec<Combo>>()
+        });
+        let same = [0, 1].map(|player| {
+            let other: HashMap<u16, usize> = combos[1 - player]
+                .iter()
+                .enumerate()
+                .map(|(index, combo)| (hole_index(&combo.hole), index))
+                .collect();
+            combos[player]
+                .iter()
+                .map(|combo| other.get(&hole_index(&combo.hole)).copied())
+                .collect()
+        });
+
+        let mut solver = RiverSolver {
+            river,
+            tree: Vec::new(),
+            combos,
+            same,
+            regrets: Vec::new(),
+            strategy_sum: Vec::new(),
+            iterations: 0,
+        };
+        solver.build(None, 0, [0, 0], 0);
+        for node in solver.tree.iter() {
+            let size = solver.combos[node.player].len() * node.actions.len();
+            solver.regrets.push(vec![0.0; size]);
+            solver.strategy_sum.push(vec![0.0; size]);
+        }
+        solver
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Combos of the player that do not touch the board.
+    pub fn combos(&self, player: usize) -> Vec<HoleCards> {
+        self.combos[player].iter().map(|combo| combo.hole).collect()
+    }
+
+    fn build(
+        &mut self,
+        parent: Option<(usize, usize)>,
+        player: usize,
+        invested: [u64; 2],
+        raises: usize,
+    ) -> usize {
+        let index = self.tree.len();
+        self.tree.push(Node {
+            player,
+            actions: Vec::new(),
+            children: Vec::new(),
+            parent,
+            invested,
+            folded: None,
+        });
+        let previous = parent.map(|(node, action)| self.tree[node].actions[action]);
+        let other = 1 - player;
+        let stack = self.river.stack;
+        let pot = self.river.pot + invested[0] + invested[1];
+
+        let mut actions = Vec::new();
+        match previous {
+            Some(Action::Fold) => {
+                self.tree[index].folded = Some(other);
+                return index;
+            }
+            Some(Action::Call) => return index,
+            Some(Action::Check) if player == 0 => return index,
+            _ => {}
+        }
+
+        if invested[other] == invested[player] {
+            actions.push(Action::Check);
+            let sizes = self.sizes(&self.river.bet_sizes, pot, 0);
+            actions.extend(sizes.into_iter().map(Action::Bet));
+        } else {
+            actions.push(Action::Fold);
+            actions.push(Action::Call);
+            if raises < self.river.max_raises && invested[other] < stack {
+                let called = pot + invested[other] - invested[player];
+                let sizes = self.sizes(&self.river.raise_sizes, called, invested[other]);
+                actions.extend(sizes.into_iter().map(Action::Raise));
+            }
+        }
+
+        self.tree[index].actions = actions.clone();
+        for (position, action) in actions.iter().enumerate() {
+            let mut next = invested;
+            let mut next_raises = raises;
+            match action {
+                Action::Call => next[player] = invested[other],
+                Action::Bet(to) => next[player] = *to,
+                Action::Raise(to) => {
+                    next[player] = *to;
+                    next_raises += 1;
+                }
+                Action::Fold | Action::Check => {}
+            }
+            let child = self.build(Some((index, position)), other, next, next_raises);
+            self.tree[index].children.push(child);
+        }
+        index
+    }
+
+    /// Totals of the bets or raises of the given shares of the pot over the current bet,
+    /// capped by the stack, with the all in added when it is always allowed.
+    fn sizes(&self, shares: &[f64], pot: u64, current: u64) -> Vec<u64> {
+        let stack = self.river.stack;
+        let mut sizes: Vec<u64> = shares
+            .iter()
+            .map(|share| (current + ((pot as f64 * share).round() as u64).max(1)).min(stack))
+            .collect();
+        if self.river.all_in {
+            sizes.push(stack);
+        }
+        sizes.retain(|size| *size > current);
+        sizes.sort_unstable();
+        sizes.dedup();
+        sizes
+    }
+
+    /// Runs CFR+ iterations, updating the two players in turn.
+    pub fn iterate(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            self.iterations += 1;
+            let weight = self.iterations as f64;
+            for player in 0..2 {
+                let own = self.weights(player);
+                let opponent = self.weights(1 - player);
+                self.cfr(0, player, &own, &opponent, weight);
+            }
+        }
+    }
+
+    /// Iterates until the exploitability is at most the given share of the pot,
+    /// or the iterations run out. Returns the exploitability reached.
+    pub fn solve(&mut self, target: f64, max_iterations: usize) -> f64 {
+        let mut exploitability = self.exploitability();
+        while exploitability > target * self.river.pot as f64 && self.iterations < max_iterations {
+            self.iterate(10.min(max_iterations - self.iterations));
+            exploitability = self.exploitability();
+        }
+        exploitability
+    }
+
+    /// Chips per hand a best response wins over the equilibrium, averaged over the two players.
+    pub fn exploitability(&self) -> f64 {
+        let best: f64 = (0..2).map(|player| self.best_response(player)).sum();
+        (best - self.river.pot as f64) / 2.0
+    }
+
+    /// Expected chips of each player with the average strategies.
+    pub fn values(&self) -> [f64; 2] {
+        [0, 1].map(|player| {
+            let values = self.expected(0, player, &self.weights(1 - player));
+            self.total(player, &values)
+        })
+    }
+
+    /// Expected chips of the player's best response to the average strategy of the other.
+    pub fn best_response(&self, player: usize) -> f64 {
+        let values = self.best(0, player, &self.weights(1 - player));
+        self.total(player, &values)
+    }
+
+    /// Average strategy and value of every combo of the player to act at the node.
+    pub fn strategy(&self, node: usize) -> Vec<ComboStrategy> {
+        let player = self.tree[node].player;
+        let reach = [0, 1].map(|player| self.reach(node, player));
+        let values = self.expected(node, player, &reach[1 - player]);
+        let actions = &self.tree[node].actions;
+        let average = self.average(node);
+        let valid = self.valid(player, &reach[1 - player], 1.0);
+        self.combos[player]
+            .iter()
+            .enumerate()
+            .map(|(index, combo)| {
+                let opponents = valid[index];
+                ComboStrategy {
+                    hole: combo.hole,
+                    reach: reach[player][index],
+                    frequencies: actions
+                        .iter()
+                        .enumerate()
+                        .map(|(action, kind)| (*kind, average[index * actions.len() + action]))
+                        .collect(),
+                    ev: (opponents > 0.0).then(|| values[index] / opponents),
+                }
+            })
+            .collect()
+    }
+
+    fn weights(&self, player: usize) -> Vec<f64> {
+        self.combos[player]
+            .iter()
+            .map(|combo| combo.weight)
+            .collect()
+    }
+
+    /// Values per pair of combos that do not share a card.
+    fn total(&self, player: usize, values: &[f64]) -> f64 {
+        let valid = self.valid(player, &self.weights(1 - player), 1.0);
+        let mut pairs = 0.0;
+        let mut total = 0.0;
+        for (index, combo) in self.combos[player].iter().enumerate() {
+            pairs += combo.weight * valid[index];
+            total += combo.weight * values[index];
+        }
+        if pairs > 0.0 {
+            total / pairs
+        } else {
+            0.0
+        }
+    }
+
+    /// Reach of every combo of the player at the node with the average strategy.
+    fn reach(&self, node: usize, player: usize) -> Vec<f64> {
+        let mut reach = self.weights(player);
+        let mut current = node;
+        while let Some((parent, action)) = self.tree[current].parent {
+            if self.tree[parent].player == player {
+                let average = self.average(parent);
+                let actions = self.tree[parent].actions.len();
+                for (index, weight) in reach.iter_mut().enumerate() {
+                    *weight *= average[index * actions + action];
+                }
+            }
+            current = parent;
+        }
+        reach
+    }
+
+    /// Regret matching on the positive regrets of every combo.
+    fn current(&self, node: usize) -> Vec<f64> {
+        normalize(&self.regrets[node], self.tree[node].actions.len())
+    }
+
+    fn average(&self, node: usize) -> Vec<f64> {
+        normalize(&self.strategy_sum[node], self.tree[node].actions.len())
+    }
+
+    fn cfr(
+        &mut self,
+        node: usize,
+        player: usize,
+        own: &[f64],
+        opponent: &[f64],
+        weight: f64,
+    ) -> Vec<f64> {
+        if self.tree[node].is_terminal() {
+            return self.terminal(node, player, opponent);
+        }
+        let acting = self.tree[node].player;
+        let actions = self.tree[node].actions.len();
+        let children = self.tree[node].children.clone();
+        let strategy = self.current(node);
+
+        if acting != player {
+            let mut values = vec![0.0; own.len()];
+            for (action, child) in children.iter().enumerate() {
+                let reach: Vec<f64> = opponent
+                    .iter()
+                    .enumerate()
+                    .map(|(index, reach)| reach * strategy[index * actions + action])
+                    .collect();
+                for (value, child_value) in values
+                    .iter_mut()
+                    .zip(self.cfr(*child, player, own, &reach, weight))
+                {
+                    *value += child_value;
+                }
+            }
+            return values;
+        }
+
+        let mut action_values = Vec::with_capacity(actions);
+        for (action, child) in children.iter().enumerate() {
+            let reach: Vec<f64> = own
+                .iter()
+                .enumerate()
+                .map(|(index, reach)| reach * strategy[index * actions + action])
+                .collect();
+            action_values.push(self.cfr(*child, player, &reach, opponent, weight));
+        }
+        let mut values = vec![0.0; own.len()];
+        for (index, value) in values.iter_mut().enumerate() {
+            for (action, action_value) in action_values.iter().enumerate() {
+                *value += strategy[index * actions + action] * action_value[index];
+            }
+        }
+        let regrets = &mut self.regrets[node];
+        let sums = &mut self.strategy_sum[node];
+        for (index, value) in values.iter().enumerate() {
+            for (action, action_value) in action_values.iter().enumerate() {
+                let slot = index * actions + action;
+                regrets[slot] = (regrets[slot] + action_value[index] - value).max(0.0);
+                sums[slot] += weight * own[index] * strategy[slot];
+            }
+        }
+        values
+    }
+
+    /// Counterfactual values with the average strategies of both players.
+    fn expected(&self, node: usize, player: usize, opponent: &[f64]) -> Vec<f64> {
+        self.walk(node, player, opponent, false)
+    }
+
+    /// Counterfactual values of the best response of the player.
+    fn best(&self, node: usize, player: usize, opponent: &[f64]) -> Vec<f64> {
+        self.walk(node, player, opponent, true)
+    }
+
+    fn walk(&self, node: usize, player: usize, opponent: &[f64], best: bool) -> Vec<f64> {
+        if self.tree[node].is_terminal() {
+            return self.terminal(node, player, opponent);
+        }
+        let acting = self.tree[node].player;
+        let actions = self.tree[node].actions.len();
+        let average = self.average(node);
+        let mut values = vec![0.0; self.combos[player].len()];
+
+        for (action, child) in self.tree[node].children.iter().enumerate() {
+            if acting == player {
+                let child_values = self.walk(*child, player, opponent, best);
+                for (index, value) in values.iter_mut().enumerate() {
+                    if best {
+                        *value = if action == 0 {
+                            child_values[index]
+                        } else {
+                            value.max(child_values[index])
+                        };
+                    } else {
+                        *value += average[index * actions + action] * child_values[index];
+                    }
+                }
+            } else {
+                let reach: Vec<f64> = opponent
+                    .iter()
+                    .enumerate()
+                    .map(|(index, reach)| reach * average[index * actions + action])
+                    .collect();
+                for (value, child_value) in values
+                    .iter_mut()
+                    .zip(self.walk(*child, player, &reach, best))
+                {
+                    *value += child_value;
+                }
+            }
+        }
+        values
+    }
+
+    /// Values of the player's combos at a terminal node against the reach of the opponent.
+    fn terminal(&self, node: usize, player: usize, opponent: &[f64]) -> Vec<f64> {
+        let terminal = &self.tree[node];
+        let other = 1 - player;
+        let pot = self.river.pot as f64;
+        let own = terminal.invested[player] as f64;
+        let theirs = terminal.invested[other] as f64;
+
+        if let Some(folded) = terminal.folded {
+            let amount = if folded == player { -own } else { pot + theirs };
+            return self.valid(player, opponent, amount);
+        }
+        self.showdown(player, opponent, pot + theirs, -own, pot / 2.0)
+    }
+
+    /// Sum of the opponent's reach over the combos that do not share a card with each combo,
+    /// times the amount.
+    fn valid(&self, player: usize, opponent: &[f64], amount: f64) -> Vec<f64> {
+        let mut by_card = [0.0; 52];
+        let mut total = 0.0;
+        for (combo, reach) in self.combos[1 - player].iter().zip(opponent) {
+            total += reach;
+            by_card[combo.cards[0]] += reach;
+            by_card[combo.cards[1]] += reach;
+        }
+        self.combos[player]
+            .iter()
+            .zip(self.same[player].iter())
+            .map(|(combo, same)| {
+                let same = same.map_or(0.0, |same| opponent[same]);
+                (total - by_card[combo.cards[0]] - by_card[combo.cards[1]] + same) * amount
+            })
+            .collect()
+    }
+
+    /// Wins, losses and ties of every combo, found by sweeping both ranges by rank.
+    fn showdown(&self, player: usize, opponent: &[f64], win: f64, lose: f64, tie: f64) -> Vec<f64> {
+        let own = &self.combos[player];
+        let other = &self.combos[1 - player];
+        let mut values = vec![0.0; own.len()];
+
+        let mut own_order: Vec<usize> = (0..own.len()).collect();
+        own_order.sort_by_key(|index| own[*index].rank);
+        let mut other_order: Vec<usize> = (0..other.len()).collect();
+        other_order.sort_by_key(|index| other[*index].rank);
+
+        // Weaker opponents: from the weakest hands up.
+        let mut total = 0.0;
+        let mut by_card = [0.0; 52];
+        let mut next = other_order.len();
+        for index in own_order.iter().rev() {
+            let rank = own[*index].rank;
+            while next > 0 && other[other_order[next - 1]].rank > rank {
+                next -= 1;
+                let combo = &other[other_order[next]];
+                let reach = opponent[other_order[next]];
+                total += reach;
+                by_card[combo.cards[0]] += reach;
+                by_card[combo.cards[1]] += reach;
+            }
+            let cards = own[*index].cards;
+            values[*index] += (total - by_card[cards[0]] - by_card[cards[1]]) * win;
+        }
+
+        // Stronger opponents: from the nuts down.
+        let mut total = 0.0;
+        let mut by_card = [0.0; 52];
+        let mut next = 0;
+        for index in own_order.iter() {
+            let rank = own[*index].rank;
+            while next < other_order.len() && other[other_order[next]].rank < rank {
+                let combo = &other[other_order[next]];
+                let reach = opponent[other_order[next]];
+                total += reach;
+                by_card[combo.cards[0]] += reach;
+                by_card[combo.cards[1]] += reach;
+                next += 1;
+            }
+            let cards = own[*index].cards;
+            values[*index] += (total - by_card[cards[0]] - by_card[cards[1]]) * lose;
+        }
+
+        // Ties: opponents of the same rank.
+        if tie != 0.0 {
+            let mut ties: HashMap<usize, (f64, [f64; 52])> = HashMap::new();
+            for (combo, reach) in other.iter().zip(opponent) {
+                let entry = ties.entry(combo.rank).or_insert((0.0, [0.0; 52]));
+                entry.0 += reach;
+                entry.1[combo.cards[0]] += reach;
+                entry.1[combo.cards[1]] += reach;
+            }
+            for (index, combo) in own.iter().enumerate() {
+                if let Some((total, by_card)) = ties.get(&combo.rank) {
+                    let same = self.same[player][index].map_or(0.0, |same| opponent[same]);
+                    values[index] +=
+                        (total - by_card[combo.cards[0]] - by_card[combo.cards[1]] + same) * tie;
+                }
+            }
+        }
+        values
+    }
+}
+
+/// Turns non-negative sums per combo and action into probabilities, uniform where all are zero.
+fn normalize(sums: &[f64], actions: usize) -> Vec<f64> {
+    let mut strategy = Vec::with_capacity(sums.len());
+    for row in sums.chunks(actions) {
+        let total: f64 = row.iter().map(|sum| sum.max(0.0)).sum();
+        if total > 0.0 {
+            strategy.extend(row.iter().map(|sum| sum.max(0.0) / total));
+        } else {
+            strategy.extend(std::iter::repeat_n(1.0 / actions as f64, actions));
+        }
+    }
+    strategy
+}
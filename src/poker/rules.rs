@@ -3,34 +3,32 @@ use std::{
     fmt::Display,
 };
 
-use super::card::{Hand, Quantity};
-use crate::Card;
+use super::card::{Card, Hand, Quantity};
+use super::render::{style, Render, Style};
 
-pub type Rule = fn(&Hand) -> Option<Combination>;
+pub type Rule = fn(&Hand) -> Option<Combination<'_>>;
 
-pub fn check_flush_royal(hand: &Hand) -> Option<Combination> {
+pub fn check_flush_royal(hand: &Hand) -> Option<Combination<'_>> {
     let straight = check_straight(hand);
     let flush = check_flush(hand);
 
     match (straight, flush) {
         (Some(_), Some(_)) => {
-            let mut sorted: Hand = hand.clone();
+            let mut sorted: Hand = *hand;
             sorted.sort();
 
-            let mut start = 10;
-            for card in sorted {
+            for (start, card) in (10..).zip(sorted) {
                 if card.quantity as u8 != start {
                     return Some(Combination {
                         name: "Straight Flush",
                         cards: hand.iter().collect(),
                     });
                 }
-                start += 1;
             }
-            return Some(Combination {
+            Some(Combination {
                 name: "Flush Royal",
                 cards: hand.iter().collect(),
-            });
+            })
         }
         (Some(straight), None) => Some(straight),
         (None, Some(flush)) => Some(flush),
@@ -38,8 +36,8 @@ pub fn check_flush_royal(hand: &Hand) -> Option<Combination> {
     }
 }
 
-pub fn check_straight(hand: &Hand) -> Option<Combination> {
-    let mut sorted: Hand = hand.clone();
+pub fn check_straight(hand: &Hand) -> Option<Combination<'_>> {
+    let mut sorted: Hand = *hand;
     sorted.sort();
 
     // This handles an edge case when ace can be used
@@ -60,13 +58,13 @@ pub fn check_straight(hand: &Hand) -> Option<Combination> {
         }
     }
 
-    return Some(Combination {
+    Some(Combination {
         name: "Straight",
         cards: hand.iter().collect(),
-    });
+    })
 }
 
-pub fn check_flush(hand: &Hand) -> Option<Combination> {
+pub fn check_flush(hand: &Hand) -> Option<Combination<'_>> {
     let suit = hand[0].suit;
     for card in hand {
         if card.suit != suit {
@@ -79,13 +77,18 @@ pub fn check_flush(hand: &Hand) -> Option<Combination> {
     })
 }
 
-pub fn check_duplicates(hand: &Hand) -> Option<Combination> {
+pub fn check_duplicates(hand: &Hand) -> Option<Combination<'_>> {
     let duplicates = find_duplicates(hand);
 
     let duplicate_count = duplicates.len();
     if duplicate_count == 2 {
+        let name = if duplicates[0].len() == 3 {
+            "Full house"
+        } else {
+            "Two pairs"
+        };
         return Some(Combination {
-            name: "Two pairs",
+            name,
             cards: duplicates.into_iter().flatten().collect(),
         });
     } else if duplicate_count == 1 {
@@ -109,17 +112,17 @@ pub fn check_duplicates(hand: &Hand) -> Option<Combination> {
     None
 }
 
-pub fn check_high(hand: &Hand) -> Option<Combination> {
+pub fn check_high(hand: &Hand) -> Option<Combination<'_>> {
     let mut high = &hand[0];
-    for i in 1..5 {
-        if hand[i].quantity > high.quantity {
-            high = &hand[i];
+    for card in &hand[1..] {
+        if card.quantity > high.quantity {
+            high = card;
         }
     }
-    return Some(Combination {
+    Some(Combination {
         name: "High card",
         cards: vec![high],
-    });
+    })
 }
 
 fn find_duplicates(hand: &Hand) -> Vec<Vec<&Card>> {
@@ -132,17 +135,24 @@ fn find_duplicates(hand: &Hand) -> Vec<Vec<&Card>> {
                 duplicates.push(card);
             }
             Entry::Vacant(entry) => {
-                let mut duplicates: Vec<&Card> = Vec::new();
-                duplicates.push(card);
+                let duplicates: Vec<&Card> = vec![card];
                 entry.insert(duplicates);
             }
         }
     }
 
-    result
+    // Larger groups go first, then higher quantities,
+    // so that the result does not depend on the hash map order.
+    let mut duplicates: Vec<Vec<&Card>> = result
         .into_values()
         .filter(|duplicate| duplicate.len() > 1)
-        .collect()
+        .collect();
+    duplicates.sort_by(|a, b| {
+        b.len()
+            .cmp(&a.len())
+            .then_with(|| b[0].quantity.cmp(&a[0].quantity))
+    });
+    duplicates
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -153,10 +163,181 @@ pub struct Combination<'a> {
 
 impl Display for Combination<'_> {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.render(fmt, style())
+    }
+}
+
+impl Render for Combination<'_> {
+    fn render(&self, fmt: &mut std::fmt::Formatter<'_>, style: Style) -> std::fmt::Result {
         fmt.write_str(self.name)?;
         fmt.write_str(": ")?;
         for card in self.cards.iter() {
-            card.fmt(fmt)?;
+            card.render(fmt, style)?;
+            fmt.write_str(" ")?;
+        }
+        Ok(())
+    }
+}
+
+impl Combination<'_> {
+    pub fn category(&self) -> Category {
+        Category::from_name(self.name).expect("Unknown combination name.")
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPairs,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FlushRoyal,
+}
+
+impl Category {
+    pub fn all() -> &'static [Category] {
+        &[
+            Category::HighCard,
+            Category::Pair,
+            Category::TwoPairs,
+            Category::ThreeOfAKind,
+            Category::Straight,
+            Category::Flush,
+            Category::FullHouse,
+            Category::FourOfAKind,
+            Category::StraightFlush,
+            Category::FlushRoyal,
+        ]
+    }
+
+    /// Returns the name the rules give to combinations of this category.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::HighCard => "High card",
+            Category::Pair => "Pair",
+            Category::TwoPairs => "Two pairs",
+            Category::ThreeOfAKind => "Three of a kind",
+            Category::Straight => "Straight",
+            Category::Flush => "Flush",
+            Category::FullHouse => "Full house",
+            Category::FourOfAKind => "Four of a kind",
+            Category::StraightFlush => "Straight Flush",
+            Category::FlushRoyal => "Flush Royal",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Category> {
+        Category::all()
+            .iter()
+            .find(|category| category.name() == name)
+            .copied()
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A combination that owns its cards, so it does not borrow the hand
+/// and can be copied, stored in structs or sent to other threads.
+///
+/// A higher `strength` is a better hand: it packs the category
+/// and the quantities of the cards from the most significant one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Evaluation {
+    pub category: Category,
+    pub strength: u32,
+    cards: [Card; 5],
+    len: usize,
+}
+
+impl Evaluation {
+    /// Panics if there are more than five cards.
+    pub fn new(category: Category, cards: &[Card], strength: u32) -> Evaluation {
+        let mut evaluation = Evaluation {
+            category,
+            strength,
+            cards: [Card::new(); 5],
+            len: cards.len(),
+        };
+        evaluation.cards[..cards.len()].copy_from_slice(cards);
+        evaluation
+    }
+
+    /// Evaluates the hand with the rules. Unlike a conversion from a combination,
+    /// the strength also accounts for the kickers.
+    pub fn of(hand: &Hand) -> Evaluation {
+        let mut evaluation = Evaluation::from(evaluate(hand));
+        evaluation.strength = strength(evaluation.category, hand);
+        evaluation
+    }
+
+    /// The cards that make the combination.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards[..self.len]
+    }
+}
+
+impl From<&Combination<'_>> for Evaluation {
+    fn from(combination: &Combination<'_>) -> Self {
+        let category = combination.category();
+        let cards: Vec<Card> = combination.cards.iter().map(|card| **card).collect();
+        Evaluation::new(category, &cards, strength(category, &cards))
+    }
+}
+
+impl From<Combination<'_>> for Evaluation {
+    fn from(combination: Combination<'_>) -> Self {
+        Evaluation::from(&combination)
+    }
+}
+
+pub(crate) fn strength(category: Category, cards: &[Card]) -> u32 {
+    let mut counts = [0; 15];
+    for card in cards {
+        counts[card.quantity as usize] += 1;
+    }
+
+    let mut quantities: Vec<u32> = cards.iter().map(|card| card.quantity as u32).collect();
+    quantities.sort_by(|a, b| {
+        counts[*b as usize]
+            .cmp(&counts[*a as usize])
+            .then_with(|| b.cmp(a))
+    });
+
+    // An ace is the lowest card of a straight from ace to five.
+    let straight = matches!(category, Category::Straight | Category::StraightFlush);
+    if straight && quantities == [14, 5, 4, 3, 2] {
+        quantities = vec![5, 4, 3, 2, 1];
+    }
+
+    let mut strength = category as u32;
+    for i in 0..5 {
+        strength = strength << 4 | quantities.get(i).copied().unwrap_or(0);
+    }
+    strength
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.render(fmt, style())
+    }
+}
+
+impl Render for Evaluation {
+    fn render(&self, fmt: &mut std::fmt::Formatter<'_>, style: Style) -> std::fmt::Result {
+        fmt.write_str(self.category.name())?;
+        fmt.write_str(": ")?;
+        for card in self.cards() {
+            card.render(fmt, style)?;
             fmt.write_str(" ")?;
         }
         Ok(())
@@ -166,3 +347,13 @@ impl Display for Combination<'_> {
 pub fn get_rules() -> Vec<Rule> {
     vec![check_flush_royal, check_duplicates, check_high]
 }
+
+/// Applies the rules in order and returns the first combination found.
+pub fn evaluate(hand: &Hand) -> Combination<'_> {
+    for rule in get_rules() {
+        if let Some(combination) = rule(hand) {
+            return combination;
+        }
+    }
+    unreachable!("High card rule always matches.")
+}
@@ -0,0 +1,148 @@
+//! Serde support behind the `serde` feature.
+//!
+//! Cards are written in the compact ASCII notation, e.g. "Ah" or "Td",
+//! suits as their letter and quantities as their symbol.
+//! Evaluations are written with their combination cards only, and read back
+//! only when the category and strength agree with the cards.
+
+use std::convert::TryFrom;
+use std::fmt::Formatter;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::poker::card::{Card, Hand, Quantity, Suit};
+use crate::poker::render::{Render, Style};
+use crate::poker::rules::{evaluate, strength, Category, Evaluation};
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.styled(Style::Ascii))
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(CardVisitor)
+    }
+}
+
+struct CardVisitor;
+
+impl Visitor<'_> for CardVisitor {
+    type Value = Card;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a card such as \"Ah\"")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Card, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+impl Serialize for Suit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.letter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Suit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let letter = char::deserialize(deserializer)?;
+        Suit::from_letter(letter)
+            .ok_or_else(|| de::Error::custom(format!("invalid suit '{}'", letter)))
+    }
+}
+
+impl Serialize for Quantity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.symbol())
+    }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let symbol = char::deserialize(deserializer)?;
+        Quantity::from_symbol(symbol)
+            .ok_or_else(|| de::Error::custom(format!("invalid quantity '{}'", symbol)))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct EvaluationData {
+    category: Category,
+    cards: Vec<Card>,
+    strength: u32,
+}
+
+impl Serialize for Evaluation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EvaluationData {
+            category: self.category,
+            cards: self.cards().to_vec(),
+            strength: self.strength,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Evaluation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = EvaluationData::deserialize(deserializer)?;
+        if data.cards.len() > 5 {
+            return Err(de::Error::invalid_length(
+                data.cards.len(),
+                &"at most five cards",
+            ));
+        }
+        if category(&data.cards) != Some(data.category) {
+            return Err(de::Error::custom(format!(
+                "the cards do not make {}",
+                data.category
+            )));
+        }
+        // The strength of an evaluated hand also counts the kickers after the combination.
+        let shift = 4 * (5 - data.cards.len());
+        if data.strength >> shift != strength(data.category, &data.cards) >> shift {
+            return Err(de::Error::custom(format!(
+                "strength {} does not match the cards",
+                data.strength
+            )));
+        }
+        Ok(Evaluation::new(data.category, &data.cards, data.strength))
+    }
+}
+
+/// The category the cards of a combination make, `None` when they are not one.
+fn category(cards: &[Card]) -> Option<Category> {
+    let mut distinct = cards.to_vec();
+    distinct.sort_by_key(Card::index);
+    distinct.dedup();
+    if distinct.len() != cards.len() {
+        return None;
+    }
+    if let Ok(hand) = Hand::try_from(cards) {
+        return Some(evaluate(&hand).category());
+    }
+    let mut quantities: Vec<Quantity> = cards.iter().map(|card| card.quantity).collect();
+    quantities.sort();
+    quantities.dedup();
+    let counts: Vec<usize> = quantities
+        .iter()
+        .map(|quantity| {
+            cards
+                .iter()
+                .filter(|card| card.quantity == *quantity)
+                .count()
+        })
+        .collect();
+    match counts[..] {
+        [1] => Some(Category::HighCard),
+        [2] => Some(Category::Pair),
+        [3] => Some(Category::ThreeOfAKind),
+        [4] => Some(Category::FourOfAKind),
+        [2, 2] => Some(Category::TwoPairs),
+        _ => None,
+    }
+}
@@ -0,0 +1,446 @@
+//! Player statistics over hand histories, as shown by a HUD.
+//!
+//! Every counter is a number of hands, except the bets, raises and calls after the flop
+//! that make the aggression factor. Percentages divide a counter by the hands where the player
+//! had the chance: a 3-bet needs one raise before the player acts, a continuation bet needs
+//! the player to have made the last raise before the flop and to be checked to on the flop.
+//! Hands can be filtered by the position of the player and by the big blind.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::thread;
+
+use crate::poker::board::Street;
+use crate::poker::game::Action;
+use crate::poker::history::{Event, HandHistory};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Position {
+    /// Every seat before the middle one.
+    Early,
+    /// The seat before the cutoff.
+    Middle,
+    Cutoff,
+    Button,
+    /// Heads-up the button posts the small blind and counts as the small blind.
+    SmallBlind,
+    BigBlind,
+}
+
+impl Position {
+    pub fn all() -> &'static [Position] {
+        &[
+            Position::Early,
+            Position::Middle,
+            Position::Cutoff,
+            Position::Button,
+            Position::SmallBlind,
+            Position::BigBlind,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Position::Early => "EP",
+            Position::Middle => "MP",
+            Position::Cutoff => "CO",
+            Position::Button => "BTN",
+            Position::SmallBlind => "SB",
+            Position::BigBlind => "BB",
+        }
+    }
+
+    /// Position of every player of the hand, `None` for the ones without chips.
+    pub fn of(hand: &HandHistory) -> Vec<Option<Position>> {
+        let count = hand.players.len();
+        let mut blinds = (None, None);
+        for event in hand.events.iter() {
+            match event {
+                Event::SmallBlind { player, .. } => blinds.0 = Some(*player),
+                Event::BigBlind { player, .. } => blinds.1 = Some(*player),
+                _ => {}
+            }
+        }
+        let mut positions = vec![None; count];
+        let dealt_in = dealt_in(hand);
+        // From the button back to the first player after the blinds.
+        let dealt = (0..count)
+            .map(|offset| (hand.button + count - offset) % count)
+            .filter(|index| dealt_in[*index]);
+        let mut rank = 0;
+        for index in dealt {
+            positions[index] = Some(if blinds.0 == Some(index) {
+                Position::SmallBlind
+            } else if blinds.1 == Some(index) {
+                Position::BigBlind
+            } else {
+                rank += 1;
+                match rank {
+                    1 => Position::Button,
+                    2 => Position::Cutoff,
+                    3 => Position::Middle,
+                    _ => Position::Early,
+                }
+            });
+        }
+        positions
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which hands are counted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Filter {
+    /// Positions of the player, every position when empty.
+    pub positions: Vec<Position>,
+    /// Only hands with this big blind, in the units of the histories.
+    pub big_blind: Option<u64>,
+}
+
+impl Filter {
+    fn accepts(&self, hand: &HandHistory) -> bool {
+        self.big_blind
+            .is_none_or(|big_blind| big_blind == hand.blinds.big_blind)
+    }
+
+    fn accepts_position(&self, position: Position) -> bool {
+        self.positions.is_empty() || self.positions.contains(&position)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub hands: u64,
+    /// Hands where the player put chips in before the flop without being forced to.
+    pub vpip: u64,
+    /// Hands where the player raised before the flop.
+    pub pfr: u64,
+    pub three_bet_chances: u64,
+    pub three_bets: u64,
+    /// Hands where the player raised first and then faced a 3-bet.
+    pub three_bets_faced: u64,
+    pub folds_to_three_bet: u64,
+    pub cbet_chances: u64,
+    pub cbets: u64,
+    /// Bets and raises after the flop.
+    pub aggressive: u64,
+    /// Calls after the flop.
+    pub calls: u64,
+    pub flops: u64,
+    pub showdowns: u64,
+    /// Showdowns where the player won chips.
+    pub showdowns_won: u64,
+    /// Chips won less chips put in.
+    pub net: i64,
+    /// Net winnings in big blinds, which add up over different stakes.
+    pub big_blinds: f64,
+}
+
+impl Stats {
+    pub fn vpip(&self) -> f64 {
+        percent(self.vpip, self.hands)
+    }
+
+    pub fn pfr(&self) -> f64 {
+        percent(self.pfr, self.hands)
+    }
+
+    pub fn three_bet(&self) -> f64 {
+        percent(self.three_bets, self.three_bet_chances)
+    }
+
+    pub fn fold_to_three_bet(&self) -> f64 {
+        percent(self.folds_to_three_bet, self.three_bets_faced)
+    }
+
+    pub fn cbet(&self) -> f64 {
+        percent(self.cbets, self.cbet_chances)
+    }
+
+    /// Bets and raises per call after the flop, the bets and raises alone without calls.
+    pub fn aggression_factor(&self) -> f64 {
+        self.aggressive as f64 / self.calls.max(1) as f64
+    }
+
+    /// Went to showdown, of the flops seen.
+    pub fn wtsd(&self) -> f64 {
+        percent(self.showdowns, self.flops)
+    }
+
+    /// Won money at showdown, of the showdowns.
+    pub fn wsd(&self) -> f64 {
+        percent(self.showdowns_won, self.showdowns)
+    }
+
+    /// Win rate in big blinds per 100 hands.
+    pub fn bb_per_100(&self) -> f64 {
+        self.big_blinds / self.hands.max(1) as f64 * 100.0
+    }
+
+    pub fn add(&mut self, other: &Stats) {
+        self.hands += other.hands;
+        self.vpip += other.vpip;
+        self.pfr += other.pfr;
+        self.three_bet_chances += other.three_bet_chances;
+        self.three_bets += other.three_bets;
+        self.three_bets_faced += other.three_bets_faced;
+        self.folds_to_three_bet += other.folds_to_three_bet;
+        self.cbet_chances += other.cbet_chances;
+        self.cbets += other.cbets;
+        self.aggressive += other.aggressive;
+        self.calls += other.calls;
+        self.flops += other.flops;
+        self.showdowns += other.showdowns;
+        self.showdowns_won += other.showdowns_won;
+        self.net += other.net;
+        self.big_blinds += other.big_blinds;
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>7} {:>5.1} {:>5.1} {:>5.1} {:>5.1} {:>5.1} {:>4.1} {:>5.1} {:>5.1} {:>+8.1}",
+            self.hands,
+            self.vpip(),
+            self.pfr(),
+            self.three_bet(),
+            self.fold_to_three_bet(),
+            self.cbet(),
+            self.aggression_factor(),
+            self.wtsd(),
+            self.wsd(),
+            self.bb_per_100()
+        )
+    }
+}
+
+fn percent(count: u64, chances: u64) -> f64 {
+    if chances == 0 {
+        0.0
+    } else {
+        count as f64 / chances as f64 * 100.0
+    }
+}
+
+/// Statistics of every player by name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tracker {
+    pub filter: Filter,
+    pub players: HashMap<String, Stats>,
+}
+
+impl Tracker {
+    pub fn new(filter: Filter) -> Tracker {
+        Tracker {
+            filter,
+            players: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Stats> {
+        self.players.get(name)
+    }
+
+    /// Adds the statistics of another tracker, e.g. one that went through another part of an archive.
+    pub fn merge(&mut self, other: &Tracker) {
+        for (name, stats) in other.players.iter() {
+            self.players.entry(name.clone()).or_default().add(stats);
+        }
+    }
+
+    pub fn add(&mut self, hand: &HandHistory) {
+        if !self.filter.accepts(hand) {
+            return;
+        }
+        let positions = Position::of(hand);
+        for (index, stats) in hand_stats(hand).iter().enumerate() {
+            let counted =
+                positions[index].is_some_and(|position| self.filter.accepts_position(position));
+            if !counted {
+                continue;
+            }
+            let name = &hand.players[index].name;
+            match self.players.get_mut(name) {
+                Some(total) => total.add(stats),
+                None => {
+                    self.players.insert(name.clone(), stats.clone());
+                }
+            }
+        }
+    }
+
+    /// Players with the most hands first.
+    pub fn standings(&self) -> Vec<(&str, &Stats)> {
+        let mut players: Vec<(&str, &Stats)> = self
+            .players
+            .iter()
+            .map(|(name, stats)| (name.as_str(), stats))
+            .collect();
+        players.sort_by(|a, b| b.1.hands.cmp(&a.1.hands).then_with(|| a.0.cmp(b.0)));
+        players
+    }
+}
+
+impl Display for Tracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>7} {:>5} {:>5} {:>5} {:>5} {:>5} {:>4} {:>5} {:>5} {:>8}",
+            "Player", "Hands", "VPIP", "PFR", "3B", "F3B", "CB", "AF", "WTSD", "W$SD", "bb/100"
+        )?;
+        for (name, stats) in self.standings() {
+            writeln!(f, "{:<20} {}", name, stats)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks the hands on several threads, each one taking a slice of the hands.
+pub fn track(hands: &[HandHistory], filter: &Filter, threads: usize) -> Tracker {
+    let threads = threads.max(1);
+    let size = hands.len().div_ceil(threads).max(1);
+    let trackers: Vec<Tracker> = thread::scope(|scope| {
+        let handles: Vec<_> = hands
+            .chunks(size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut tracker = Tracker::new(filter.clone());
+                    for hand in chunk {
+                        tracker.add(hand);
+                    }
+                    tracker
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Stats thread panicked."))
+            .collect()
+    });
+
+    let mut total = Tracker::new(filter.clone());
+    for tracker in trackers.iter() {
+        total.merge(tracker);
+    }
+    total
+}
+
+/// Statistics of every player in one hand.
+fn hand_stats(hand: &HandHistory) -> Vec<Stats> {
+    let count = hand.players.len();
+    let mut stats = vec![Stats::default(); count];
+    let mut folded = vec![false; count];
+    let mut net = vec![0i64; count];
+    let mut collected = vec![0u64; count];
+    let mut street = Street::Preflop;
+    let mut raises = 0;
+    let mut first_raiser = None;
+    let mut last_raiser = None;
+    let mut flop_bet = false;
+    let dealt_in = dealt_in(hand);
+
+    for event in hand.events.iter() {
+        match event {
+            Event::Ante { player, amount }
+            | Event::SmallBlind { player, amount }
+            | Event::BigBlind { player, amount } => net[*player] -= *amount as i64,
+            Event::Uncalled { player, amount } => net[*player] += *amount as i64,
+            Event::Collect { player, amount, .. } => {
+                net[*player] += *amount as i64;
+                collected[*player] += amount;
+            }
+            Event::Deal { street: next, .. } => {
+                street = *next;
+                if street == Street::Flop {
+                    for (index, stats) in stats.iter_mut().enumerate() {
+                        stats.flops = u64::from(dealt_in[index] && !folded[index]);
+                    }
+                }
+            }
+            Event::Show { .. } => {}
+            Event::Act {
+                player,
+                action,
+                amount,
+                ..
+            } => {
+                let player = *player;
+                let stats = &mut stats[player];
+                net[player] -= *amount as i64;
+                let aggressive = matches!(action, Action::Bet(_) | Action::Raise(_));
+                if *action == Action::Fold {
+                    folded[player] = true;
+                }
+                if street == Street::Preflop {
+                    if raises == 1 && first_raiser != Some(player) {
+                        stats.three_bet_chances = 1;
+                        stats.three_bets |= u64::from(aggressive);
+                    }
+                    if raises == 2 && first_raiser == Some(player) && last_raiser != Some(player) {
+                        stats.three_bets_faced = 1;
+                        stats.folds_to_three_bet |= u64::from(*action == Action::Fold);
+                    }
+                    stats.vpip |= u64::from(*action != Action::Fold && *action != Action::Check);
+                    stats.pfr |= u64::from(aggressive);
+                    if aggressive {
+                        raises += 1;
+                        first_raiser = first_raiser.or(Some(player));
+                        last_raiser = Some(player);
+                    }
+                    continue;
+                }
+                if street == Street::Flop && !flop_bet && last_raiser == Some(player) {
+                    stats.cbet_chances = 1;
+                    stats.cbets |= u64::from(aggressive);
+                }
+                if street == Street::Flop && aggressive {
+                    flop_bet = true;
+                }
+                match action {
+                    Action::Bet(_) | Action::Raise(_) => stats.aggressive += 1,
+                    Action::Call => stats.calls += 1,
+                    Action::Fold | Action::Check => {}
+                }
+            }
+        }
+    }
+
+    let remaining = (0..count)
+        .filter(|index| dealt_in[*index] && !folded[*index])
+        .count();
+    let big_blind = hand.blinds.big_blind.max(1) as f64;
+    for (index, stats) in stats.iter_mut().enumerate() {
+        if !dealt_in[index] {
+            continue;
+        }
+        stats.hands = 1;
+        // Losers who muck at showdown show nothing, so every player left is counted.
+        stats.showdowns = u64::from(remaining > 1 && stats.flops == 1 && !folded[index]);
+        stats.showdowns_won = u64::from(stats.showdowns == 1 && collected[index] > 0);
+        stats.net = net[index];
+        stats.big_blinds = net[index] as f64 / big_blind;
+    }
+    stats
+}
+
+/// Whether every player posted or acted in the hand. Players with chips who sit out do neither.
+fn dealt_in(hand: &HandHistory) -> Vec<bool> {
+    let mut dealt = vec![false; hand.players.len()];
+    for event in hand.events.iter() {
+        match event {
+            Event::Ante { player, .. }
+            | Event::SmallBlind { player, .. }
+            | Event::BigBlind { player, .. }
+            | Event::Act { player, .. } => dealt[*player] = true,
+            _ => {}
+        }
+    }
+    dealt
+}
@@ -0,0 +1,371 @@
+use std::fmt::Display;
+
+use crate::poker::card::{Card, Quantity};
+use crate::poker::deck::Deck;
+
+pub type ThreeCardHand = [Card; 3];
+
+/// Three card hand categories from the weakest to the strongest.
+/// With three cards a straight is rarer than a flush and ranks above it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThreeCardCategory {
+    HighCard,
+    Pair,
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    StraightFlush,
+}
+
+impl Display for ThreeCardCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThreeCardCategory::HighCard => write!(f, "High card"),
+            ThreeCardCategory::Pair => write!(f, "Pair"),
+            ThreeCardCategory::Flush => write!(f, "Flush"),
+            ThreeCardCategory::Straight => write!(f, "Straight"),
+            ThreeCardCategory::ThreeOfAKind => write!(f, "Three of a kind"),
+            ThreeCardCategory::StraightFlush => write!(f, "Straight Flush"),
+        }
+    }
+}
+
+/// Strength of a three card hand.
+///
+/// Quantities are ordered by significance: a pair comes before its kicker,
+/// and the ace of an ace-two-three straight is the lowest card.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThreeCardRank {
+    pub category: ThreeCardCategory,
+    pub quantities: [Quantity; 3],
+}
+
+impl ThreeCardRank {
+    /// Dealer qualifies with queen high or better.
+    pub fn qualifies(&self) -> bool {
+        self.category > ThreeCardCategory::HighCard || self.quantities[0] >= Quantity::Queen
+    }
+}
+
+impl Display for ThreeCardRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.category)?;
+        for quantity in self.quantities.iter() {
+            write!(f, "{}", quantity)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn evaluate(hand: &ThreeCardHand) -> ThreeCardRank {
+    let mut quantities = [hand[0].quantity, hand[1].quantity, hand[2].quantity];
+    quantities.sort_by(|a, b| b.cmp(a));
+
+    let flush = hand[1].suit == hand[0].suit && hand[2].suit == hand[0].suit;
+    let wheel = quantities == [Quantity::Ace, Quantity::Three, Quantity::Two];
+    let straight = wheel
+        || (quantities[0] as u8 == quantities[1] as u8 + 1
+            && quantities[1] as u8 == quantities[2] as u8 + 1);
+    if wheel {
+        quantities = [Quantity::Three, Quantity::Two, Quantity::Ace];
+    }
+
+    let category = if straight && flush {
+        ThreeCardCategory::StraightFlush
+    } else if quantities[0] == quantities[2] {
+        ThreeCardCategory::ThreeOfAKind
+    } else if straight {
+        ThreeCardCategory::Straight
+    } else if flush {
+        ThreeCardCategory::Flush
+    } else if quantities[0] == quantities[1] {
+        ThreeCardCategory::Pair
+    } else if quantities[1] == quantities[2] {
+        quantities = [quantities[1], quantities[2], quantities[0]];
+        ThreeCardCategory::Pair
+    } else {
+        ThreeCardCategory::HighCard
+    };
+
+    ThreeCardRank {
+        category,
+        quantities,
+    }
+}
+
+/// Pays per unit bet for the bonus bets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Paytables {
+    /// Paid on the ante when the player plays, regardless of the dealer hand.
+    pub ante_bonus: Vec<(ThreeCardCategory, u32)>,
+    /// Paid on the player hand alone.
+    pub pair_plus: Vec<(ThreeCardCategory, u32)>,
+}
+
+impl Paytables {
+    /// Ante bonus of 5-4-1 and the 40-30-6-4-1 Pair Plus table.
+    pub fn standard() -> Paytables {
+        Paytables {
+            ante_bonus: vec![
+                (ThreeCardCategory::StraightFlush, 5),
+                (ThreeCardCategory::ThreeOfAKind, 4),
+                (ThreeCardCategory::Straight, 1),
+            ],
+            pair_plus: vec![
+                (ThreeCardCategory::StraightFlush, 40),
+                (ThreeCardCategory::ThreeOfAKind, 30),
+                (ThreeCardCategory::Straight, 6),
+                (ThreeCardCategory::Flush, 4),
+                (ThreeCardCategory::Pair, 1),
+            ],
+        }
+    }
+
+    pub fn ante_bonus(&self, rank: &ThreeCardRank) -> Option<u32> {
+        lookup(&self.ante_bonus, rank.category)
+    }
+
+    pub fn pair_plus(&self, rank: &ThreeCardRank) -> Option<u32> {
+        lookup(&self.pair_plus, rank.category)
+    }
+
+    /// Resolves all bets of a round. The player either plays, doubling the ante,
+    /// or folds and loses the ante. Pair Plus is paid on the player hand alone.
+    pub fn settle(
+        &self,
+        bets: Bets,
+        player: &ThreeCardHand,
+        dealer: &ThreeCardHand,
+        play: bool,
+    ) -> Settlement {
+        let player = evaluate(player);
+        let dealer = evaluate(dealer);
+        let ante = bets.ante as i64;
+
+        let pair_plus = if bets.pair_plus == 0 {
+            0
+        } else {
+            match self.pair_plus(&player) {
+                Some(pays) => pays as i64 * bets.pair_plus as i64,
+                None => -(bets.pair_plus as i64),
+            }
+        };
+
+        if !play {
+            return Settlement {
+                ante: -ante,
+                play: 0,
+                ante_bonus: 0,
+                pair_plus,
+            };
+        }
+
+        let ante_bonus = self.ante_bonus(&player).unwrap_or(0) as i64 * ante;
+        let (ante, play) = if !dealer.qualifies() {
+            (ante, 0)
+        } else if player > dealer {
+            (ante, ante)
+        } else if player < dealer {
+            (-ante, -ante)
+        } else {
+            (0, 0)
+        };
+
+        Settlement {
+            ante,
+            play,
+            ante_bonus,
+            pair_plus,
+        }
+    }
+
+    /// Computes the house edge of both bets by enumerating every player hand
+    /// against every dealer hand. The player plays whenever playing loses less than folding.
+    pub fn house_edge(&self) -> HouseEdge {
+        let hands: Vec<(u64, ThreeCardRank, u32)> = all_hands()
+            .into_iter()
+            .map(|hand| {
+                let rank = evaluate(&hand);
+                (mask(&hand), rank, key(&rank))
+            })
+            .collect();
+
+        let mut ante_total = 0.0;
+        let mut pair_plus_total = 0.0;
+
+        for (player_mask, player, player_key) in hands.iter() {
+            let mut total: i64 = 0;
+            let mut count: i64 = 0;
+            for (dealer_mask, dealer, dealer_key) in hands.iter() {
+                if player_mask & dealer_mask != 0 {
+                    continue;
+                }
+                count += 1;
+                total += if !dealer.qualifies() {
+                    1
+                } else if player_key > dealer_key {
+                    2
+                } else if player_key < dealer_key {
+                    -2
+                } else {
+                    0
+                };
+            }
+
+            let bonus = self.ante_bonus(player).unwrap_or(0) as f64;
+            let play = bonus + total as f64 / count as f64;
+            ante_total += play.max(-1.0);
+
+            pair_plus_total += match self.pair_plus(player) {
+                Some(pays) => pays as f64,
+                None => -1.0,
+            };
+        }
+
+        HouseEdge {
+            ante: -ante_total / hands.len() as f64,
+            pair_plus: -pair_plus_total / hands.len() as f64,
+        }
+    }
+}
+
+fn lookup(table: &[(ThreeCardCategory, u32)], category: ThreeCardCategory) -> Option<u32> {
+    table
+        .iter()
+        .find(|(entry, _)| *entry == category)
+        .map(|(_, pays)| *pays)
+}
+
+/// Packs a rank into an integer with the same ordering, which is faster to compare.
+fn key(rank: &ThreeCardRank) -> u32 {
+    rank.quantities
+        .iter()
+        .fold(rank.category as u32, |key, quantity| {
+            key << 4 | *quantity as u32
+        })
+}
+
+fn mask(hand: &ThreeCardHand) -> u64 {
+    hand.iter().fold(0, |mask, card| mask | 1 << card.index())
+}
+
+fn all_hands() -> Vec<ThreeCardHand> {
+    let cards = Deck::create().cards;
+    let mut hands = Vec::with_capacity(22100);
+    for a in 0..cards.len() {
+        for b in a + 1..cards.len() {
+            for c in b + 1..cards.len() {
+                hands.push([cards[a], cards[b], cards[c]]);
+            }
+        }
+    }
+    hands
+}
+
+/// Bets of a round in chips. Pair Plus is optional and may be zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bets {
+    pub ante: u32,
+    pub pair_plus: u32,
+}
+
+/// Net chips won or lost on each bet of a round.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Settlement {
+    pub ante: i64,
+    pub play: i64,
+    pub ante_bonus: i64,
+    pub pair_plus: i64,
+}
+
+impl Settlement {
+    pub fn net(&self) -> i64 {
+        self.ante + self.play + self.ante_bonus + self.pair_plus
+    }
+}
+
+/// Expected loss per unit bet.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HouseEdge {
+    pub ante: f64,
+    pub pair_plus: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TableError {
+    InvalidBet,
+    NotEnoughCredits,
+    NotDealt,
+    AlreadyDealt,
+    /// The deck has fewer than the six cards of a round.
+    NotEnoughCards,
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::InvalidBet => write!(f, "Ante must be positive."),
+            TableError::NotEnoughCredits => write!(f, "Not enough credits."),
+            TableError::NotDealt => write!(f, "No hand has been dealt."),
+            TableError::AlreadyDealt => write!(f, "Hand has already been dealt."),
+            TableError::NotEnoughCards => write!(f, "Not enough cards in the deck."),
+        }
+    }
+}
+
+/// A Three Card Poker table for a single player:
+/// `deal` takes the ante and Pair Plus bets, `decide` plays or folds and settles the round.
+pub struct Table {
+    pub paytables: Paytables,
+    pub credits: u32,
+    round: Option<(Bets, ThreeCardHand, ThreeCardHand)>,
+}
+
+impl Table {
+    pub fn new(paytables: Paytables, credits: u32) -> Table {
+        Table {
+            paytables,
+            credits,
+            round: None,
+        }
+    }
+
+    pub fn deal(&mut self, bets: Bets) -> Result<ThreeCardHand, TableError> {
+        let mut deck = Deck::create();
+        deck.shuffle();
+        self.deal_from(deck, bets)
+    }
+
+    /// Deals the player first and the dealer second from the given deck.
+    pub fn deal_from(&mut self, mut deck: Deck, bets: Bets) -> Result<ThreeCardHand, TableError> {
+        if self.round.is_some() {
+            return Err(TableError::AlreadyDealt);
+        }
+        if bets.ante == 0 {
+            return Err(TableError::InvalidBet);
+        }
+        // The play bet has to be covered as well.
+        if 2 * bets.ante as u64 + bets.pair_plus as u64 > self.credits as u64 {
+            return Err(TableError::NotEnoughCredits);
+        }
+
+        if deck.cards.len() < 6 {
+            return Err(TableError::NotEnoughCards);
+        }
+        let mut deal = || deck.deal().ok_or(TableError::NotEnoughCards);
+        let player = [deal()?, deal()?, deal()?];
+        let dealer = [deal()?, deal()?, deal()?];
+        self.round = Some((bets, player, dealer));
+        Ok(player)
+    }
+
+    /// Returns the dealer hand together with the result of the round.
+    pub fn decide(&mut self, play: bool) -> Result<(ThreeCardHand, Settlement), TableError> {
+        let (bets, player, dealer) = self.round.take().ok_or(TableError::NotDealt)?;
+        let settlement = self.paytables.settle(bets, &player, &dealer, play);
+        self.credits = (self.credits as i64 + settlement.net()) as u32;
+        Ok((dealer, settlement))
+    }
+}
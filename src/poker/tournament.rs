@@ -0,0 +1,504 @@
+//! Tournaments: blind levels, eliminations, payouts and seating over several tables.
+//!
+//! A structure is usually loaded from a config file of `key = value` lines:
+//!
+//! ```text
+//! # Comments start with a hash.
+//! name = Sunday Special
+//! buy_in = 100
+//! stack = 1500
+//! seats = 9
+//! payouts = 50, 30, 20
+//! level_hands = 10
+//! level = 10/20
+//! level = 20/40/5
+//! ```
+//!
+//! Levels are `small/big` or `small/big/ante` and last either `level_hands` hands
+//! or `level_minutes` minutes. Payouts are percentages of the prize pool by place.
+
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::poker::deck::Deck;
+use crate::poker::game::{Action, Blinds, Game, Table};
+
+/// Source of the time the tournament has been running, so tests can control it.
+pub trait Clock {
+    fn elapsed(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    elapsed: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn elapsed(&self) -> Duration {
+        self.elapsed.get()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LevelLength {
+    Hands(u32),
+    Time(Duration),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Structure {
+    pub name: String,
+    pub buy_in: u64,
+    pub starting_stack: u64,
+    /// Seats at every table.
+    pub seats: usize,
+    pub levels: Vec<Blinds>,
+    pub length: LevelLength,
+    /// Percentages of the prize pool, from the first place down.
+    pub payouts: Vec<f64>,
+}
+
+impl Structure {
+    /// The level after the given number of hands or time, the last level never ends.
+    pub fn level(&self, hands: u32, elapsed: Duration) -> usize {
+        let level = match self.length {
+            LevelLength::Hands(length) => (hands / length.max(1)) as usize,
+            LevelLength::Time(length) => {
+                (elapsed.as_secs_f64() / length.as_secs_f64().max(f64::MIN_POSITIVE)) as usize
+            }
+        };
+        level.min(self.levels.len() - 1)
+    }
+
+    /// Prizes by place for the number of entrants, rounded down to whole chips.
+    /// The remainder, with the shares of places the field is too small for, goes to the first place.
+    pub fn prizes(&self, entrants: usize) -> Vec<u64> {
+        let pool = self.buy_in * entrants as u64;
+        let mut prizes: Vec<u64> = self
+            .payouts
+            .iter()
+            .take(entrants)
+            .map(|percent| (pool as f64 * percent / 100.0) as u64)
+            .collect();
+        let paid: u64 = prizes.iter().sum();
+        let total: f64 = self.payouts.iter().sum();
+        if let Some(first) = prizes.first_mut() {
+            // Only a full payout table hands out the whole pool.
+            if (total - 100.0).abs() < 1e-9 {
+                *first += pool - paid;
+            }
+        }
+        prizes
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Structure, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|error| ConfigError {
+            line: 0,
+            message: error.to_string(),
+        })?;
+        text.parse()
+    }
+}
+
+/// An error in a config file, on a line counted from 1 or on line 0 for the file as a whole.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "Line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl FromStr for Structure {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut structure = Structure {
+            name: String::from("Tournament"),
+            buy_in: 0,
+            starting_stack: 0,
+            seats: 9,
+            levels: Vec::new(),
+            length: LevelLength::Hands(0),
+            payouts: vec![100.0],
+        };
+        let mut length = None;
+
+        for (number, line) in s.lines().enumerate() {
+            let error = |message: &str| ConfigError {
+                line: number + 1,
+                message: message.to_string(),
+            };
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("Expected a line like \"key = value\"."))?;
+            let value = value.trim();
+            let number = |value: &str| {
+                value
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|_| error(&format!("Invalid number \"{}\".", value.trim())))
+            };
+
+            match key.trim() {
+                "name" => structure.name = value.to_string(),
+                "buy_in" => structure.buy_in = number(value)?,
+                "stack" => structure.starting_stack = number(value)?,
+                "seats" => structure.seats = number(value)? as usize,
+                "level_hands" => length = Some(LevelLength::Hands(number(value)? as u32)),
+                "level_minutes" => {
+                    length = Some(LevelLength::Time(Duration::from_secs(number(value)? * 60)))
+                }
+                "level" => {
+                    let amounts = value
+                        .split('/')
+                        .map(number)
+                        .collect::<Result<Vec<u64>, ConfigError>>()?;
+                    let blinds = match amounts[..] {
+                        [small, big] => Blinds::new(small, big, 0),
+                        [small, big, ante] => Blinds::new(small, big, ante),
+                        _ => return Err(error("Expected a level like \"10/20\" or \"10/20/5\".")),
+                    };
+                    if blinds.big_blind == 0 || blinds.small_blind > blinds.big_blind {
+                        return Err(error(
+                            "The big blind must be positive and at least the small blind.",
+                        ));
+                    }
+                    structure.levels.push(blinds);
+                }
+                "payouts" => {
+                    structure.payouts = value
+                        .split(',')
+                        .map(|percent| {
+                            percent
+                                .trim()
+                                .parse::<f64>()
+                                .ok()
+                                .filter(|percent| *percent >= 0.0)
+                                .ok_or_else(|| {
+                                    error(&format!("Invalid percentage \"{}\".", percent.trim()))
+                                })
+                        })
+                        .collect::<Result<Vec<f64>, ConfigError>>()?;
+                    if structure.payouts.iter().sum::<f64>() > 100.0 + 1e-9 {
+                        return Err(error("Payouts add up to more than 100 percent."));
+                    }
+                }
+                key => return Err(error(&format!("Unknown key \"{}\".", key))),
+            }
+        }
+
+        let missing = |message: &str| ConfigError {
+            line: 0,
+            message: message.to_string(),
+        };
+        if structure.levels.is_empty() {
+            return Err(missing("The structure has no levels."));
+        }
+        if structure.starting_stack == 0 {
+            return Err(missing("The starting stack is missing."));
+        }
+        if structure.seats < 2 {
+            return Err(missing("Tables need at least two seats."));
+        }
+        structure.length = match length {
+            Some(LevelLength::Hands(0)) | Some(LevelLength::Time(Duration::ZERO)) => {
+                return Err(missing("Levels must last at least one hand or one minute."))
+            }
+            None => return Err(missing("Set either level_hands or level_minutes.")),
+            Some(length) => length,
+        };
+        Ok(structure)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finish {
+    pub name: String,
+    pub place: usize,
+    pub prize: u64,
+}
+
+/// A player moved by balancing or breaking tables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub name: String,
+    /// Table and seat before the move. A broken table keeps the index it had before it was
+    /// removed, so the tables after it are numbered one lower in `to` and in later moves.
+    pub from: (usize, usize),
+    /// Table and seat after the move.
+    pub to: (usize, usize),
+}
+
+pub struct Tournament {
+    pub structure: Structure,
+    pub tables: Vec<Table>,
+    /// Players in the order they were eliminated, the winner last.
+    pub finishes: Vec<Finish>,
+    entrants: usize,
+    hands: u32,
+    clock: Box<dyn Clock>,
+    rng: StdRng,
+}
+
+impl Tournament {
+    pub fn new(structure: Structure, names: &[&str], seed: u64) -> Tournament {
+        Tournament::with_clock(structure, names, seed, Box::new(SystemClock::new()))
+    }
+
+    /// Draws seats at random over as few tables as needed, filled as evenly as possible.
+    pub fn with_clock(
+        structure: Structure,
+        names: &[&str],
+        seed: u64,
+        clock: Box<dyn Clock>,
+    ) -> Tournament {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut names = names.to_vec();
+        names.shuffle(&mut rng);
+
+        let count = names.len().div_ceil(structure.seats);
+        let mut tables: Vec<Table> = (0..count.max(1))
+            .map(|_| Table::new(structure.seats))
+            .collect();
+        for (position, name) in names.iter().enumerate() {
+            let table = position % tables.len();
+            let seat = position / tables.len();
+            tables[table].sit(seat, name, structure.starting_stack);
+        }
+
+        Tournament {
+            structure,
+            tables,
+            finishes: Vec::new(),
+            entrants: names.len(),
+            hands: 0,
+            clock,
+            rng,
+        }
+    }
+
+    pub fn entrants(&self) -> usize {
+        self.entrants
+    }
+
+    /// Hands played at every table so far.
+    pub fn hands(&self) -> u32 {
+        self.hands
+    }
+
+    pub fn level(&self) -> usize {
+        self.structure.level(self.hands, self.clock.elapsed())
+    }
+
+    pub fn blinds(&self) -> Blinds {
+        self.structure.levels[self.level()]
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.tables.iter().map(Table::players).sum()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining() <= 1
+    }
+
+    /// Plays a hand at every table, then eliminates the busted players and balances the tables.
+    /// Returns the finishes of this round.
+    pub fn play_round<F: FnMut(&Game) -> Action>(&mut self, mut decide: F) -> Vec<Finish> {
+        if self.is_finished() {
+            return Vec::new();
+        }
+        let blinds = self.blinds();
+        let mut busted = Vec::new();
+        for (index, table) in self.tables.iter_mut().enumerate() {
+            if table.players() < 2 {
+                continue;
+            }
+            let starting: Vec<u64> = table
+                .seats
+                .iter()
+                .map(|seat| seat.as_ref().map_or(0, |seat| seat.stack))
+                .collect();
+            let mut deck = Deck::create();
+            deck.cards.shuffle(&mut self.rng);
+            table.play(blinds, deck, &mut decide);
+
+            for (seat, stack) in starting.iter().enumerate() {
+                if matches!(&table.seats[seat], Some(player) if player.stack == 0) {
+                    busted.push((*stack, index, seat));
+                }
+            }
+        }
+        self.hands += 1;
+
+        // Players busted in the same round finish in the order of their stacks before the hand.
+        busted.sort_by_key(|(stack, _, _)| Reverse(*stack));
+        let prizes = self.structure.prizes(self.entrants);
+        let best = self.remaining() - busted.len() + 1;
+        let mut finishes = Vec::new();
+        for (place, (_, table, seat)) in (best..).zip(busted) {
+            let player = self.tables[table].leave(seat).unwrap();
+            finishes.push(Finish {
+                name: player.name,
+                place,
+                prize: prizes.get(place - 1).copied().unwrap_or(0),
+            });
+        }
+        finishes.reverse();
+
+        if self.remaining() == 1 {
+            let (table, seat) = self.seated()[0];
+            let winner = self.tables[table].seats[seat].as_ref().unwrap();
+            finishes.push(Finish {
+                name: winner.name.clone(),
+                place: 1,
+                prize: prizes.first().copied().unwrap_or(0),
+            });
+        } else {
+            self.balance();
+        }
+        self.finishes.extend(finishes.iter().cloned());
+        finishes
+    }
+
+    /// Plays rounds until a winner is left and returns all finishes.
+    pub fn play<F: FnMut(&Game) -> Action>(&mut self, mut decide: F) -> &[Finish] {
+        while !self.is_finished() {
+            self.play_round(&mut decide);
+        }
+        &self.finishes
+    }
+
+    /// Breaks tables while the players fit on fewer of them,
+    /// then moves players from the fullest tables to the emptiest ones.
+    pub fn balance(&mut self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let seats = self.structure.seats;
+
+        while self.tables.len() > 1 && self.remaining() <= (self.tables.len() - 1) * seats {
+            let broken = (0..self.tables.len())
+                .min_by_key(|table| self.tables[*table].players())
+                .unwrap();
+            // The players leave with the index of the broken table, the others shift down.
+            let table = self.tables.remove(broken);
+            for (seat, player) in table.seats.into_iter().enumerate() {
+                if let Some(player) = player {
+                    let to = (0..self.tables.len())
+                        .min_by_key(|table| self.tables[*table].players())
+                        .unwrap();
+                    let to_seat = self.tables[to].empty_seats()[0];
+                    self.tables[to].sit(to_seat, &player.name, player.stack);
+                    moves.push(Move {
+                        name: player.name,
+                        from: (broken, seat),
+                        to: (to, to_seat),
+                    });
+                }
+            }
+        }
+
+        loop {
+            let players: Vec<usize> = self.tables.iter().map(Table::players).collect();
+            let (Some(most), Some(least)) = (
+                (0..players.len()).max_by_key(|table| players[*table]),
+                (0..players.len()).min_by_key(|table| players[*table]),
+            ) else {
+                break;
+            };
+            if players[most] <= players[least] + 1 {
+                break;
+            }
+            // Moves the player who would post the big blind next, so nobody skips the blinds twice.
+            let from = &self.tables[most];
+            let seat = (1..=seats)
+                .map(|offset| (from.button + offset + 1) % seats)
+                .find(|seat| from.seats[*seat].is_some())
+                .unwrap();
+            let player = self.tables[most].leave(seat).unwrap();
+            let to_seat = self.tables[least].empty_seats()[0];
+            self.tables[least].sit(to_seat, &player.name, player.stack);
+            moves.push(Move {
+                name: player.name,
+                from: (most, seat),
+                to: (least, to_seat),
+            });
+        }
+        moves
+    }
+
+    /// Table and seat of every remaining player.
+    fn seated(&self) -> Vec<(usize, usize)> {
+        self.tables
+            .iter()
+            .enumerate()
+            .flat_map(|(index, table)| {
+                table
+                    .seats
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, seat)| seat.is_some())
+                    .map(move |(seat, _)| (index, seat))
+            })
+            .collect()
+    }
+}
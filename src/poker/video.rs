@@ -0,0 +1,464 @@
+use std::fmt::Display;
+
+use crate::poker::card::{Hand, Quantity, Suit};
+use crate::poker::deck::Deck;
+
+/// A hand that a video poker paytable pays for.
+///
+/// Variants that carry quantities are qualifiers:
+/// `Pair(Quantity::Jack)` is "Jacks or better",
+/// `FourOfAKind(Quantity::Two, Quantity::Four)` is "Four Twos through Fours".
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PayHand {
+    NaturalRoyal,
+    FourWilds,
+    WildRoyal,
+    FiveOfAKind,
+    StraightFlush,
+    FourOfAKind(Quantity, Quantity),
+    FullHouse,
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    TwoPairs,
+    Pair(Quantity),
+}
+
+impl Display for PayHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayHand::NaturalRoyal => write!(f, "Royal Flush"),
+            PayHand::FourWilds => write!(f, "Four Wilds"),
+            PayHand::WildRoyal => write!(f, "Wild Royal Flush"),
+            PayHand::FiveOfAKind => write!(f, "Five of a kind"),
+            PayHand::StraightFlush => write!(f, "Straight Flush"),
+            PayHand::FourOfAKind(Quantity::Two, Quantity::Ace) => write!(f, "Four of a kind"),
+            PayHand::FourOfAKind(low, high) if low == high => write!(f, "Four {}", low.plural()),
+            PayHand::FourOfAKind(low, high) => {
+                write!(f, "Four {} through {}", low.plural(), high.plural())
+            }
+            PayHand::FullHouse => write!(f, "Full house"),
+            PayHand::Flush => write!(f, "Flush"),
+            PayHand::Straight => write!(f, "Straight"),
+            PayHand::ThreeOfAKind => write!(f, "Three of a kind"),
+            PayHand::TwoPairs => write!(f, "Two pairs"),
+            PayHand::Pair(Quantity::Jack) => write!(f, "Jacks or better"),
+            PayHand::Pair(low) => write!(f, "{} or better", low.plural()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PayEntry {
+    pub hand: PayHand,
+    pub pays: u32,
+}
+
+/// Payouts per coin bet, ordered from the best hand to the worst.
+/// The first entry that matches the hand is paid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Paytable {
+    pub name: &'static str,
+    pub wild: Option<Quantity>,
+    pub entries: Vec<PayEntry>,
+    pub max_bet: u32,
+    /// Pays per coin for a natural royal flush when the maximum bet is played.
+    pub max_bet_royal: u32,
+}
+
+impl Paytable {
+    /// Full pay 9/6 Jacks or Better.
+    pub fn jacks_or_better() -> Paytable {
+        Paytable {
+            name: "Jacks or Better 9/6",
+            wild: None,
+            entries: vec![
+                PayEntry {
+                    hand: PayHand::NaturalRoyal,
+                    pays: 250,
+                },
+                PayEntry {
+                    hand: PayHand::StraightFlush,
+                    pays: 50,
+                },
+                PayEntry {
+                    hand: PayHand::FourOfAKind(Quantity::Two, Quantity::Ace),
+                    pays: 25,
+                },
+                PayEntry {
+                    hand: PayHand::FullHouse,
+                    pays: 9,
+                },
+                PayEntry {
+                    hand: PayHand::Flush,
+                    pays: 6,
+                },
+                PayEntry {
+                    hand: PayHand::Straight,
+                    pays: 4,
+                },
+                PayEntry {
+                    hand: PayHand::ThreeOfAKind,
+                    pays: 3,
+                },
+                PayEntry {
+                    hand: PayHand::TwoPairs,
+                    pays: 2,
+                },
+                PayEntry {
+                    hand: PayHand::Pair(Quantity::Jack),
+                    pays: 1,
+                },
+            ],
+            max_bet: 5,
+            max_bet_royal: 800,
+        }
+    }
+
+    /// 8/5 Bonus Poker, which pays extra for four aces and four low cards.
+    pub fn bonus_poker() -> Paytable {
+        Paytable {
+            name: "Bonus Poker 8/5",
+            wild: None,
+            entries: vec![
+                PayEntry {
+                    hand: PayHand::NaturalRoyal,
+                    pays: 250,
+                },
+                PayEntry {
+                    hand: PayHand::StraightFlush,
+                    pays: 50,
+                },
+                PayEntry {
+                    hand: PayHand::FourOfAKind(Quantity::Ace, Quantity::Ace),
+                    pays: 80,
+                },
+                PayEntry {
+                    hand: PayHand::FourOfAKind(Quantity::Two, Quantity::Four),
+                    pays: 40,
+                },
+                PayEntry {
+                    hand: PayHand::FourOfAKind(Quantity::Five, Quantity::King),
+                    pays: 25,
+                },
+                PayEntry {
+                    hand: PayHand::FullHouse,
+                    pays: 8,
+                },
+                PayEntry {
+                    hand: PayHand::Flush,
+                    pays: 5,
+                },
+                PayEntry {
+                    hand: PayHand::Straight,
+                    pays: 4,
+                },
+                PayEntry {
+                    hand: PayHand::ThreeOfAKind,
+                    pays: 3,
+                },
+                PayEntry {
+                    hand: PayHand::TwoPairs,
+                    pays: 2,
+                },
+                PayEntry {
+                    hand: PayHand::Pair(Quantity::Jack),
+                    pays: 1,
+                },
+            ],
+            max_bet: 5,
+            max_bet_royal: 800,
+        }
+    }
+
+    /// Full pay Deuces Wild, where every two substitutes for any card.
+    pub fn deuces_wild() -> Paytable {
+        Paytable {
+            name: "Deuces Wild",
+            wild: Some(Quantity::Two),
+            entries: vec![
+                PayEntry {
+                    hand: PayHand::NaturalRoyal,
+                    pays: 250,
+                },
+                PayEntry {
+                    hand: PayHand::FourWilds,
+                    pays: 200,
+                },
+                PayEntry {
+                    hand: PayHand::WildRoyal,
+                    pays: 25,
+                },
+                PayEntry {
+                    hand: PayHand::FiveOfAKind,
+                    pays: 15,
+                },
+                PayEntry {
+                    hand: PayHand::StraightFlush,
+                    pays: 9,
+                },
+                PayEntry {
+                    hand: PayHand::FourOfAKind(Quantity::Two, Quantity::Ace),
+                    pays: 5,
+                },
+                PayEntry {
+                    hand: PayHand::FullHouse,
+                    pays: 3,
+                },
+                PayEntry {
+                    hand: PayHand::Flush,
+                    pays: 2,
+                },
+                PayEntry {
+                    hand: PayHand::Straight,
+                    pays: 2,
+                },
+                PayEntry {
+                    hand: PayHand::ThreeOfAKind,
+                    pays: 1,
+                },
+            ],
+            max_bet: 5,
+            max_bet_royal: 800,
+        }
+    }
+
+    /// Returns the best paying entry that matches the hand.
+    pub fn lookup(&self, hand: &Hand) -> Option<PayEntry> {
+        let analysis = Analysis::create(hand, self.wild);
+        self.entries
+            .iter()
+            .find(|entry| analysis.matches(entry.hand))
+            .copied()
+    }
+
+    /// Returns the total amount paid for the hand with the given bet.
+    pub fn payout(&self, hand: &Hand, bet: u32) -> u32 {
+        match self.lookup(hand) {
+            Some(PayEntry {
+                hand: PayHand::NaturalRoyal,
+                ..
+            }) if bet >= self.max_bet => self.max_bet_royal * bet,
+            Some(entry) => entry.pays * bet,
+            None => 0,
+        }
+    }
+}
+
+/// Shape of a hand with wild cards separated from the natural ones.
+struct Analysis {
+    wilds: usize,
+    counts: [usize; 15],
+    suited: bool,
+    distinct: bool,
+    /// Bit per natural quantity, an ace sets both the first and the fourteenth bit.
+    quantities: u16,
+}
+
+impl Analysis {
+    fn create(hand: &Hand, wild: Option<Quantity>) -> Analysis {
+        let mut analysis = Analysis {
+            wilds: 0,
+            counts: [0; 15],
+            suited: true,
+            distinct: true,
+            quantities: 0,
+        };
+        let mut suit: Option<Suit> = None;
+
+        for card in hand {
+            if Some(card.quantity) == wild {
+                analysis.wilds += 1;
+                continue;
+            }
+            let count = &mut analysis.counts[card.quantity as usize];
+            *count += 1;
+            if *count > 1 {
+                analysis.distinct = false;
+            }
+            analysis.quantities |= 1 << card.quantity as u16;
+            if card.quantity == Quantity::Ace {
+                analysis.quantities |= 1 << 1;
+            }
+            match suit {
+                Some(suit) if suit != card.suit => analysis.suited = false,
+                _ => suit = Some(card.suit),
+            }
+        }
+        analysis
+    }
+
+    /// Largest count of a single natural quantity.
+    fn most(&self) -> usize {
+        *self.counts.iter().max().unwrap_or(&0)
+    }
+
+    /// Counts of the two most frequent natural quantities.
+    fn top_two(&self) -> (usize, usize) {
+        let mut top = (0, 0);
+        for &count in &self.counts[2..] {
+            if count > top.0 {
+                top = (count, top.0);
+            } else if count > top.1 {
+                top.1 = count;
+            }
+        }
+        top
+    }
+
+    /// Checks whether the natural cards fit into a five card window,
+    /// counting an ace as both the lowest and the highest card.
+    fn fits_straight(&self, low: u16) -> bool {
+        let window = 0b11111 << low;
+        // The low ace bit only matters for the wheel.
+        let quantities = if low == 1 {
+            self.quantities & !(1 << 14)
+        } else {
+            self.quantities & !(1 << 1)
+        };
+        self.distinct && quantities & !window == 0
+    }
+
+    fn straight(&self) -> bool {
+        (1..=10).any(|low| self.fits_straight(low))
+    }
+
+    fn royal(&self) -> bool {
+        self.suited && self.fits_straight(10)
+    }
+
+    fn matches(&self, hand: PayHand) -> bool {
+        let most = self.most();
+        match hand {
+            PayHand::NaturalRoyal => self.wilds == 0 && self.royal(),
+            PayHand::FourWilds => self.wilds == 4,
+            PayHand::WildRoyal => self.wilds > 0 && self.royal(),
+            PayHand::FiveOfAKind => most + self.wilds >= 5,
+            PayHand::StraightFlush => self.suited && self.straight(),
+            PayHand::FourOfAKind(low, high) => {
+                self.wilds >= 4
+                    || (low as usize..=high as usize).any(|quantity| {
+                        self.counts[quantity] > 0 && self.counts[quantity] + self.wilds >= 4
+                    })
+            }
+            PayHand::FullHouse => {
+                let (first, second) = self.top_two();
+                3usize.saturating_sub(first) + 2usize.saturating_sub(second) <= self.wilds
+            }
+            PayHand::Flush => self.suited,
+            PayHand::Straight => self.straight(),
+            PayHand::ThreeOfAKind => most + self.wilds >= 3,
+            PayHand::TwoPairs => {
+                let (first, second) = self.top_two();
+                first >= 2 && second >= 2
+            }
+            PayHand::Pair(low) => (2..15).any(|quantity| {
+                quantity >= low as usize
+                    && self.counts[quantity] > 0
+                    && self.counts[quantity] + self.wilds >= 2
+            }),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MachineError {
+    InvalidBet,
+    NotEnoughCredits,
+    NotDealt,
+    AlreadyDealt,
+    /// The deck has too few cards to deal the hand or to replace the drawn cards.
+    NotEnoughCards,
+}
+
+impl Display for MachineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MachineError::InvalidBet => write!(f, "Bet is out of range."),
+            MachineError::NotEnoughCredits => write!(f, "Not enough credits."),
+            MachineError::NotDealt => write!(f, "No hand has been dealt."),
+            MachineError::AlreadyDealt => write!(f, "Hand has already been dealt."),
+            MachineError::NotEnoughCards => write!(f, "Not enough cards in the deck."),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub hand: Hand,
+    pub entry: Option<PayEntry>,
+    pub won: u32,
+}
+
+/// A single video poker machine playing one hand at a time:
+/// `deal` takes the bet and deals five cards,
+/// `draw` replaces the cards which are not held and pays the result.
+pub struct Machine {
+    pub paytable: Paytable,
+    pub credits: u32,
+    deck: Deck,
+    hand: Option<Hand>,
+    bet: u32,
+}
+
+impl Machine {
+    pub fn new(paytable: Paytable, credits: u32) -> Machine {
+        Machine {
+            paytable,
+            credits,
+            deck: Deck::create(),
+            hand: None,
+            bet: 0,
+        }
+    }
+
+    pub fn deal(&mut self, bet: u32) -> Result<Hand, MachineError> {
+        let mut deck = Deck::create();
+        deck.shuffle();
+        self.deal_from(deck, bet)
+    }
+
+    /// Deals from the given deck, which lets the caller control the card order.
+    /// A deck too short to replace the drawn cards fails at the draw, and the hand is kept.
+    pub fn deal_from(&mut self, mut deck: Deck, bet: u32) -> Result<Hand, MachineError> {
+        if self.hand.is_some() {
+            return Err(MachineError::AlreadyDealt);
+        }
+        if bet == 0 || bet > self.paytable.max_bet {
+            return Err(MachineError::InvalidBet);
+        }
+        if bet > self.credits {
+            return Err(MachineError::NotEnoughCredits);
+        }
+
+        let hand = deck.deal_hand().ok_or(MachineError::NotEnoughCards)?;
+        self.credits -= bet;
+        self.bet = bet;
+        self.deck = deck;
+        self.hand = Some(hand);
+        Ok(hand)
+    }
+
+    pub fn draw(&mut self, held: [bool; 5]) -> Result<Outcome, MachineError> {
+        let mut hand = self.hand.ok_or(MachineError::NotDealt)?;
+        let drawn = held.iter().filter(|held| !**held).count();
+        if drawn > self.deck.cards.len() {
+            return Err(MachineError::NotEnoughCards);
+        }
+        self.hand = None;
+
+        for (card, held) in hand.iter_mut().zip(held.iter()) {
+            if !held {
+                *card = self.deck.deal().ok_or(MachineError::NotEnoughCards)?;
+            }
+        }
+
+        let won = self.paytable.payout(&hand, self.bet);
+        self.credits += won;
+        Ok(Outcome {
+            hand,
+            entry: self.paytable.lookup(&hand),
+            won,
+        })
+    }
+}
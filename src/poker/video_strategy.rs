@@ -0,0 +1,254 @@
+use std::fmt::Display;
+
+use crate::poker::card::{Card, Hand};
+use crate::poker::deck::Deck;
+use crate::poker::index::HANDS;
+use crate::poker::video::Paytable;
+
+/// A choice of cards to keep with the expected return per coin played.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hold {
+    pub held: [bool; 5],
+    pub expected: f64,
+}
+
+impl Hold {
+    pub fn cards(&self, hand: &Hand) -> Vec<Card> {
+        hand.iter()
+            .zip(self.held.iter())
+            .filter(|(_, held)| **held)
+            .map(|(card, _)| *card)
+            .collect()
+    }
+}
+
+impl Display for Hold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for held in self.held.iter() {
+            f.write_str(if *held { "H" } else { "-" })?;
+        }
+        write!(f, " {:.5}", self.expected)
+    }
+}
+
+/// Computes exact expected returns of video poker holds for a paytable.
+///
+/// Creating a calculator evaluates every five card hand once
+/// and sums the payouts of all hands that contain each smaller set of cards.
+/// The return of a hold is then found by inclusion-exclusion over the discarded cards,
+/// which is fast enough to play every possible deal.
+pub struct Calculator {
+    pub paytable: Paytable,
+    binomials: [[usize; 6]; 53],
+    payouts: Vec<u32>,
+    totals: Vec<Vec<u64>>,
+}
+
+impl Calculator {
+    pub fn new(paytable: Paytable) -> Calculator {
+        let binomials = binomials();
+        let mut payouts = vec![0; HANDS as usize];
+        let mut totals: Vec<Vec<u64>> = (0..6).map(|size| vec![0; binomials[52][size]]).collect();
+
+        for_each_hand(|ordinals| {
+            let hand = ordinals.map(from_ordinal);
+            let payout = paytable.payout(&hand, paytable.max_bet);
+            payouts[index(&binomials, &ordinals)] = payout;
+
+            let indices = subset_indices(&binomials, &ordinals);
+            for (mask, subset) in indices.iter().enumerate() {
+                totals[(mask as u32).count_ones() as usize][*subset] += payout as u64;
+            }
+        });
+
+        Calculator {
+            paytable,
+            binomials,
+            payouts,
+            totals,
+        }
+    }
+
+    /// Returns all 32 holds sorted from the best to the worst.
+    pub fn holds(&self, hand: &Hand) -> Vec<Hold> {
+        let mut sorted: Vec<(usize, usize)> = hand
+            .iter()
+            .enumerate()
+            .map(|(position, card)| (ordinal(card), position))
+            .collect();
+        sorted.sort_unstable();
+        let ordinals = [0, 1, 2, 3, 4].map(|i| sorted[i].0);
+        let returns = self.returns(&ordinals);
+
+        let mut holds: Vec<Hold> = (0..32)
+            .map(|mask: usize| {
+                let mut held = [false; 5];
+                for (i, (_, position)) in sorted.iter().enumerate() {
+                    held[*position] = mask & (1 << i) != 0;
+                }
+                Hold {
+                    held,
+                    expected: returns[mask],
+                }
+            })
+            .collect();
+        holds.sort_by(|a, b| b.expected.partial_cmp(&a.expected).unwrap());
+        holds
+    }
+
+    pub fn best_hold(&self, hand: &Hand) -> Hold {
+        self.holds(hand)[0]
+    }
+
+    /// Computes the return of a hold by drawing every combination
+    /// of the replaced cards from the remaining 47 cards.
+    pub fn enumerate(&self, hand: &Hand, held: [bool; 5]) -> f64 {
+        let mut deck = Deck::create();
+        for card in hand {
+            deck.remove(card);
+        }
+        let remaining: Vec<usize> = deck.cards.iter().map(ordinal).collect();
+        let kept: Vec<usize> = hand
+            .iter()
+            .zip(held.iter())
+            .filter(|(_, held)| **held)
+            .map(|(card, _)| ordinal(card))
+            .collect();
+
+        let draws = 5 - kept.len();
+        let mut total: u64 = 0;
+        let mut count: u64 = 0;
+        let mut chosen = Vec::with_capacity(draws);
+        choose(&remaining, draws, 0, &mut chosen, &mut |drawn| {
+            let mut ordinals = [0; 5];
+            for (slot, card) in ordinals.iter_mut().zip(kept.iter().chain(drawn.iter())) {
+                *slot = *card;
+            }
+            ordinals.sort_unstable();
+            total += self.payouts[index(&self.binomials, &ordinals)] as u64;
+            count += 1;
+        });
+
+        total as f64 / count as f64 / self.paytable.max_bet as f64
+    }
+
+    /// Returns the expected return per coin of the paytable when every deal
+    /// is played with its best hold.
+    pub fn return_rate(&self) -> f64 {
+        let mut total = 0.0;
+        for_each_hand(|ordinals| {
+            let returns = self.returns(&ordinals);
+            total += returns.iter().cloned().fold(f64::MIN, f64::max);
+        });
+        total / HANDS as f64
+    }
+
+    /// Expected returns of the holds of sorted ordinals, indexed by the mask of held cards.
+    fn returns(&self, ordinals: &[usize; 5]) -> [f64; 32] {
+        let indices = subset_indices(&self.binomials, ordinals);
+        let mut returns = [0.0; 32];
+
+        for (held, expected) in returns.iter_mut().enumerate() {
+            let discarded = !held & 31;
+            let mut total: i64 = 0;
+
+            // Walks every subset of the discarded cards: hands containing
+            // a discarded card cannot be drawn and are subtracted.
+            let mut subset = discarded;
+            loop {
+                let mask = held | subset;
+                let sum = self.totals[mask.count_ones() as usize][indices[mask]] as i64;
+                if subset.count_ones() % 2 == 0 {
+                    total += sum;
+                } else {
+                    total -= sum;
+                }
+                if subset == 0 {
+                    break;
+                }
+                subset = (subset - 1) & discarded;
+            }
+
+            let draws = self.binomials[47][discarded.count_ones() as usize];
+            *expected = total as f64 / draws as f64 / self.paytable.max_bet as f64;
+        }
+        returns
+    }
+}
+
+fn ordinal(card: &Card) -> usize {
+    card.index() as usize
+}
+
+fn from_ordinal(ordinal: usize) -> Card {
+    Card::from_index(ordinal as u8).unwrap()
+}
+
+fn binomials() -> [[usize; 6]; 53] {
+    let mut table = [[0; 6]; 53];
+    for n in 0..53 {
+        table[n][0] = 1;
+        for k in 1..6 {
+            if n > 0 {
+                table[n][k] = table[n - 1][k - 1] + table[n - 1][k];
+            }
+        }
+    }
+    table
+}
+
+/// Combinatorial index of sorted ordinals among all sets of the same size.
+fn index(binomials: &[[usize; 6]; 53], ordinals: &[usize]) -> usize {
+    ordinals
+        .iter()
+        .enumerate()
+        .map(|(i, ordinal)| binomials[*ordinal][i + 1])
+        .sum()
+}
+
+/// Indices of all subsets of sorted ordinals, keyed by the mask of included cards.
+fn subset_indices(binomials: &[[usize; 6]; 53], ordinals: &[usize; 5]) -> [usize; 32] {
+    let mut indices = [0; 32];
+    for (mask, index) in indices.iter_mut().enumerate() {
+        let mut size = 0;
+        for (i, ordinal) in ordinals.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                size += 1;
+                *index += binomials[*ordinal][size];
+            }
+        }
+    }
+    indices
+}
+
+fn for_each_hand<F: FnMut([usize; 5])>(mut f: F) {
+    for a in 0..48 {
+        for b in a + 1..49 {
+            for c in b + 1..50 {
+                for d in c + 1..51 {
+                    for e in d + 1..52 {
+                        f([a, b, c, d, e]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn choose<F: FnMut(&[usize])>(
+    cards: &[usize],
+    size: usize,
+    start: usize,
+    chosen: &mut Vec<usize>,
+    f: &mut F,
+) {
+    if chosen.len() == size {
+        f(chosen);
+        return;
+    }
+    for i in start..cards.len() {
+        chosen.push(cards[i]);
+        choose(cards, size, i + 1, chosen, f);
+        chosen.pop();
+    }
+}